@@ -41,9 +41,22 @@ unsafe impl Send for DatabaseConnection {}
 unsafe impl Sync for DatabaseConnection {}
 
 impl DatabaseConnection {
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "canyon.connect",
+            skip_all,
+            fields(
+                db_type = ?datasource.db_type,
+                host = datasource.host,
+                database = datasource.db_name
+            ),
+            err
+        )
+    )]
     pub async fn new(
         datasource: &DatasourceProperties<'_>,
-    ) -> Result<DatabaseConnection, Box<(dyn std::error::Error + Send + Sync + 'static)>> {
+    ) -> Result<DatabaseConnection, Box<dyn std::error::Error + Send + Sync + 'static>> {
         match datasource.db_type {
             DatabaseType::PostgreSql => {
                 let (new_client, new_connection) = tokio_postgres::connect(
@@ -61,6 +74,9 @@ impl DatabaseConnection {
 
                 tokio::spawn(async move {
                     if let Err(e) = new_connection.await {
+                        #[cfg(feature = "tracing")]
+                        tracing::error!(error = %e, "The connection with the PostgreSQL database failed");
+                        #[cfg(not(feature = "tracing"))]
                         eprintln!("An error occurred while trying to connect to the PostgreSQL database: {e}");
                     }
                 });
@@ -9,7 +9,7 @@ fn load_ds_config_from_array() {
         [canyon_sql]
         datasources = [
             {name = 'PostgresDS', properties.db_type = 'postgresql', properties.username = 'username', properties.password = 'random_pass', properties.host = 'localhost', properties.db_name = 'triforce', properties.migrations = 'enabled'},
-            {name = 'SqlServerDS', properties.db_type = 'sqlserver', properties.username = 'username2', properties.password = 'random_pass2', properties.host = '192.168.0.250.1', properties.port = 3340, properties.db_name = 'triforce2'}
+            {name = 'SqlServerDS', properties.db_type = 'sqlserver', properties.username = 'username2', properties.password = 'random_pass2', properties.host = '192.168.0.250.1', properties.port = 3340, properties.db_name = 'triforce2', properties.slow_query_threshold_ms = 250}
         ]
     "#;
 
@@ -27,6 +27,7 @@ fn load_ds_config_from_array() {
     assert_eq!(ds_0.properties.port, None);
     assert_eq!(ds_0.properties.db_name, "triforce");
     assert_eq!(ds_0.properties.migrations, Some(Migrations::Enabled));
+    assert_eq!(ds_0.properties.slow_query_threshold_ms, None);
 
     assert_eq!(ds_1.name, "SqlServerDS");
     assert_eq!(ds_1.properties.db_type, DatabaseType::SqlServer);
@@ -36,8 +37,9 @@ fn load_ds_config_from_array() {
     assert_eq!(ds_1.properties.port, Some(3340));
     assert_eq!(ds_1.properties.db_name, "triforce2");
     assert_eq!(ds_1.properties.migrations, None);
+    assert_eq!(ds_1.properties.slow_query_threshold_ms, Some(250));
 }
-///
+
 #[derive(Deserialize, Debug, Clone)]
 pub struct CanyonSqlConfig<'a> {
     #[serde(borrow)]
@@ -65,6 +67,9 @@ pub struct DatasourceProperties<'a> {
     pub port: Option<u16>,
     pub db_name: &'a str,
     pub migrations: Option<Migrations>,
+    /// The statements that takes longer than this number of milliseconds
+    /// are reported as slow queries
+    pub slow_query_threshold_ms: Option<u64>,
 }
 
 /// Represents the enabled or disabled migrations for a whole datasource
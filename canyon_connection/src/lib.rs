@@ -5,6 +5,8 @@ pub extern crate tiberius;
 pub extern crate tokio;
 pub extern crate tokio_postgres;
 pub extern crate tokio_util;
+#[cfg(feature = "tracing")]
+pub extern crate tracing;
 
 pub mod canyon_database_connector;
 pub mod datasources;
@@ -12,10 +14,10 @@ pub mod datasources;
 use std::fs;
 
 use crate::datasources::{CanyonSqlConfig, DatasourceConfig};
-use canyon_database_connector::DatabaseConnection;
+use canyon_database_connector::{DatabaseConnection, DatabaseType};
 use indexmap::IndexMap;
 use lazy_static::lazy_static;
-use tokio::sync::Mutex;
+use tokio::sync::{Mutex, MutexGuard};
 
 const CONFIG_FILE_IDENTIFIER: &str = "canyon.toml";
 
@@ -24,18 +26,98 @@ lazy_static! {
         tokio::runtime::Runtime::new()  // TODO Make the config with the builder
             .expect("Failed initializing the Canyon-SQL Tokio Runtime");
 
-    static ref RAW_CONFIG_FILE: String = fs::read_to_string(CONFIG_FILE_IDENTIFIER)
-        .expect("Error opening or reading the Canyon configuration file");
-    static ref CONFIG_FILE: CanyonSqlConfig<'static> = toml::from_str(RAW_CONFIG_FILE.as_str())
-        .expect("Error generating the configuration for Canyon-SQL");
+    static ref RAW_CONFIG_FILE: Result<String, String> = fs::read_to_string(CONFIG_FILE_IDENTIFIER)
+        .map_err(|error| format!("Error opening or reading the Canyon configuration file: {error}"));
+    static ref CONFIG_FILE: Result<CanyonSqlConfig<'static>, String> = RAW_CONFIG_FILE
+        .as_ref()
+        .map_err(Clone::clone)
+        .and_then(|raw_config| {
+            toml::from_str(raw_config.as_str())
+                .map_err(|error| format!("Error generating the configuration for Canyon-SQL: {error}"))
+        });
 
-    pub static ref DATASOURCES: Vec<DatasourceConfig<'static>> =
-        CONFIG_FILE.canyon_sql.datasources.clone();
+    pub static ref DATASOURCES: Vec<DatasourceConfig<'static>> = match CONFIG_FILE.as_ref() {
+        Ok(config) => config.canyon_sql.datasources.clone(),
+        Err(error) => panic!("{error}"),
+    };
 
     pub static ref CACHED_DATABASE_CONN: Mutex<IndexMap<&'static str, &'static mut DatabaseConnection>> =
         Mutex::new(IndexMap::new());
 }
 
+/// Returns the [`DatabaseType`] of the datasource with the provided name, as it's
+/// declared in the configuration file, or of the default one (the first) if
+/// the name is empty
+pub fn get_database_type_from_datasource_name(
+    datasource_name: &str,
+) -> Result<DatabaseType, Box<dyn std::error::Error + Send + Sync + 'static>> {
+    get_datasource(datasource_name).map(|datasource| datasource.properties.db_type)
+}
+
+/// Returns the configuration of the datasource with the provided name, as it's
+/// declared in the configuration file, or of the default one (the first) if
+/// the name is empty.
+///
+/// Fails if the configuration file can't be read or there's no such datasource
+pub fn get_datasource(
+    datasource_name: &str,
+) -> Result<&'static DatasourceConfig<'static>, Box<dyn std::error::Error + Send + Sync + 'static>>
+{
+    let datasources = &CONFIG_FILE
+        .as_ref()
+        .map_err(|error| error.as_str())?
+        .canyon_sql
+        .datasources;
+    let datasource = if datasource_name.is_empty() {
+        datasources.first().ok_or_else(|| {
+            String::from("No default datasource found. Check your `canyon.toml` file")
+        })
+    } else {
+        datasources
+            .iter()
+            .find(|ds| ds.name == datasource_name)
+            .ok_or_else(|| {
+                format!(
+                    "Canyon couldn't find a datasource with the name provided: {datasource_name}"
+                )
+            })
+    };
+
+    datasource.map_err(|message| std::io::Error::new(std::io::ErrorKind::NotFound, message).into())
+}
+
+/// Takes the cache of connections, waiting until no other statement is using it
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(name = "canyon.checkout", level = "debug")
+)]
+pub async fn checkout_connections(
+) -> MutexGuard<'static, IndexMap<&'static str, &'static mut DatabaseConnection>> {
+    CACHED_DATABASE_CONN.lock().await
+}
+
+/// Looks for the connection of the datasource with the provided name in the cache
+/// taken with [`checkout_connections`], or for the one of the default datasource
+/// (the first) if the name is empty, failing if there's no such datasource
+pub fn get_database_connection<'c>(
+    cache: &'c IndexMap<&'static str, &'static mut DatabaseConnection>,
+    datasource_name: &str,
+) -> Result<&'c &'static mut DatabaseConnection, Box<dyn std::error::Error + Send + Sync + 'static>>
+{
+    let database_conn = if datasource_name.is_empty() {
+        cache.values().next().ok_or_else(|| {
+            String::from("No default datasource found. Check your `canyon.toml` file")
+        })
+    } else {
+        cache.get(datasource_name).ok_or_else(|| {
+            format!("Canyon couldn't find a datasource in the pool with the argument provided: {datasource_name}")
+        })
+    };
+
+    database_conn
+        .map_err(|message| std::io::Error::new(std::io::ErrorKind::NotFound, message).into())
+}
+
 /// Convenient free function to initialize a kind of connection pool based on the datasources present defined
 /// in the configuration file.
 ///
@@ -46,6 +128,7 @@ lazy_static! {
 /// with a new connection per query without no problem, but the [`tiberius`] crate (MSSQL) sufferes a lot when it has continuous
 /// statements with multiple queries, like and insert followed by a find by id to check if the insert query has done its
 /// job done.
+#[cfg_attr(feature = "tracing", tracing::instrument(name = "canyon.pool"))]
 pub async fn init_connections_cache() {
     for datasource in DATASOURCES.iter() {
         CACHED_DATABASE_CONN.lock().await.insert(
@@ -3,6 +3,7 @@
 use crate::{
     crud::{CrudOperations, Transaction},
     mapper::RowMapper,
+    soft_delete::SoftDelete,
 };
 use canyon_connection::{
     tiberius::{self, ColumnData, IntoSql},
@@ -67,6 +68,38 @@ where
     fn value(self) -> (&'static str, &'a dyn QueryParameter<'a>);
 }
 
+/// Exposes the metadata of the table mapped by an entity, generated
+/// by the `CanyonCrud` derive macro.
+///
+/// Allows Canyon to build queries that involves more than one entity,
+/// like the typed joins
+pub trait TableMetadata {
+    /// The name of the table, with the schema if it's declared
+    const TABLE_NAME: &'static str;
+    /// The name of every column, in the order of the fields of the entity
+    const COLUMNS: &'static [&'static str];
+    /// The columns of the `#[primary_key]` fields, in their declaration order
+    const PRIMARY_KEY: &'static [&'static str];
+    /// The `(column, referenced table, referenced column)` of every
+    /// `#[foreign_key]` annotation of the entity
+    const FOREIGN_KEYS: &'static [(&'static str, &'static str, &'static str)];
+    /// The columns of the `#[created_at]` and `#[updated_at]` fields
+    const TIMESTAMPS: &'static [&'static str];
+    /// The column of the `#[soft_delete]` field, if any, and how it tells the deleted rows
+    const SOFT_DELETE: Option<(&'static str, SoftDelete)>;
+    /// The column of the `#[version]` field, if any
+    const VERSION: Option<&'static str>;
+
+    /// Retrieves the value of the field mapped to the `column` passed in
+    fn column_value(&self, column: &str) -> Option<&dyn QueryParameter<'_>>;
+
+    /// Retrieves a representation of the value of the field mapped to the
+    /// `column` passed in, that can be compared with the ones of other entities,
+    /// like the two sides of a foreign key. [`None`] if the column doesn't
+    /// exists or its value is `NULL`
+    fn column_key(&self, column: &str) -> Option<String>;
+}
+
 /// Bounds to some type T in order to make it callable over some fn parameter T
 ///
 /// Represents the ability of an struct to be considered as candidate to perform
@@ -79,6 +112,168 @@ pub trait ForeignKeyable<T> {
     fn get_fk_column(&self, column: &str) -> Option<&dyn QueryParameter<'_>>;
 }
 
+/// The values that looks for an entity by its primary key.
+///
+/// It's built from any reference to a [`QueryParameter`], for the entities with a
+/// single `#[primary_key]` field, and from tuples of [`QueryParameter`], for the
+/// entities that has a composite primary key.
+///
+/// The values are always retrieved in the same order that they were provided,
+/// so the elements of the tuple must follow the declaration order of the
+/// `#[primary_key]` fields on the entity
+/// ```ignore
+/// let league = League::find_by_pk(&1).await;
+/// let player_team = PlayerTeam::find_by_pk((1, 7)).await;
+/// ```
+pub struct PrimaryKeyValue<'a> {
+    values: Vec<Box<dyn for<'p> QueryParameter<'p> + 'a>>,
+}
+
+impl<'a> PrimaryKeyValue<'a> {
+    /// Retrieves the values that compose the primary key
+    pub fn values(&self) -> Vec<&dyn QueryParameter<'_>> {
+        self.values
+            .iter()
+            .map(|value| value.as_ref() as &dyn QueryParameter<'_>)
+            .collect()
+    }
+}
+
+/// A [`QueryParameter`] borrowed by a [`PrimaryKeyValue`]
+#[derive(Debug)]
+struct BorrowedParameter<'a>(&'a dyn QueryParameter<'a>);
+
+impl<'p, 'a> QueryParameter<'p> for BorrowedParameter<'a> {
+    fn as_postgres_param(&self) -> &(dyn ToSql + Sync) {
+        self.0.as_postgres_param()
+    }
+
+    fn as_sqlserver_param(&self) -> ColumnData<'_> {
+        self.0.as_sqlserver_param()
+    }
+
+    fn type_name(&self) -> &'static str {
+        self.0.type_name()
+    }
+}
+
+impl<'a> From<&'a dyn QueryParameter<'a>> for PrimaryKeyValue<'a> {
+    fn from(value: &'a dyn QueryParameter<'a>) -> Self {
+        Self {
+            values: vec![Box::new(BorrowedParameter(value))],
+        }
+    }
+}
+
+impl<'a, Q> From<&'a Q> for PrimaryKeyValue<'a>
+where
+    Q: for<'p> QueryParameter<'p>,
+{
+    fn from(value: &'a Q) -> Self {
+        Self {
+            values: vec![Box::new(BorrowedParameter(value))],
+        }
+    }
+}
+
+macro_rules! impl_primary_key_value_for_tuples {
+    ($(($($ty:ident => $idx:tt),+)),+) => {
+        $(
+            impl<'a, $($ty),+> From<($($ty,)+)> for PrimaryKeyValue<'a>
+            where
+                $($ty: for<'p> QueryParameter<'p> + 'a),+
+            {
+                fn from(value: ($($ty,)+)) -> Self {
+                    Self {
+                        values: vec![$(Box::new(value.$idx)),+],
+                    }
+                }
+            }
+        )+
+    };
+}
+
+impl_primary_key_value_for_tuples! {
+    (A => 0, B => 1),
+    (A => 0, B => 1, C => 2),
+    (A => 0, B => 1, C => 2, D => 3)
+}
+
+/// Bounds the types that can be retrieved as a single value from the first column
+/// of a row, like the result of an aggregate function, from any of the
+/// supported databases
+pub trait ScalarValue: Sized {
+    /// The SQL type, valid on every supported database, that the value must be
+    /// casted to in order to be retrieved as `Self` no matter the database
+    /// that returns it (p.e., `SUM` over an `INT` column returns a `BIGINT` on
+    /// `PostgreSQL`, but an `INT` on `SqlServer`)
+    const SQL_TYPE: Option<&'static str>;
+
+    /// Retrieves the value of the first column of the `row`, failing if it
+    /// can't be converted into `Self`
+    fn from_postgresql(
+        row: &tokio_postgres::Row,
+    ) -> Result<Option<Self>, Box<dyn std::error::Error + Sync + Send + 'static>>;
+
+    /// Retrieves the value of the first column of the `row`, failing if it
+    /// can't be converted into `Self`
+    fn from_sqlserver(
+        row: &tiberius::Row,
+    ) -> Result<Option<Self>, Box<dyn std::error::Error + Sync + Send + 'static>>;
+}
+
+macro_rules! impl_scalar_value {
+    ($($ty:ty => $sql_type:expr),+) => {
+        $(
+            impl ScalarValue for $ty {
+                const SQL_TYPE: Option<&'static str> = $sql_type;
+
+                fn from_postgresql(
+                    row: &tokio_postgres::Row,
+                ) -> Result<Option<Self>, Box<dyn std::error::Error + Sync + Send + 'static>> {
+                    Ok(row.try_get::<usize, Option<$ty>>(0)?)
+                }
+
+                fn from_sqlserver(
+                    row: &tiberius::Row,
+                ) -> Result<Option<Self>, Box<dyn std::error::Error + Sync + Send + 'static>> {
+                    Ok(row.try_get::<$ty, usize>(0)?)
+                }
+            }
+        )+
+    };
+}
+
+impl_scalar_value! {
+    i16 => Some("SMALLINT"),
+    i32 => Some("INT"),
+    i64 => Some("BIGINT"),
+    f32 => Some("REAL"),
+    f64 => Some("FLOAT"),
+    bool => None,
+    NaiveDate => None,
+    NaiveTime => None,
+    NaiveDateTime => None,
+    DateTime<FixedOffset> => None,
+    DateTime<Utc> => None
+}
+
+impl ScalarValue for String {
+    const SQL_TYPE: Option<&'static str> = None;
+
+    fn from_postgresql(
+        row: &tokio_postgres::Row,
+    ) -> Result<Option<Self>, Box<dyn std::error::Error + Sync + Send + 'static>> {
+        Ok(row.try_get::<usize, Option<String>>(0)?)
+    }
+
+    fn from_sqlserver(
+        row: &tiberius::Row,
+    ) -> Result<Option<Self>, Box<dyn std::error::Error + Sync + Send + 'static>> {
+        Ok(row.try_get::<&str, usize>(0)?.map(|value| value.to_owned()))
+    }
+}
+
 /// To define trait objects that helps to relates the necessary bounds in the 'IN` SQL clause
 pub trait InClauseValues: ToSql + ToString {}
 
@@ -110,7 +305,7 @@ impl<'a> Column<'a> {
     pub fn column_type(&self) -> &ColumnType {
         &self.type_
     }
-    pub fn type_(&'a self) -> &'_ dyn Type {
+    pub fn type_(&'a self) -> &'a dyn Type {
         match &self.type_ {
             ColumnType::Postgres(v) => v as &'a dyn Type,
             ColumnType::SqlServer(v) => v as &'a dyn Type,
@@ -149,7 +344,7 @@ pub trait RowOperations {
     where
         Output: tokio_postgres::types::FromSql<'a> + tiberius::FromSql<'a>;
 
-    fn columns(&self) -> Vec<Column>;
+    fn columns(&self) -> Vec<Column<'_>>;
 }
 
 impl RowOperations for &dyn Row {
@@ -168,7 +363,7 @@ impl RowOperations for &dyn Row {
         panic!()
     }
 
-    fn columns(&self) -> Vec<Column> {
+    fn columns(&self) -> Vec<Column<'_>> {
         let mut cols = vec![];
 
         if self.as_any().is::<tokio_postgres::Row>() {
@@ -221,6 +416,31 @@ impl RowOperations for &dyn Row {
 pub trait QueryParameter<'a>: std::fmt::Debug + Sync + Send {
     fn as_postgres_param(&self) -> &(dyn ToSql + Sync);
     fn as_sqlserver_param(&self) -> ColumnData<'_>;
+
+    /// The name of the Rust type of the value
+    fn type_name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
+
+    /// Writes the value as an SQL literal, so it can be inlined in the
+    /// statements when they're inspected or logged
+    fn as_sql_literal(&self) -> String {
+        match self.as_sqlserver_param() {
+            ColumnData::I16(Some(value)) => value.to_string(),
+            ColumnData::I32(Some(value)) => value.to_string(),
+            ColumnData::I64(Some(value)) => value.to_string(),
+            ColumnData::F32(Some(value)) => value.to_string(),
+            ColumnData::F64(Some(value)) => value.to_string(),
+            ColumnData::String(Some(value)) => format!("'{}'", value.replace('\'', "''")),
+            ColumnData::I16(None)
+            | ColumnData::I32(None)
+            | ColumnData::I64(None)
+            | ColumnData::F32(None)
+            | ColumnData::F64(None)
+            | ColumnData::String(None) => String::from("NULL"),
+            _ => format!("{self:?}"),
+        }
+    }
 }
 
 /// The implementation of the [`canyon_connection::tiberius`] [`IntoSql`] for the
@@ -231,7 +451,7 @@ pub trait QueryParameter<'a>: std::fmt::Debug + Sync + Send {
 /// a collection of [`QueryParameter<'a>`], in order to allow a workflow
 /// that is not dependent of the specific type of the argument that holds
 /// the query parameters of the database connectors
-impl<'a> IntoSql<'a> for &'a dyn QueryParameter<'a> {
+impl<'a, 'b> IntoSql<'a> for &'a dyn QueryParameter<'b> {
     fn into_sql(self) -> ColumnData<'a> {
         self.as_sqlserver_param()
     }
@@ -492,6 +712,10 @@ impl<'a> QueryParameter<'_> for NaiveDate {
     fn as_sqlserver_param(&self) -> ColumnData<'_> {
         self.into_sql()
     }
+
+    fn as_sql_literal(&self) -> String {
+        format!("'{self}'")
+    }
 }
 impl<'a> QueryParameter<'a> for Option<NaiveDate> {
     fn as_postgres_param(&self) -> &(dyn ToSql + Sync) {
@@ -501,6 +725,11 @@ impl<'a> QueryParameter<'a> for Option<NaiveDate> {
     fn as_sqlserver_param(&self) -> ColumnData<'_> {
         self.into_sql()
     }
+
+    fn as_sql_literal(&self) -> String {
+        self.as_ref()
+            .map_or_else(|| String::from("NULL"), QueryParameter::as_sql_literal)
+    }
 }
 impl<'a> QueryParameter<'_> for NaiveTime {
     fn as_postgres_param(&self) -> &(dyn ToSql + Sync) {
@@ -510,6 +739,10 @@ impl<'a> QueryParameter<'_> for NaiveTime {
     fn as_sqlserver_param(&self) -> ColumnData<'_> {
         self.into_sql()
     }
+
+    fn as_sql_literal(&self) -> String {
+        format!("'{self}'")
+    }
 }
 impl<'a> QueryParameter<'a> for Option<NaiveTime> {
     fn as_postgres_param(&self) -> &(dyn ToSql + Sync) {
@@ -519,6 +752,11 @@ impl<'a> QueryParameter<'a> for Option<NaiveTime> {
     fn as_sqlserver_param(&self) -> ColumnData<'_> {
         self.into_sql()
     }
+
+    fn as_sql_literal(&self) -> String {
+        self.as_ref()
+            .map_or_else(|| String::from("NULL"), QueryParameter::as_sql_literal)
+    }
 }
 impl<'a> QueryParameter<'_> for NaiveDateTime {
     fn as_postgres_param(&self) -> &(dyn ToSql + Sync) {
@@ -528,6 +766,10 @@ impl<'a> QueryParameter<'_> for NaiveDateTime {
     fn as_sqlserver_param(&self) -> ColumnData<'_> {
         self.into_sql()
     }
+
+    fn as_sql_literal(&self) -> String {
+        format!("'{self}'")
+    }
 }
 impl<'a> QueryParameter<'a> for Option<NaiveDateTime> {
     fn as_postgres_param(&self) -> &(dyn ToSql + Sync) {
@@ -537,6 +779,11 @@ impl<'a> QueryParameter<'a> for Option<NaiveDateTime> {
     fn as_sqlserver_param(&self) -> ColumnData<'_> {
         self.into_sql()
     }
+
+    fn as_sql_literal(&self) -> String {
+        self.as_ref()
+            .map_or_else(|| String::from("NULL"), QueryParameter::as_sql_literal)
+    }
 }
 impl<'a> QueryParameter<'_> for DateTime<FixedOffset> {
     fn as_postgres_param(&self) -> &(dyn ToSql + Sync) {
@@ -546,6 +793,10 @@ impl<'a> QueryParameter<'_> for DateTime<FixedOffset> {
     fn as_sqlserver_param(&self) -> ColumnData<'_> {
         self.into_sql()
     }
+
+    fn as_sql_literal(&self) -> String {
+        format!("'{}'", self.to_rfc3339())
+    }
 }
 impl<'a> QueryParameter<'a> for Option<DateTime<FixedOffset>> {
     fn as_postgres_param(&self) -> &(dyn ToSql + Sync) {
@@ -555,6 +806,11 @@ impl<'a> QueryParameter<'a> for Option<DateTime<FixedOffset>> {
     fn as_sqlserver_param(&self) -> ColumnData<'_> {
         self.into_sql()
     }
+
+    fn as_sql_literal(&self) -> String {
+        self.as_ref()
+            .map_or_else(|| String::from("NULL"), QueryParameter::as_sql_literal)
+    }
 }
 impl<'a> QueryParameter<'_> for DateTime<Utc> {
     fn as_postgres_param(&self) -> &(dyn ToSql + Sync) {
@@ -564,6 +820,10 @@ impl<'a> QueryParameter<'_> for DateTime<Utc> {
     fn as_sqlserver_param(&self) -> ColumnData<'_> {
         self.into_sql()
     }
+
+    fn as_sql_literal(&self) -> String {
+        format!("'{}'", self.to_rfc3339())
+    }
 }
 impl<'a> QueryParameter<'_> for Option<DateTime<Utc>> {
     fn as_postgres_param(&self) -> &(dyn ToSql + Sync) {
@@ -573,4 +833,9 @@ impl<'a> QueryParameter<'_> for Option<DateTime<Utc>> {
     fn as_sqlserver_param(&self) -> ColumnData<'_> {
         self.into_sql()
     }
+
+    fn as_sql_literal(&self) -> String {
+        self.as_ref()
+            .map_or_else(|| String::from("NULL"), QueryParameter::as_sql_literal)
+    }
 }
@@ -1,11 +1,14 @@
 use std::fmt::Display;
+use std::time::{Duration, Instant};
 
 use async_trait::async_trait;
 use canyon_connection::canyon_database_connector::DatabaseType;
-use canyon_connection::CACHED_DATABASE_CONN;
+use canyon_connection::{checkout_connections, get_database_connection};
 
-use crate::bounds::QueryParameter;
+use crate::bounds::{PrimaryKeyValue, QueryParameter};
+use crate::interceptor::{self, entity_name, InterceptedStatement, StatementOutcome};
 use crate::mapper::RowMapper;
+use crate::query_elements::parameters::NamedStatement;
 use crate::query_elements::query_builder::{
     DeleteQueryBuilder, SelectQueryBuilder, UpdateQueryBuilder,
 };
@@ -23,48 +26,221 @@ pub trait Transaction<T> {
     /// Performs a query against the targeted database by the selected datasource.
     ///
     /// No datasource means take the entry zero
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "canyon.query",
+            skip_all,
+            fields(
+                datasource = datasource_name,
+                db_type = tracing::field::Empty,
+                statement = tracing::field::Empty,
+                rows = tracing::field::Empty,
+                elapsed_ms = tracing::field::Empty,
+            ),
+            err
+        )
+    )]
     async fn query<'a, S, Z>(
         stmt: S,
         params: Z,
         datasource_name: &'a str,
-    ) -> Result<DatabaseResult<T>, Box<(dyn std::error::Error + Sync + Send + 'static)>>
+    ) -> Result<DatabaseResult<T>, Box<dyn std::error::Error + Sync + Send + 'static>>
     where
         S: AsRef<str> + Display + Sync + Send + 'a,
         Z: AsRef<[&'a dyn QueryParameter<'a>]> + Sync + Send + 'a,
     {
-        let guarded_cache = CACHED_DATABASE_CONN.lock().await;
-
-        let database_conn = if datasource_name.is_empty() {
-            guarded_cache
-                .values()
-                .next()
-                .expect("No default datasource found. Check your `canyon.toml` file")
-        } else {
-            guarded_cache.get(datasource_name)
-                .unwrap_or_else(||
-                    panic!("Canyon couldn't find a datasource in the pool with the argument provided: {datasource_name}"
-                ))
+        let statement = intercept(
+            stmt.to_string(),
+            params.as_ref(),
+            datasource_name,
+            entity_name::<T>(),
+        )
+        .await?;
+        let params = statement.params();
+
+        let started = Instant::now();
+        let result = {
+            let guarded_cache = checkout_connections().await;
+            let database_conn = get_database_connection(&guarded_cache, datasource_name)?;
+
+            match database_conn.database_type {
+                DatabaseType::PostgreSql => {
+                    postgres_query_launcher::launch::<T>(
+                        database_conn,
+                        statement.sql.clone(),
+                        &params,
+                    )
+                    .await
+                }
+                DatabaseType::SqlServer => {
+                    sqlserver_query_launcher::launch::<T>(database_conn, &statement.sql, &params)
+                        .await
+                }
+            }
         };
 
-        match database_conn.database_type {
-            DatabaseType::PostgreSql => {
-                postgres_query_launcher::launch::<T>(
-                    database_conn,
-                    stmt.to_string(),
-                    params.as_ref(),
-                )
-                .await
-            }
-            DatabaseType::SqlServer => {
-                sqlserver_query_launcher::launch::<T, Z>(
-                    database_conn,
-                    &mut stmt.to_string(),
-                    params,
-                )
-                .await
+        conclude(
+            &statement,
+            &params,
+            match &result {
+                Ok(rows) => StatementOutcome::Rows(rows.number_of_results()),
+                Err(error) => StatementOutcome::Error(&**error),
+            },
+            started,
+        );
+
+        result
+    }
+
+    /// Executes a statement that does not retrieve rows against the targeted
+    /// database by the selected datasource, returning the number of affected rows.
+    ///
+    /// No datasource means take the entry zero
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "canyon.query",
+            skip_all,
+            fields(
+                datasource = datasource_name,
+                db_type = tracing::field::Empty,
+                statement = tracing::field::Empty,
+                rows = tracing::field::Empty,
+                elapsed_ms = tracing::field::Empty,
+            ),
+            err
+        )
+    )]
+    async fn execute<'a, S, Z>(
+        stmt: S,
+        params: Z,
+        datasource_name: &'a str,
+    ) -> Result<u64, Box<dyn std::error::Error + Sync + Send + 'static>>
+    where
+        S: AsRef<str> + Display + Sync + Send + 'a,
+        Z: AsRef<[&'a dyn QueryParameter<'a>]> + Sync + Send + 'a,
+    {
+        let statement = intercept(
+            stmt.to_string(),
+            params.as_ref(),
+            datasource_name,
+            entity_name::<T>(),
+        )
+        .await?;
+        let params = statement.params();
+
+        let started = Instant::now();
+        let result = {
+            let guarded_cache = checkout_connections().await;
+            let database_conn = get_database_connection(&guarded_cache, datasource_name)?;
+
+            match database_conn.database_type {
+                DatabaseType::PostgreSql => {
+                    postgres_query_launcher::execute(database_conn, statement.sql.clone(), &params)
+                        .await
+                }
+                DatabaseType::SqlServer => {
+                    sqlserver_query_launcher::execute(database_conn, statement.sql.clone(), &params)
+                        .await
+                }
             }
-        }
+        };
+
+        conclude(
+            &statement,
+            &params,
+            match &result {
+                Ok(rows) => StatementOutcome::AffectedRows(*rows),
+                Err(error) => StatementOutcome::Error(&**error),
+            },
+            started,
+        );
+
+        result
+    }
+
+    /// Performs a query written with named parameters (`:name` or `@name`)
+    /// against the targeted database by the selected datasource.
+    ///
+    /// Every value in `params` it's bound to the parameter with the same name,
+    /// no matters the position or how many times it's used in the statement
+    async fn query_named<'a, S>(
+        stmt: S,
+        params: &'a [(&'a str, &'a dyn QueryParameter<'a>)],
+        datasource_name: &'a str,
+    ) -> Result<DatabaseResult<T>, Box<dyn std::error::Error + Sync + Send + 'static>>
+    where
+        S: AsRef<str> + Display + Sync + Send + 'a,
+    {
+        let named_stmt = NamedStatement::new(stmt.as_ref());
+        let values = named_stmt.bind(params)?;
+
+        Self::query(named_stmt.sql().to_string(), values, datasource_name).await
+    }
+}
+
+/// Passes the `stmt` through the registered interceptors before it's launched, and
+/// records the resulting SQL on the span of the statement.
+///
+/// The connections cache is only held to look for the type of the database of the
+/// datasource, and released before calling the interceptors
+pub(crate) async fn intercept<'a>(
+    stmt: String,
+    params: &[&'a dyn QueryParameter<'a>],
+    datasource_name: &'a str,
+    entity: &'static str,
+) -> Result<InterceptedStatement<'a>, Box<dyn std::error::Error + Sync + Send + 'static>> {
+    let database_type =
+        get_database_connection(&*checkout_connections().await, datasource_name)?.database_type;
+
+    let mut statement =
+        InterceptedStatement::new(datasource_name, database_type, entity, stmt, params);
+    if let Err(error) = interceptor::before(&mut statement) {
+        interceptor::after(&statement, StatementOutcome::Error(&*error), Duration::ZERO);
+        return Err(error);
     }
+
+    #[cfg(feature = "tracing")]
+    tracing::Span::current().record(
+        "statement",
+        crate::query_elements::parameters::sanitize_statement(&statement.sql),
+    );
+
+    Ok(statement)
+}
+
+/// Notifies the `outcome` of the launched `statement` to the interceptors, reports
+/// it if it was slow and records it on its span. Called without holding the
+/// connections cache
+pub(crate) fn conclude(
+    statement: &InterceptedStatement<'_>,
+    params: &[&dyn QueryParameter<'_>],
+    outcome: StatementOutcome<'_>,
+    started: Instant,
+) {
+    let elapsed = started.elapsed();
+
+    #[cfg(feature = "tracing")]
+    crate::instrumentation::record_statement(
+        statement.database_type,
+        match outcome {
+            StatementOutcome::Rows(rows) => Some(rows as u64),
+            StatementOutcome::AffectedRows(rows) => Some(rows),
+            StatementOutcome::Plan | StatementOutcome::Error(_) => None,
+        },
+        started,
+    );
+
+    interceptor::after(statement, outcome, elapsed);
+    crate::slow_query::report_if_slow(
+        statement.datasource_name,
+        statement.database_type,
+        statement.entity,
+        &statement.sql,
+        params,
+        elapsed,
+    );
 }
 
 /// *CrudOperations* it's the core part of Canyon-SQL.
@@ -87,11 +263,18 @@ pub trait CrudOperations<T>: Transaction<T>
 where
     T: CrudOperations<T> + RowMapper<T>,
 {
-    async fn find_all<'a>() -> Result<Vec<T>, Box<(dyn std::error::Error + Send + Sync + 'static)>>;
+    /// The type of the primary key of the entity. A tuple with the types of
+    /// every `#[primary_key]` field for composite keys, or `()` if the
+    /// entity does not have a primary key
+    type PrimaryKey;
+
+    fn primary_key(&self) -> Self::PrimaryKey;
+
+    async fn find_all<'a>() -> Result<Vec<T>, Box<dyn std::error::Error + Send + Sync + 'static>>;
 
     async fn find_all_datasource<'a>(
         datasource_name: &'a str,
-    ) -> Result<Vec<T>, Box<(dyn std::error::Error + Send + Sync + 'static)>>;
+    ) -> Result<Vec<T>, Box<dyn std::error::Error + Send + Sync + 'static>>;
 
     async fn find_all_unchecked<'a>() -> Vec<T>;
 
@@ -101,20 +284,39 @@ where
 
     fn select_query_datasource(datasource_name: &str) -> SelectQueryBuilder<'_, T>;
 
-    async fn count() -> Result<i64, Box<(dyn std::error::Error + Send + Sync + 'static)>>;
+    /// The SQL of the `find_all` operation for the `database_type`, along
+    /// with its parameters, generated without querying the database
+    fn find_all_sql<'a>(database_type: DatabaseType) -> (String, Vec<&'a dyn QueryParameter<'a>>);
+
+    async fn count() -> Result<i64, Box<dyn std::error::Error + Send + Sync + 'static>>;
 
     async fn count_datasource<'a>(
         datasource_name: &'a str,
-    ) -> Result<i64, Box<(dyn std::error::Error + Send + Sync + 'static)>>;
+    ) -> Result<i64, Box<dyn std::error::Error + Send + Sync + 'static>>;
 
+    /// The SQL of the `count` operation for the `database_type`, along
+    /// with its parameters, generated without querying the database
+    fn count_sql<'a>(database_type: DatabaseType) -> (String, Vec<&'a dyn QueryParameter<'a>>);
+
+    /// Finds an entity by the value of its primary key, or by the tuple with
+    /// the values of every `#[primary_key]` field for the composite keys, in
+    /// their declaration order
     async fn find_by_pk<'a>(
-        value: &'a dyn QueryParameter<'a>,
-    ) -> Result<Option<T>, Box<(dyn std::error::Error + Send + Sync + 'static)>>;
+        value: impl Into<PrimaryKeyValue<'a>> + Send + 'a,
+    ) -> Result<Option<T>, Box<dyn std::error::Error + Send + Sync + 'static>>;
 
     async fn find_by_pk_datasource<'a>(
-        value: &'a dyn QueryParameter<'a>,
+        value: impl Into<PrimaryKeyValue<'a>> + Send + 'a,
         datasource_name: &'a str,
-    ) -> Result<Option<T>, Box<(dyn std::error::Error + Send + Sync + 'static)>>;
+    ) -> Result<Option<T>, Box<dyn std::error::Error + Send + Sync + 'static>>;
+
+    /// The SQL of the `find_by_pk` operation for the `database_type`, along
+    /// with the [`PrimaryKeyValue`] that holds its parameters, generated without
+    /// querying the database
+    fn find_by_pk_sql<'a>(
+        value: impl Into<PrimaryKeyValue<'a>>,
+        database_type: DatabaseType,
+    ) -> Result<(String, PrimaryKeyValue<'a>), Box<dyn std::error::Error + Send + Sync + 'static>>;
 
     async fn insert<'a>(
         &mut self,
@@ -125,26 +327,81 @@ where
         datasource_name: &'a str,
     ) -> Result<(), Box<dyn std::error::Error + Sync + std::marker::Send>>;
 
+    /// The SQL of the `insert` operation for the `database_type`, along
+    /// with its parameters, generated without querying the database
+    fn insert_sql(&self, database_type: DatabaseType) -> (String, Vec<&dyn QueryParameter<'_>>);
+
+    async fn insert_returning_all<'a>(
+        &mut self,
+    ) -> Result<(), Box<dyn std::error::Error + Sync + std::marker::Send>>;
+
+    async fn insert_returning_all_datasource<'a>(
+        &mut self,
+        datasource_name: &'a str,
+    ) -> Result<(), Box<dyn std::error::Error + Sync + std::marker::Send>>;
+
     async fn multi_insert<'a>(
         instances: &'a mut [&'a mut T],
-    ) -> Result<(), Box<(dyn std::error::Error + Send + Sync + 'static)>>;
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>>;
 
     async fn multi_insert_datasource<'a>(
         instances: &'a mut [&'a mut T],
         datasource_name: &'a str,
-    ) -> Result<(), Box<(dyn std::error::Error + Send + Sync + 'static)>>;
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>>;
 
-    async fn update(&self) -> Result<(), Box<dyn std::error::Error + Sync + std::marker::Send>>;
+    /// Updates the row of the entity. The `#[version]` of the entity moves
+    /// forward along with the one of the row
+    async fn update(&mut self)
+        -> Result<(), Box<dyn std::error::Error + Sync + std::marker::Send>>;
 
     async fn update_datasource<'a>(
-        &self,
+        &mut self,
         datasource_name: &'a str,
     ) -> Result<(), Box<dyn std::error::Error + Sync + std::marker::Send>>;
 
+    /// The SQL of the `update` operation for the `database_type`, along
+    /// with its parameters, generated without querying the database
+    #[allow(clippy::type_complexity)]
+    fn update_sql(
+        &self,
+        database_type: DatabaseType,
+    ) -> Result<
+        (String, Vec<&dyn QueryParameter<'_>>),
+        Box<dyn std::error::Error + Sync + std::marker::Send>,
+    >;
+
     fn update_query<'a>() -> UpdateQueryBuilder<'a, T>;
 
     fn update_query_datasource(datasource_name: &str) -> UpdateQueryBuilder<'_, T>;
 
+    /// Updates only the columns whose values changed since the `snapshot`, a copy
+    /// of the entity taken when it was read, without querying the database if
+    /// there's none. The `#[version]` of the entity moves forward from the one
+    /// of the `snapshot`, that guards the write
+    async fn update_changes(
+        &mut self,
+        snapshot: &T,
+    ) -> Result<(), Box<dyn std::error::Error + Sync + std::marker::Send>>;
+
+    async fn update_changes_datasource<'a>(
+        &mut self,
+        snapshot: &T,
+        datasource_name: &'a str,
+    ) -> Result<(), Box<dyn std::error::Error + Sync + std::marker::Send>>;
+
+    /// Generates an [`UpdateQueryBuilder`] that assigns the columns whose values
+    /// changed between `old` and `new` to the ones of `new`, filtered by its primary key
+    fn diff<'a>(
+        old: &'a T,
+        new: &'a T,
+    ) -> Result<UpdateQueryBuilder<'a, T>, Box<dyn std::error::Error + Sync + std::marker::Send>>;
+
+    fn diff_datasource<'a>(
+        old: &'a T,
+        new: &'a T,
+        datasource_name: &'a str,
+    ) -> Result<UpdateQueryBuilder<'a, T>, Box<dyn std::error::Error + Sync + std::marker::Send>>;
+
     async fn delete(&self) -> Result<(), Box<dyn std::error::Error + Sync + std::marker::Send>>;
 
     async fn delete_datasource<'a>(
@@ -152,22 +409,57 @@ where
         datasource_name: &'a str,
     ) -> Result<(), Box<dyn std::error::Error + Sync + std::marker::Send>>;
 
+    /// The SQL of the `delete` operation for the `database_type`, along
+    /// with its parameters, generated without querying the database
+    #[allow(clippy::type_complexity)]
+    fn delete_sql(
+        &self,
+        database_type: DatabaseType,
+    ) -> Result<
+        (String, Vec<&dyn QueryParameter<'_>>),
+        Box<dyn std::error::Error + Sync + std::marker::Send>,
+    >;
+
+    /// Removes the row of the entity from the table, even if it has a
+    /// `#[soft_delete]` field. Same as `delete` for the rest of the entities
+    async fn force_delete(
+        &self,
+    ) -> Result<(), Box<dyn std::error::Error + Sync + std::marker::Send>>;
+
+    async fn force_delete_datasource<'a>(
+        &self,
+        datasource_name: &'a str,
+    ) -> Result<(), Box<dyn std::error::Error + Sync + std::marker::Send>>;
+
+    /// Marks back as alive the soft deleted row of the entity. Only available
+    /// for the entities with a `#[soft_delete]` field
+    async fn restore(&self) -> Result<(), Box<dyn std::error::Error + Sync + std::marker::Send>>;
+
+    async fn restore_datasource<'a>(
+        &self,
+        datasource_name: &'a str,
+    ) -> Result<(), Box<dyn std::error::Error + Sync + std::marker::Send>>;
+
     fn delete_query<'a>() -> DeleteQueryBuilder<'a, T>;
 
     fn delete_query_datasource(datasource_name: &str) -> DeleteQueryBuilder<'_, T>;
 }
 
-mod postgres_query_launcher {
+pub(crate) mod postgres_query_launcher {
     use crate::bounds::QueryParameter;
     use crate::result::DatabaseResult;
     use canyon_connection::canyon_database_connector::DatabaseConnection;
 
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "canyon.postgres.launch", level = "debug", skip_all)
+    )]
     pub async fn launch<'a, T>(
         db_conn: &DatabaseConnection,
         // datasource_name: &str,
         stmt: String,
         params: &'a [&'_ dyn QueryParameter<'_>],
-    ) -> Result<DatabaseResult<T>, Box<(dyn std::error::Error + Send + Sync + 'static)>> {
+    ) -> Result<DatabaseResult<T>, Box<dyn std::error::Error + Send + Sync + 'static>> {
         let mut m_params = Vec::new();
         for param in params {
             m_params.push(param.as_postgres_param());
@@ -189,50 +481,58 @@ mod postgres_query_launcher {
             ))
         }
     }
+
+    /// Executes a statement that does not retrieve rows, returning
+    /// the number of affected rows
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "canyon.postgres.execute", level = "debug", skip_all)
+    )]
+    pub async fn execute<'a>(
+        db_conn: &DatabaseConnection,
+        stmt: String,
+        params: &'a [&'_ dyn QueryParameter<'_>],
+    ) -> Result<u64, Box<dyn std::error::Error + Send + Sync + 'static>> {
+        let mut m_params = Vec::new();
+        for param in params {
+            m_params.push(param.as_postgres_param());
+        }
+
+        Ok(db_conn
+            .postgres_connection
+            .as_ref()
+            .unwrap()
+            .client
+            .execute(&stmt, m_params.as_slice())
+            .await?)
+    }
 }
 
-mod sqlserver_query_launcher {
+pub(crate) mod sqlserver_query_launcher {
     use std::mem::transmute;
 
-    use canyon_connection::tiberius::Row;
+    use canyon_connection::tiberius::{xml::XmlData, Row};
 
     use crate::{
         bounds::QueryParameter,
         canyon_connection::{canyon_database_connector::DatabaseConnection, tiberius::Query},
+        query_elements::parameters::to_sqlserver_placeholders,
         result::DatabaseResult,
     };
 
-    pub async fn launch<'a, T, Z>(
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "canyon.sqlserver.launch", level = "debug", skip_all)
+    )]
+    pub async fn launch<T>(
         db_conn: &&mut DatabaseConnection,
-        stmt: &mut String,
-        params: Z,
-    ) -> Result<DatabaseResult<T>, Box<(dyn std::error::Error + Send + Sync + 'static)>>
-    where
-        Z: AsRef<[&'a dyn QueryParameter<'a>]> + Sync + Send + 'a,
-    {
-        // Re-generate de insert statement to adequate it to the SQL SERVER syntax to retrieve the PK value(s) after insert
-        if stmt.contains("RETURNING") {
-            let c = stmt.clone();
-            let temp = c
-                .split_once("RETURNING")
-                .expect("An error happened generating an INSERT statement for a SQL SERVER client");
-            let temp2 = temp.0.split_once("VALUES").expect(
-                "An error happened generating an INSERT statement for a SQL SERVER client [1]",
-            );
-
-            *stmt = format!(
-                "{} OUTPUT inserted.{} VALUES {}",
-                temp2.0.trim(),
-                temp.1.trim(),
-                temp2.1.trim()
-            );
-        }
-
-        let mut mssql_query = Query::new(stmt.to_owned().replace('$', "@P"));
-        params
-            .as_ref()
-            .iter()
-            .for_each(|param| mssql_query.bind(*param));
+        stmt: &str,
+        params: &[&'_ dyn QueryParameter<'_>],
+    ) -> Result<DatabaseResult<T>, Box<dyn std::error::Error + Send + Sync + 'static>> {
+        // The statements generated by Canyon are already rendered with the SQL Server
+        // placeholders, but the raw ones written by the user are not
+        let mut mssql_query = Query::new(to_sqlserver_placeholders(stmt));
+        params.iter().for_each(|param| mssql_query.bind(*param));
 
         #[allow(mutable_transmutes)]
         let _results: Vec<Row> = mssql_query
@@ -252,4 +552,105 @@ mod sqlserver_query_launcher {
 
         Ok(DatabaseResult::new_sqlserver(_results))
     }
+
+    /// Executes a statement that does not retrieve rows, returning
+    /// the number of affected rows
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "canyon.sqlserver.execute", level = "debug", skip_all)
+    )]
+    pub async fn execute(
+        db_conn: &&mut DatabaseConnection,
+        stmt: String,
+        params: &[&'_ dyn QueryParameter<'_>],
+    ) -> Result<u64, Box<dyn std::error::Error + Send + Sync + 'static>> {
+        let mut mssql_query = Query::new(to_sqlserver_placeholders(&stmt));
+        params.iter().for_each(|param| mssql_query.bind(*param));
+
+        #[allow(mutable_transmutes)]
+        let result = mssql_query
+            .execute(
+                unsafe { transmute::<&DatabaseConnection, &mut DatabaseConnection>(db_conn) }
+                    .sqlserver_connection
+                    .as_mut()
+                    .expect("Error querying the MSSQL database")
+                    .client,
+            )
+            .await?;
+
+        Ok(result.total())
+    }
+
+    /// Retrieves the XML plan of the statement, switching the session to return it
+    /// with `SHOWPLAN_XML`, that compiles the statement without executing it, or with
+    /// `STATISTICS XML` if it's `analyze`d, that executes it and reports the actual plan
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "canyon.sqlserver.explain", level = "debug", skip_all)
+    )]
+    pub async fn explain(
+        db_conn: &&mut DatabaseConnection,
+        stmt: &str,
+        params: &[&'_ dyn QueryParameter<'_>],
+        analyze: bool,
+    ) -> Result<String, Box<dyn std::error::Error + Send + Sync + 'static>> {
+        let option = if analyze {
+            "STATISTICS XML"
+        } else {
+            "SHOWPLAN_XML"
+        };
+
+        let mut mssql_query = Query::new(to_sqlserver_placeholders(stmt));
+        params.iter().for_each(|param| mssql_query.bind(*param));
+
+        #[allow(mutable_transmutes)]
+        let client =
+            &mut unsafe { transmute::<&DatabaseConnection, &mut DatabaseConnection>(db_conn) }
+                .sqlserver_connection
+                .as_mut()
+                .expect("Error querying the MSSQL database")
+                .client;
+
+        // The option must be the only statement of its batch
+        client
+            .simple_query(format!("SET {option} ON"))
+            .await?
+            .into_results()
+            .await?;
+        let results = match mssql_query.query(client).await {
+            Ok(stream) => stream.into_results().await,
+            Err(error) => Err(error),
+        };
+        // The session is restored even if the statement failed
+        client
+            .simple_query(format!("SET {option} OFF"))
+            .await?
+            .into_results()
+            .await?;
+
+        // The plan comes in its own result set, after the rows of the executed statement
+        results?
+            .into_iter()
+            .flatten()
+            .find_map(|row| {
+                row.try_get::<&str, usize>(0)
+                    .ok()
+                    .flatten()
+                    .map(str::to_string)
+                    .or_else(|| {
+                        row.try_get::<&XmlData, usize>(0)
+                            .ok()
+                            .flatten()
+                            .map(XmlData::to_string)
+                    })
+                    .filter(|plan| plan.contains("<ShowPlanXML"))
+            })
+            .ok_or_else(|| {
+                std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    "SQL Server didn't return the plan of the statement",
+                )
+                .into()
+            })
+    }
 }
@@ -0,0 +1,430 @@
+//! The execution plans of the queries.
+//!
+//! `PostgreSQL` describes the plan of a statement with `EXPLAIN (FORMAT JSON)` and
+//! `SqlServer` with the XML documents of `SET SHOWPLAN_XML` and `SET STATISTICS XML`.
+//! Both are read into a [`QueryPlan`], a tree of [`PlanNode`] with the same shape
+//! for every database, that also keeps the raw plan, as returned by the database.
+
+use std::time::Instant;
+
+use canyon_connection::{
+    canyon_database_connector::DatabaseType,
+    checkout_connections, get_database_connection,
+    tokio_postgres::types::{FromSql, Type},
+};
+
+use crate::{
+    bounds::QueryParameter,
+    crud::{conclude, intercept, postgres_query_launcher, sqlserver_query_launcher},
+    interceptor::StatementOutcome,
+};
+
+/// The execution plan of a statement
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryPlan {
+    /// The type of the database that planned the statement
+    pub database_type: DatabaseType,
+    /// The root operation of the plan
+    pub root: PlanNode,
+    /// The plan as returned by the database, a JSON document on `PostgreSQL`
+    /// and an XML one on `SqlServer`
+    pub raw: String,
+}
+
+/// An operation of an execution plan
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlanNode {
+    /// The kind of operation, like `Seq Scan` or `Clustered Index Seek`
+    pub node_type: String,
+    /// The estimated cost of the operation, including the cost of its children
+    pub cost: f64,
+    /// The estimated number of rows returned by the operation
+    pub rows: f64,
+    /// The number of rows really returned by the operation. Only available
+    /// when the statement it's executed to obtain the plan
+    pub actual_rows: Option<f64>,
+    /// The operations that provides the input rows of this one
+    pub children: Vec<PlanNode>,
+}
+
+/// Obtains the plan of the `stmt` on the database pointed by the `datasource_name`
+/// (the default one if it's empty). The statement it's only executed if `analyze`.
+///
+/// Like any other statement, it's passed through the interceptors before it's
+/// explained, and reported to them and to the slow queries sink afterwards
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(
+        name = "canyon.explain",
+        skip_all,
+        fields(
+            datasource = datasource_name,
+            statement = tracing::field::Empty,
+            analyze
+        ),
+        err
+    )
+)]
+pub(crate) async fn explain<'a>(
+    stmt: String,
+    params: &[&'a dyn QueryParameter<'a>],
+    datasource_name: &'a str,
+    entity: &'static str,
+    analyze: bool,
+) -> Result<QueryPlan, Box<dyn std::error::Error + Sync + Send + 'static>> {
+    let statement = intercept(stmt, params, datasource_name, entity).await?;
+    let params = statement.params();
+
+    let started = Instant::now();
+    let result = plan(&statement.sql, &params, datasource_name, analyze).await;
+
+    conclude(
+        &statement,
+        &params,
+        match &result {
+            Ok(_) => StatementOutcome::Plan,
+            Err(error) => StatementOutcome::Error(&**error),
+        },
+        started,
+    );
+
+    result
+}
+
+/// Retrieves the plan of the `stmt`, already passed through the interceptors
+async fn plan(
+    stmt: &str,
+    params: &[&dyn QueryParameter<'_>],
+    datasource_name: &str,
+    analyze: bool,
+) -> Result<QueryPlan, Box<dyn std::error::Error + Sync + Send + 'static>> {
+    let guarded_cache = checkout_connections().await;
+
+    let database_conn = get_database_connection(&guarded_cache, datasource_name)?;
+
+    let (raw, root) = match database_conn.database_type {
+        DatabaseType::PostgreSql => {
+            let options = if analyze { "ANALYZE, " } else { "" };
+            let result = postgres_query_launcher::launch::<QueryPlan>(
+                database_conn,
+                format!("EXPLAIN ({options}FORMAT JSON) {stmt}"),
+                params,
+            )
+            .await?;
+            let raw = result
+                .postgres
+                .first()
+                .map(|row| row.try_get::<usize, JsonText>(0))
+                .transpose()?
+                .map(|json| json.0)
+                .unwrap_or_default();
+            let root = parse_postgres_plan(&raw);
+            (raw, root)
+        }
+        DatabaseType::SqlServer => {
+            let raw =
+                sqlserver_query_launcher::explain(database_conn, stmt, params, analyze).await?;
+            let root = parse_sqlserver_plan(&raw);
+            (raw, root)
+        }
+    };
+
+    match root {
+        Some(root) => Ok(QueryPlan {
+            database_type: database_conn.database_type,
+            root,
+            raw,
+        }),
+        None => Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("Canyon couldn't read the plan returned by the database: {raw}"),
+        )
+        .into()),
+    }
+}
+
+/// The text of a `json` value returned by `PostgreSQL`
+struct JsonText(String);
+
+impl<'a> FromSql<'a> for JsonText {
+    fn from_sql(
+        _ty: &Type,
+        raw: &'a [u8],
+    ) -> Result<Self, Box<dyn std::error::Error + Sync + Send>> {
+        Ok(Self(std::str::from_utf8(raw)?.to_string()))
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        *ty == Type::JSON
+    }
+}
+
+/// Reads the root node of a plan in the `PostgreSQL` JSON format, where every node
+/// is an object with its `Node Type`, `Total Cost`, `Plan Rows`, `Actual Rows`
+/// (only for the analyzed statements) and its children `Plans`
+fn parse_postgres_plan(raw: &str) -> Option<PlanNode> {
+    fn node(value: &Json) -> Option<PlanNode> {
+        Some(PlanNode {
+            node_type: value.get("Node Type")?.as_str()?.to_string(),
+            cost: value.get("Total Cost")?.as_f64()?,
+            rows: value.get("Plan Rows")?.as_f64()?,
+            actual_rows: value.get("Actual Rows").and_then(Json::as_f64),
+            children: match value.get("Plans") {
+                Some(Json::Array(plans)) => plans.iter().map(node).collect::<Option<_>>()?,
+                _ => vec![],
+            },
+        })
+    }
+
+    match Json::parse(raw)? {
+        Json::Array(statements) => node(statements.first()?.get("Plan")?),
+        _ => None,
+    }
+}
+
+/// Reads the root node of a plan in the `SqlServer` showplan XML format, where every
+/// node is a `RelOp` element, with its `PhysicalOp`, `EstimatedTotalSubtreeCost` and
+/// `EstimateRows`, the `RunTimeCountersPerThread` with the `ActualRows` (only for the
+/// analyzed statements) and the children `RelOp` nested inside
+fn parse_sqlserver_plan(raw: &str) -> Option<PlanNode> {
+    let attribute = |tag: &str, name: &str| -> Option<f64> {
+        let pattern = format!(" {name}=\"");
+        let start = tag.find(&pattern)? + pattern.len();
+        let end = start + tag[start..].find('"')?;
+        tag[start..end].parse().ok()
+    };
+
+    let mut open: Vec<PlanNode> = Vec::new();
+    let mut rest = raw;
+    while let Some(start) = rest.find('<') {
+        let end = start + rest[start..].find('>')?;
+        let tag = &rest[start + 1..end];
+        rest = &rest[end + 1..];
+
+        if tag.starts_with("RelOp ") {
+            let pattern = " PhysicalOp=\"";
+            let node_type = tag.find(pattern).map(|idx| &tag[idx + pattern.len()..])?;
+            open.push(PlanNode {
+                node_type: node_type[..node_type.find('"')?].to_string(),
+                cost: attribute(tag, "EstimatedTotalSubtreeCost")?,
+                rows: attribute(tag, "EstimateRows")?,
+                actual_rows: None,
+                children: vec![],
+            });
+        } else if tag.starts_with("RunTimeCountersPerThread ") {
+            // Every thread that runs the operation reports its own rows
+            if let (Some(node), Some(rows)) = (open.last_mut(), attribute(tag, "ActualRows")) {
+                node.actual_rows = Some(node.actual_rows.unwrap_or_default() + rows);
+            }
+        } else if tag == "/RelOp" {
+            let node = open.pop()?;
+            match open.last_mut() {
+                Some(parent) => parent.children.push(node),
+                None => return Some(node),
+            }
+        }
+    }
+
+    None
+}
+
+/// A minimal JSON document, enough for read the plans of `PostgreSQL`
+#[derive(Debug, Clone, PartialEq)]
+enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    fn parse(text: &str) -> Option<Self> {
+        let mut chars = text.chars().peekable();
+        let value = Self::parse_value(&mut chars)?;
+        chars.all(char::is_whitespace).then_some(value)
+    }
+
+    fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(members) => members
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        match self {
+            Json::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    fn as_f64(&self) -> Option<f64> {
+        match self {
+            Json::Number(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    fn parse_value(chars: &mut std::iter::Peekable<std::str::Chars>) -> Option<Self> {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+
+        match chars.peek()? {
+            '{' => {
+                chars.next();
+                let mut members = Vec::new();
+                loop {
+                    while chars.next_if(|c| c.is_whitespace()).is_some() {}
+                    match chars.next()? {
+                        '}' if members.is_empty() => break,
+                        '"' => {
+                            let name = Self::parse_string(chars)?;
+                            while chars.next_if(|c| c.is_whitespace()).is_some() {}
+                            chars.next_if_eq(&':')?;
+                            members.push((name, Self::parse_value(chars)?));
+                        }
+                        _ => return None,
+                    }
+                    while chars.next_if(|c| c.is_whitespace()).is_some() {}
+                    match chars.next()? {
+                        ',' => continue,
+                        '}' => break,
+                        _ => return None,
+                    }
+                }
+                Some(Json::Object(members))
+            }
+            '[' => {
+                chars.next();
+                let mut values = Vec::new();
+                loop {
+                    while chars.next_if(|c| c.is_whitespace()).is_some() {}
+                    if values.is_empty() && chars.next_if_eq(&']').is_some() {
+                        break;
+                    }
+                    values.push(Self::parse_value(chars)?);
+                    while chars.next_if(|c| c.is_whitespace()).is_some() {}
+                    match chars.next()? {
+                        ',' => continue,
+                        ']' => break,
+                        _ => return None,
+                    }
+                }
+                Some(Json::Array(values))
+            }
+            '"' => {
+                chars.next();
+                Self::parse_string(chars).map(Json::String)
+            }
+            _ => {
+                let mut literal = String::new();
+                while let Some(c) = chars.next_if(|c| c.is_alphanumeric() || "+-.".contains(*c)) {
+                    literal.push(c);
+                }
+                match literal.as_str() {
+                    "null" => Some(Json::Null),
+                    "true" => Some(Json::Bool(true)),
+                    "false" => Some(Json::Bool(false)),
+                    number => number.parse().ok().map(Json::Number),
+                }
+            }
+        }
+    }
+
+    /// Reads the rest of a string, after its opening quote
+    fn parse_string(chars: &mut std::iter::Peekable<std::str::Chars>) -> Option<String> {
+        let mut value = String::new();
+        loop {
+            match chars.next()? {
+                '"' => return Some(value),
+                '\\' => match chars.next()? {
+                    'n' => value.push('\n'),
+                    't' => value.push('\t'),
+                    'r' => value.push('\r'),
+                    'b' => value.push('\u{8}'),
+                    'f' => value.push('\u{c}'),
+                    'u' => {
+                        let code = (0..4).map(|_| chars.next()).collect::<Option<String>>()?;
+                        value.push(char::from_u32(u32::from_str_radix(&code, 16).ok()?)?);
+                    }
+                    escaped => value.push(escaped),
+                },
+                c => value.push(c),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod plans_parsing {
+    use super::*;
+
+    #[test]
+    fn postgres_json_plan() {
+        let raw = r#"[
+          {
+            "Plan": {
+              "Node Type": "Hash Join", "Total Cost": 42.5, "Plan Rows": 10,
+              "Actual Rows": 3, "Join Type": "Inner", "Hash Cond": "(t.league = l.id)",
+              "Plans": [
+                {"Node Type": "Seq Scan", "Relation Name": "tournament", "Total Cost": 20.1, "Plan Rows": 1010, "Actual Rows": 3},
+                {"Node Type": "Hash", "Total Cost": 12.0, "Plan Rows": 200, "Actual Rows": 8, "Plans": []}
+              ]
+            },
+            "Planning Time": 0.1, "Triggers": [], "Execution Time": null
+          }
+        ]"#;
+
+        let root = parse_postgres_plan(raw).expect("The plan is well formed");
+        assert_eq!(root.node_type, "Hash Join");
+        assert_eq!(
+            (root.cost, root.rows, root.actual_rows),
+            (42.5, 10.0, Some(3.0))
+        );
+        assert_eq!(
+            root.children
+                .iter()
+                .map(|node| node.node_type.as_str())
+                .collect::<Vec<_>>(),
+            ["Seq Scan", "Hash"]
+        );
+        assert!(parse_postgres_plan(r#"[{"Plan": {"Node Type": "Result"}}]"#).is_none());
+    }
+
+    #[test]
+    fn sqlserver_xml_plan() {
+        let raw = r#"<?xml version="1.0" encoding="utf-16"?>
+            <ShowPlanXML xmlns="http://schemas.microsoft.com/sqlserver/2004/07/showplan">
+              <RelOp NodeId="0" PhysicalOp="Nested Loops" LogicalOp="Inner Join" EstimateRows="4" EstimatedTotalSubtreeCost="0.0065">
+                <RunTimeInformation>
+                  <RunTimeCountersPerThread Thread="0" ActualRows="2" ActualExecutions="1" />
+                </RunTimeInformation>
+                <NestedLoops Optimized="0">
+                  <RelOp NodeId="1" PhysicalOp="Clustered Index Scan" LogicalOp="Clustered Index Scan" EstimateRows="4" EstimatedTotalSubtreeCost="0.0032">
+                    <RunTimeInformation>
+                      <RunTimeCountersPerThread Thread="1" ActualRows="1" />
+                      <RunTimeCountersPerThread Thread="2" ActualRows="1" />
+                    </RunTimeInformation>
+                  </RelOp>
+                  <RelOp NodeId="2" PhysicalOp="Clustered Index Seek" LogicalOp="Clustered Index Seek" EstimateRows="1" EstimatedTotalSubtreeCost="0.0031">
+                  </RelOp>
+                </NestedLoops>
+              </RelOp>
+            </ShowPlanXML>"#;
+
+        let root = parse_sqlserver_plan(raw).expect("The plan is well formed");
+        assert_eq!(root.node_type, "Nested Loops");
+        assert_eq!(
+            (root.cost, root.rows, root.actual_rows),
+            (0.0065, 4.0, Some(2.0))
+        );
+        assert_eq!(root.children.len(), 2);
+        assert_eq!(root.children[0].node_type, "Clustered Index Scan");
+        assert_eq!(root.children[0].actual_rows, Some(2.0));
+        assert_eq!(root.children[1].actual_rows, None);
+    }
+}
@@ -0,0 +1,182 @@
+//! Lifecycle hooks of the entities.
+//!
+//! The CRUD operations generated by the `CanyonCrud` derive macro, and the
+//! mapping of the rows generated by the `CanyonMapper` one, invokes the
+//! [`CanyonHooks`] of the entities that implements it, and it's completely
+//! optional: for the entities that doesn't, the hooks are a no-op.
+
+use std::{error::Error, marker::PhantomData};
+
+/// The hooks invoked by the CRUD operations over an entity.
+///
+/// Every method has an empty default implementation, so only the needed ones
+/// should be implemented. The `before_*` hooks are able to abort the operation,
+/// returning an error that it's returned to the caller without querying the
+/// database. The `after_*` ones are invoked only if the operation succeeded.
+///
+/// ```ignore
+/// impl CanyonHooks for League {
+///     fn before_insert(&mut self) -> Result<(), Box<dyn Error + Send + Sync>> {
+///         self.slug = self.slug.trim().to_lowercase();
+///         Ok(())
+///     }
+/// }
+/// ```
+pub trait CanyonHooks {
+    /// Invoked by `insert`, `insert_returning_all` and `multi_insert`, before
+    /// the values of the entity are sent to the database
+    fn before_insert(&mut self) -> Result<(), Box<dyn Error + Send + Sync>> {
+        Ok(())
+    }
+
+    /// Invoked after the entity is inserted, with the generated primary key already assigned
+    fn after_insert(&self) {}
+
+    /// Invoked by `update` before the values of the entity are sent to the database
+    fn before_update(&self) -> Result<(), Box<dyn Error + Send + Sync>> {
+        Ok(())
+    }
+
+    /// Invoked after the row of the entity is updated
+    fn after_update(&self) {}
+
+    /// Invoked by `delete` before the row of the entity is removed
+    fn before_delete(&self) -> Result<(), Box<dyn Error + Send + Sync>> {
+        Ok(())
+    }
+
+    /// Invoked after the row of the entity is removed
+    fn after_delete(&self) {}
+
+    /// Invoked for every entity mapped from a row of the database
+    fn after_load(&mut self) {}
+}
+
+/// Resolves the hooks of the type `T`, if it implements [`CanyonHooks`], or
+/// the no-op ones otherwise, in the code generated by the derive macros.
+///
+/// The hooks are called on a reference to the probe, `(&HooksProbe::<T>::new())`,
+/// so the method resolution picks the [`ImplementedHooks`] of the probe, when it's
+/// available, before the [`MissingHooks`] of the reference to the probe
+#[doc(hidden)]
+pub struct HooksProbe<T>(PhantomData<T>);
+
+impl<T> HooksProbe<T> {
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+#[doc(hidden)]
+pub trait ImplementedHooks<T> {
+    fn before_insert(&self, entity: &mut T) -> Result<(), Box<dyn Error + Send + Sync>>;
+    fn after_insert(&self, entity: &T);
+    fn before_update(&self, entity: &T) -> Result<(), Box<dyn Error + Send + Sync>>;
+    fn after_update(&self, entity: &T);
+    fn before_delete(&self, entity: &T) -> Result<(), Box<dyn Error + Send + Sync>>;
+    fn after_delete(&self, entity: &T);
+    fn after_load(&self, entity: &mut T);
+}
+
+impl<T: CanyonHooks> ImplementedHooks<T> for HooksProbe<T> {
+    fn before_insert(&self, entity: &mut T) -> Result<(), Box<dyn Error + Send + Sync>> {
+        entity.before_insert()
+    }
+
+    fn after_insert(&self, entity: &T) {
+        entity.after_insert()
+    }
+
+    fn before_update(&self, entity: &T) -> Result<(), Box<dyn Error + Send + Sync>> {
+        entity.before_update()
+    }
+
+    fn after_update(&self, entity: &T) {
+        entity.after_update()
+    }
+
+    fn before_delete(&self, entity: &T) -> Result<(), Box<dyn Error + Send + Sync>> {
+        entity.before_delete()
+    }
+
+    fn after_delete(&self, entity: &T) {
+        entity.after_delete()
+    }
+
+    fn after_load(&self, entity: &mut T) {
+        entity.after_load()
+    }
+}
+
+#[doc(hidden)]
+pub trait MissingHooks<T> {
+    fn before_insert(&self, _entity: &mut T) -> Result<(), Box<dyn Error + Send + Sync>> {
+        Ok(())
+    }
+
+    fn after_insert(&self, _entity: &T) {}
+
+    fn before_update(&self, _entity: &T) -> Result<(), Box<dyn Error + Send + Sync>> {
+        Ok(())
+    }
+
+    fn after_update(&self, _entity: &T) {}
+
+    fn before_delete(&self, _entity: &T) -> Result<(), Box<dyn Error + Send + Sync>> {
+        Ok(())
+    }
+
+    fn after_delete(&self, _entity: &T) {}
+
+    fn after_load(&self, _entity: &mut T) {}
+}
+
+impl<T> MissingHooks<T> for &HooksProbe<T> {}
+
+#[cfg(test)]
+mod probe {
+    use super::*;
+
+    struct Hooked(String);
+
+    impl CanyonHooks for Hooked {
+        fn before_insert(&mut self) -> Result<(), Box<dyn Error + Send + Sync>> {
+            self.0 = self.0.trim().to_lowercase();
+            Ok(())
+        }
+
+        fn before_delete(&self) -> Result<(), Box<dyn Error + Send + Sync>> {
+            Err("Protected".into())
+        }
+    }
+
+    struct NotHooked(String);
+
+    // The probe is borrowed as in the generated code, where it's needed for
+    // fall back to the missing hooks
+    #[test]
+    #[allow(clippy::needless_borrow)]
+    fn resolves_the_implemented_hooks() {
+        let mut hooked = Hooked(String::from("  LEC "));
+        assert!((&HooksProbe::<Hooked>::new())
+            .before_insert(&mut hooked)
+            .is_ok());
+        assert_eq!(hooked.0, "lec");
+        assert!((&HooksProbe::<Hooked>::new())
+            .before_delete(&hooked)
+            .is_err());
+    }
+
+    #[test]
+    fn resolves_the_missing_hooks_as_no_ops() {
+        let mut not_hooked = NotHooked(String::from("  LEC "));
+        assert!((&HooksProbe::<NotHooked>::new())
+            .before_insert(&mut not_hooked)
+            .is_ok());
+        assert_eq!(not_hooked.0, "  LEC ");
+        assert!((&HooksProbe::<NotHooked>::new())
+            .before_delete(&not_hooked)
+            .is_ok());
+    }
+}
@@ -0,0 +1,19 @@
+//! Records the outcome of the statements on their `tracing` spans.
+//!
+//! Only available with the `tracing` feature.
+
+use std::time::Instant;
+
+use canyon_connection::canyon_database_connector::DatabaseType;
+
+/// Records on the current span, the one of the statement, the type of the
+/// database that executed it, the number of rows retrieved or affected and
+/// the elapsed time
+pub(crate) fn record_statement(database_type: DatabaseType, rows: Option<u64>, started: Instant) {
+    let span = tracing::Span::current();
+    span.record("db_type", tracing::field::debug(database_type));
+    if let Some(rows) = rows {
+        span.record("rows", rows);
+    }
+    span.record("elapsed_ms", started.elapsed().as_secs_f64() * 1000.0);
+}
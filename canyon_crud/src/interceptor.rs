@@ -0,0 +1,297 @@
+//! Middlewares for the statements launched by Canyon.
+//!
+//! An [`Interceptor`] registered with [`register_interceptor`] receives every
+//! statement, generated by the CRUD operations, the query builders or written
+//! by the user, as an [`InterceptedStatement`] before it's launched against the
+//! database, where it can rewrite the SQL and the parameters, or reject the
+//! statement returning an error. After the statement is launched, it observes
+//! the [`StatementOutcome`] and the time that took.
+//!
+//! The interceptors are chained in the order that they were registered, so
+//! every one of them receives the statement rewritten by the previous ones, and
+//! they observe the outcomes in the reverse order.
+
+use std::{
+    error::Error,
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+
+use canyon_connection::canyon_database_connector::DatabaseType;
+
+use crate::{bounds::QueryParameter, query_elements::dialect::dialect};
+
+static INTERCEPTORS: RwLock<Vec<Arc<dyn Interceptor>>> = RwLock::new(Vec::new());
+
+/// Inspects and rewrites the statements before they're launched, and observes
+/// their outcome afterwards.
+///
+/// ```ignore
+/// struct ReadOnly;
+///
+/// impl Interceptor for ReadOnly {
+///     fn before(&self, statement: &mut InterceptedStatement<'_>) -> Result<(), Box<dyn Error + Send + Sync>> {
+///         if statement.sql.starts_with("SELECT") {
+///             Ok(())
+///         } else {
+///             Err("The database is in read-only mode".into())
+///         }
+///     }
+/// }
+///
+/// register_interceptor(ReadOnly);
+/// ```
+pub trait Interceptor: Send + Sync {
+    /// Called before the statement is launched. Returning an error cancels
+    /// the statement, and the error is returned to the caller
+    fn before(
+        &self,
+        _statement: &mut InterceptedStatement<'_>,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        Ok(())
+    }
+
+    /// Called after the statement is launched, or cancelled by an interceptor
+    fn after(
+        &self,
+        _statement: &InterceptedStatement<'_>,
+        _outcome: &StatementOutcome<'_>,
+        _elapsed: Duration,
+    ) {
+    }
+}
+
+/// Appends the `interceptor` to the chain of the registered ones.
+///
+/// Intended to be called on the initialization of the program, before any
+/// statement is launched
+pub fn register_interceptor(interceptor: impl Interceptor + 'static) {
+    INTERCEPTORS
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .push(Arc::new(interceptor));
+}
+
+/// Removes every registered interceptor
+pub fn clear_interceptors() {
+    INTERCEPTORS
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clear();
+}
+
+/// The name of the type `T`, without the path of its module
+pub(crate) fn entity_name<T>() -> &'static str {
+    let name = std::any::type_name::<T>();
+    name.rsplit("::").next().unwrap_or(name)
+}
+
+/// A statement about to be launched against the database
+pub struct InterceptedStatement<'a> {
+    /// The name of the datasource targeted by the statement, empty for the default one
+    pub datasource_name: &'a str,
+    /// The type of the database of the datasource
+    pub database_type: DatabaseType,
+    /// The type that launched the statement, usually the entity
+    pub entity: &'static str,
+    /// The SQL of the statement, with the placeholders of the `database_type`
+    pub sql: String,
+    params: Vec<Parameter<'a>>,
+}
+
+enum Parameter<'a> {
+    Borrowed(&'a dyn QueryParameter<'a>),
+    Owned(Box<dyn QueryParameter<'a> + 'a>),
+}
+
+impl<'a> InterceptedStatement<'a> {
+    pub(crate) fn new(
+        datasource_name: &'a str,
+        database_type: DatabaseType,
+        entity: &'static str,
+        sql: String,
+        params: &[&'a dyn QueryParameter<'a>],
+    ) -> Self {
+        Self {
+            datasource_name,
+            database_type,
+            entity,
+            sql,
+            params: params
+                .iter()
+                .map(|param| Parameter::Borrowed(*param))
+                .collect(),
+        }
+    }
+
+    /// The parameters bound to the placeholders of the statement
+    pub fn params(&self) -> Vec<&dyn QueryParameter<'a>> {
+        self.params
+            .iter()
+            .map(|param| match param {
+                Parameter::Borrowed(param) => *param,
+                Parameter::Owned(param) => param.as_ref(),
+            })
+            .collect()
+    }
+
+    /// Appends a parameter, that should be bound to the [`Self::next_placeholder`]
+    pub fn push_param(&mut self, value: impl QueryParameter<'a> + 'a) {
+        self.params.push(Parameter::Owned(Box::new(value)));
+    }
+
+    /// Replaces the value of the parameter at `index`, starting from zero
+    pub fn set_param(&mut self, index: usize, value: impl QueryParameter<'a> + 'a) {
+        self.params[index] = Parameter::Owned(Box::new(value));
+    }
+
+    /// Removes every parameter of the statement
+    pub fn clear_params(&mut self) {
+        self.params.clear();
+    }
+
+    /// The placeholder, for the database of the datasource, of the next
+    /// parameter appended with [`Self::push_param`]
+    pub fn next_placeholder(&self) -> String {
+        dialect(self.database_type).placeholder(self.params.len() + 1)
+    }
+}
+
+/// The result of an [`InterceptedStatement`]
+#[derive(Debug)]
+pub enum StatementOutcome<'r> {
+    /// The query retrieved this number of rows
+    Rows(usize),
+    /// The statement affected this number of rows
+    AffectedRows(u64),
+    /// The execution plan of the statement was retrieved
+    Plan,
+    /// The statement failed, or it was cancelled by an interceptor
+    Error(&'r (dyn Error + Send + Sync)),
+}
+
+/// Passes the statement through the chain of interceptors, until one of them rejects it
+pub(crate) fn before(
+    statement: &mut InterceptedStatement<'_>,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let interceptors = INTERCEPTORS
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clone();
+    interceptors
+        .iter()
+        .try_for_each(|interceptor| interceptor.before(statement))
+}
+
+/// Notifies the outcome of the statement to the interceptors, in the reverse order
+pub(crate) fn after(
+    statement: &InterceptedStatement<'_>,
+    outcome: StatementOutcome<'_>,
+    elapsed: Duration,
+) {
+    let interceptors = INTERCEPTORS
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clone();
+    interceptors
+        .iter()
+        .rev()
+        .for_each(|interceptor| interceptor.after(statement, &outcome, elapsed));
+}
+
+#[cfg(test)]
+mod chain {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    static OUTCOMES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+    struct Tenant;
+
+    impl Interceptor for Tenant {
+        fn before(
+            &self,
+            statement: &mut InterceptedStatement<'_>,
+        ) -> Result<(), Box<dyn Error + Send + Sync>> {
+            statement.sql = format!(
+                "{} AND tenant_id = {}",
+                statement.sql,
+                statement.next_placeholder()
+            );
+            statement.push_param(7);
+            Ok(())
+        }
+    }
+
+    struct ReadOnly;
+
+    impl Interceptor for ReadOnly {
+        fn before(
+            &self,
+            statement: &mut InterceptedStatement<'_>,
+        ) -> Result<(), Box<dyn Error + Send + Sync>> {
+            if statement.sql.starts_with("SELECT") {
+                Ok(())
+            } else {
+                Err("The database is in read-only mode".into())
+            }
+        }
+
+        fn after(
+            &self,
+            statement: &InterceptedStatement<'_>,
+            outcome: &StatementOutcome<'_>,
+            _elapsed: Duration,
+        ) {
+            OUTCOMES
+                .lock()
+                .unwrap()
+                .push(format!("{} -> {outcome:?}", statement.sql));
+        }
+    }
+
+    #[test]
+    fn rewrites_and_rejects_the_statements() {
+        register_interceptor(Tenant);
+        register_interceptor(ReadOnly);
+
+        let id = 1;
+        let mut select = InterceptedStatement::new(
+            "",
+            DatabaseType::SqlServer,
+            "League",
+            String::from("SELECT * FROM league WHERE id = @P1"),
+            &[&id],
+        );
+        assert!(before(&mut select).is_ok());
+        assert_eq!(
+            select
+                .params()
+                .iter()
+                .map(|param| param.as_sql_literal())
+                .collect::<Vec<_>>(),
+            ["1", "7"]
+        );
+        after(&select, StatementOutcome::Rows(1), Duration::ZERO);
+
+        let mut delete = InterceptedStatement::new(
+            "",
+            DatabaseType::PostgreSql,
+            "League",
+            String::from("DELETE FROM league WHERE id = $1"),
+            &[&id],
+        );
+        let error = before(&mut delete).unwrap_err();
+        after(&delete, StatementOutcome::Error(&*error), Duration::ZERO);
+        clear_interceptors();
+
+        assert_eq!(
+            *OUTCOMES.lock().unwrap(),
+            [
+                "SELECT * FROM league WHERE id = @P1 AND tenant_id = @P2 -> Rows(1)",
+                "DELETE FROM league WHERE id = $1 AND tenant_id = $2 -> Error(\"The database is in read-only mode\")"
+            ]
+        );
+    }
+}
@@ -2,9 +2,22 @@ extern crate canyon_connection;
 
 pub mod bounds;
 pub mod crud;
+pub mod explain;
+pub mod hooks;
+#[cfg(feature = "tracing")]
+mod instrumentation;
+pub mod interceptor;
+pub mod locking;
 pub mod mapper;
 pub mod query_elements;
+pub mod raw;
+pub mod relations;
 pub mod result;
+pub mod slow_query;
+pub mod soft_delete;
+pub mod timestamps;
+pub mod tracking;
+pub mod validation;
 
 pub use query_elements::operators::*;
 
@@ -0,0 +1,75 @@
+//! Optimistic locking of the entities.
+//!
+//! A `#[canyon_entity]` with an integer field annotated with `#[version]` only
+//! writes the row with the `update`, `update_changes`, `delete`, `force_delete` and
+//! `restore` operations if its version in the database is still the one held by
+//! the entity. When no row matches, because another operation modified or deleted
+//! it after it was read, the operation fails with a [`VersionConflict`] instead of
+//! overwriting the changes.
+//!
+//! Every update moves the version of the row forward by one, and the one of the
+//! entity along with it, so the same instance can keep writing the row. The soft
+//! deletions and the restorations only flip the mark of the row, and leave both
+//! versions as they are.
+
+use std::{error::Error, fmt::Display};
+
+use crate::interceptor::entity_name;
+
+/// The error of the operations over an entity whose `#[version]` doesn't match
+/// the one of the row in the database, or whose row no longer exists.
+///
+/// It can be recovered with `error.downcast_ref::<VersionConflict>()`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionConflict {
+    /// The type of the entity
+    pub entity: &'static str,
+    /// The version held by the entity
+    pub version: i64,
+}
+
+impl VersionConflict {
+    /// Fails with a conflict of the `T` entity at the `version` if the
+    /// statement guarded by it didn't affected any row
+    pub fn check<T>(version: i64, affected_rows: u64) -> Result<(), VersionConflict> {
+        if affected_rows == 0 {
+            Err(VersionConflict {
+                entity: entity_name::<T>(),
+                version,
+            })
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl Display for VersionConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "The {} at version {} was modified or deleted by another operation",
+            self.entity, self.version
+        )
+    }
+}
+
+impl Error for VersionConflict {}
+
+#[cfg(test)]
+mod conflicts {
+    use super::*;
+
+    struct League;
+
+    #[test]
+    fn fails_when_no_row_matches_the_version() {
+        assert_eq!(VersionConflict::check::<League>(3, 1), Ok(()));
+
+        let conflict = VersionConflict::check::<League>(3, 0).unwrap_err();
+        assert_eq!(conflict.entity, "League");
+        assert_eq!(
+            conflict.to_string(),
+            "The League at version 3 was modified or deleted by another operation"
+        );
+    }
+}
@@ -1,12 +1,24 @@
 use canyon_connection::{tiberius, tokio_postgres};
 
-use crate::crud::Transaction;
-
 /// Declares functions that takes care to deserialize data incoming
 /// from some supported database in Canyon-SQL into a user's defined
 /// type `T`
-pub trait RowMapper<T: Transaction<T>>: Sized {
+pub trait RowMapper<T>: Sized {
     fn deserialize_postgresql(row: &tokio_postgres::Row) -> T;
 
     fn deserialize_sqlserver(row: &tiberius::Row) -> T;
 }
+
+/// Declares the functions that deserializes a type `T` from the columns
+/// aliased with a prefix, like the ones of both sides of the typed joins.
+///
+/// Generated by the `CanyonMapper` derive macro along with the [`RowMapper`]
+pub trait PrefixedRowMapper<T>: RowMapper<T> {
+    /// Same as [`RowMapper::deserialize_postgresql`], but every column is
+    /// retrieved with the `prefix` before its name
+    fn deserialize_postgresql_with_prefix(row: &tokio_postgres::Row, prefix: &str) -> T;
+
+    /// Same as [`RowMapper::deserialize_sqlserver`], but every column is
+    /// retrieved with the `prefix` before its name
+    fn deserialize_sqlserver_with_prefix(row: &tiberius::Row, prefix: &str) -> T;
+}
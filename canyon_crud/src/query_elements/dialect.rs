@@ -0,0 +1,176 @@
+//! The SQL dialects of the supported databases.
+//!
+//! A [`Dialect`] renders the elements of a [`Statement`](super::statement::Statement)
+//! whose syntax differs between databases, like the placeholders of the parameters
+//! or the clause that reads back the inserted rows, so every statement is generated
+//! directly in the SQL of the targeted database.
+
+use canyon_connection::{
+    canyon_database_connector::DatabaseType, get_database_type_from_datasource_name,
+};
+
+use crate::{query_elements::parameters::to_sqlserver_placeholders, Nulls, Order};
+
+/// The syntax of a concrete database for the elements of the statements
+pub trait Dialect: Sync {
+    /// The type of the database that speaks the dialect
+    fn database_type(&self) -> DatabaseType;
+
+    /// The placeholder of the parameter at the `position` (starting at 1)
+    fn placeholder(&self, position: usize) -> String;
+
+    /// Adapts a fragment of raw SQL written by the user, where the parameters
+    /// are always referenced as `$1`, `$2`...
+    fn raw(&self, sql: &str) -> String {
+        sql.to_string()
+    }
+
+    /// Matches the `expression` against the `pattern` ignoring the case
+    fn ilike(&self, expression: &str, pattern: &str) -> String;
+
+    /// A key of an `ORDER BY` clause, ordering by the `expression` and
+    /// placing the `NULL` values where `nulls` indicates
+    fn order_key(&self, expression: &str, order: Order, nulls: Option<Nulls>) -> String;
+
+    /// The clause that reads back the `columns` of the inserted rows that
+    /// goes between the columns and the `VALUES` of an `INSERT`
+    fn output_clause(&self, _columns: &[String]) -> Option<String> {
+        None
+    }
+
+    /// The clause that reads back the `columns` of the inserted rows that
+    /// goes after the `VALUES` of an `INSERT`
+    fn returning_clause(&self, _columns: &[String]) -> Option<String> {
+        None
+    }
+
+    /// The expression of the current time of the database, in UTC
+    fn current_timestamp(&self) -> &'static str;
+
+    /// The literal of a boolean `value`
+    fn boolean(&self, value: bool) -> &'static str;
+}
+
+/// The dialect of `PostgreSQL`
+#[derive(Debug, Clone, Copy)]
+pub struct PostgreSqlDialect;
+
+impl Dialect for PostgreSqlDialect {
+    fn database_type(&self) -> DatabaseType {
+        DatabaseType::PostgreSql
+    }
+
+    fn placeholder(&self, position: usize) -> String {
+        format!("${position}")
+    }
+
+    fn ilike(&self, expression: &str, pattern: &str) -> String {
+        format!("{expression} ILIKE {pattern}")
+    }
+
+    fn order_key(&self, expression: &str, order: Order, nulls: Option<Nulls>) -> String {
+        match nulls {
+            Some(nulls) => format!("{expression}{} {}", order.as_str(), nulls.as_str()),
+            None => format!("{expression}{}", order.as_str()),
+        }
+    }
+
+    fn returning_clause(&self, columns: &[String]) -> Option<String> {
+        Some(format!("RETURNING {}", columns.join(", ")))
+    }
+
+    fn current_timestamp(&self) -> &'static str {
+        "(CURRENT_TIMESTAMP AT TIME ZONE 'UTC')"
+    }
+
+    fn boolean(&self, value: bool) -> &'static str {
+        if value {
+            "TRUE"
+        } else {
+            "FALSE"
+        }
+    }
+}
+
+/// The dialect of `SqlServer`
+#[derive(Debug, Clone, Copy)]
+pub struct SqlServerDialect;
+
+impl Dialect for SqlServerDialect {
+    fn database_type(&self) -> DatabaseType {
+        DatabaseType::SqlServer
+    }
+
+    fn placeholder(&self, position: usize) -> String {
+        format!("@P{position}")
+    }
+
+    fn raw(&self, sql: &str) -> String {
+        to_sqlserver_placeholders(sql)
+    }
+
+    /// There's no `ILIKE` operator, so both sides are compared in lowercase
+    fn ilike(&self, expression: &str, pattern: &str) -> String {
+        format!("LOWER({expression}) LIKE LOWER({pattern})")
+    }
+
+    /// `NULLS FIRST` and `NULLS LAST` are not supported, so they're emulated
+    /// with a previous key that orders the `NULL` values with a `CASE`
+    fn order_key(&self, expression: &str, order: Order, nulls: Option<Nulls>) -> String {
+        let key = format!("{expression}{}", order.as_str());
+        match nulls {
+            Some(nulls) => {
+                let (null_rank, value_rank) = match nulls {
+                    Nulls::First => (0, 1),
+                    Nulls::Last => (1, 0),
+                };
+                format!(
+                    "CASE WHEN {expression} IS NULL THEN {null_rank} ELSE {value_rank} END, {key}"
+                )
+            }
+            None => key,
+        }
+    }
+
+    fn output_clause(&self, columns: &[String]) -> Option<String> {
+        Some(format!(
+            "OUTPUT {}",
+            columns
+                .iter()
+                .map(|column| format!("inserted.{column}"))
+                .collect::<Vec<String>>()
+                .join(", ")
+        ))
+    }
+
+    fn current_timestamp(&self) -> &'static str {
+        "SYSUTCDATETIME()"
+    }
+
+    /// The booleans are stored as a `BIT`
+    fn boolean(&self, value: bool) -> &'static str {
+        if value {
+            "1"
+        } else {
+            "0"
+        }
+    }
+}
+
+/// Returns the [`Dialect`] of the `database_type`
+pub fn dialect(database_type: DatabaseType) -> &'static dyn Dialect {
+    match database_type {
+        DatabaseType::PostgreSql => &PostgreSqlDialect,
+        DatabaseType::SqlServer => &SqlServerDialect,
+    }
+}
+
+/// Returns the [`Dialect`] of the database targeted by the datasource, failing if
+/// it isn't declared in the configuration file.
+///
+/// No datasource means take the entry zero
+pub fn datasource_dialect(
+    datasource_name: &str,
+) -> Result<&'static dyn Dialect, Box<dyn std::error::Error + Send + Sync + 'static>> {
+    get_database_type_from_datasource_name(datasource_name).map(dialect)
+}
@@ -0,0 +1,224 @@
+//! Predicate expressions for the filters of the query builders.
+//!
+//! An [`Expr`] is a tree of conditions grouped with `AND`, `OR` and `NOT`,
+//! that can be built dynamically (p.e. from optional filters) and is rendered
+//! with the correct parentheses and placeholder numbering when it's passed
+//! to a query builder.
+
+use canyon_connection::canyon_database_connector::DatabaseType;
+
+use crate::{
+    bounds::{FieldIdentifier, FieldValueIdentifier, QueryParameter},
+    crud::{CrudOperations, Transaction},
+    mapper::RowMapper,
+    query_elements::{
+        dialect::dialect,
+        query_builder::SelectQueryBuilder,
+        statement::{Predicate, Select},
+    },
+    Condition, Operator,
+};
+
+/// A node of a predicate expression
+pub enum Expr<'a> {
+    /// Comparison of a column against a value
+    Compare(&'static str, Box<dyn Operator>, &'a dyn QueryParameter<'a>),
+    /// A [`Condition`] over a column
+    Condition(&'static str, Condition<'a>),
+    /// Checks if the column matches some of the values, or none of them if
+    /// it's negated
+    In(&'static str, Vec<&'a dyn QueryParameter<'a>>, bool),
+    /// Every expression must be satisfied
+    And(Vec<Expr<'a>>),
+    /// Some of the expressions must be satisfied
+    Or(Vec<Expr<'a>>),
+    /// The expression must not be satisfied
+    Not(Box<Expr<'a>>),
+    /// Checks if the column matches some of the rows of the subquery
+    InSubquery(&'static str, Select, Vec<&'a dyn QueryParameter<'a>>),
+    /// Checks if the subquery retrieves some row
+    Exists(Select, Vec<&'a dyn QueryParameter<'a>>),
+}
+
+impl<'a> Expr<'a> {
+    /// Compares the column of the [`FieldValueIdentifier`] against its value
+    pub fn compare<T, Z>(column: Z, op: impl Operator + 'static) -> Self
+    where
+        T: CrudOperations<T> + Transaction<T> + RowMapper<T>,
+        Z: FieldValueIdentifier<'a, T>,
+    {
+        let (column, value) = column.value();
+        Self::Compare(column, Box::new(op), value)
+    }
+
+    /// Checks the [`Condition`] over the column
+    pub fn condition<T, Z>(column: Z, condition: Condition<'a>) -> Self
+    where
+        T: CrudOperations<T> + Transaction<T> + RowMapper<T>,
+        Z: FieldIdentifier<T>,
+    {
+        Self::Condition(column.as_str(), condition)
+    }
+
+    /// Checks that the column matches some of the `values`
+    pub fn values_in<T, Z, Q>(column: Z, values: &'a [Q]) -> Self
+    where
+        T: CrudOperations<T> + Transaction<T> + RowMapper<T>,
+        Z: FieldIdentifier<T>,
+        Q: QueryParameter<'a>,
+    {
+        Self::In(column.as_str(), Self::as_params(values), false)
+    }
+
+    /// Checks that the column does not match any of the `values`
+    pub fn not_values_in<T, Z, Q>(column: Z, values: &'a [Q]) -> Self
+    where
+        T: CrudOperations<T> + Transaction<T> + RowMapper<T>,
+        Z: FieldIdentifier<T>,
+        Q: QueryParameter<'a>,
+    {
+        Self::In(column.as_str(), Self::as_params(values), true)
+    }
+
+    /// Checks that the column matches some of the rows retrieved by the `subquery`,
+    /// that must select only one column.
+    ///
+    /// The parameters of the subquery are merged into the ones of the query that
+    /// contains the expression
+    pub fn in_subquery<T, Z, U>(column: Z, subquery: &SelectQueryBuilder<'a, U>) -> Self
+    where
+        T: CrudOperations<T> + Transaction<T> + RowMapper<T>,
+        Z: FieldIdentifier<T>,
+        U: CrudOperations<U> + Transaction<U> + RowMapper<U>,
+    {
+        let (select, params) = subquery.statement_and_params();
+        Self::InSubquery(column.as_str(), select.clone(), params.to_vec())
+    }
+
+    /// Checks that the `subquery` retrieves some row.
+    ///
+    /// The parameters of the subquery are merged into the ones of the query that
+    /// contains the expression
+    pub fn exists<U>(subquery: &SelectQueryBuilder<'a, U>) -> Self
+    where
+        U: CrudOperations<U> + Transaction<U> + RowMapper<U>,
+    {
+        let (select, params) = subquery.statement_and_params();
+        Self::Exists(select.clone(), params.to_vec())
+    }
+
+    /// Groups the expressions with `AND`. The ones without content are skipped
+    pub fn and(exprs: impl IntoIterator<Item = Expr<'a>>) -> Self {
+        Self::And(exprs.into_iter().collect())
+    }
+
+    /// Groups the expressions with `OR`. The ones without content are skipped
+    pub fn or(exprs: impl IntoIterator<Item = Expr<'a>>) -> Self {
+        Self::Or(exprs.into_iter().collect())
+    }
+
+    /// Returns `true` if the expression does not contain any condition, like an
+    /// `AND` or an `OR` without elements, so it will not be rendered
+    pub fn is_empty(&self) -> bool {
+        match self {
+            Self::And(exprs) | Self::Or(exprs) => exprs.iter().all(Self::is_empty),
+            Self::Not(expr) => expr.is_empty(),
+            _ => false,
+        }
+    }
+
+    /// Generates the SQL of the expression, pushing its values to the `params`,
+    /// so the placeholders are numbered after the ones already present.
+    ///
+    /// Returns [`None`] if the expression [`Self::is_empty`]
+    pub fn to_sql(
+        &self,
+        params: &mut Vec<&'a dyn QueryParameter<'a>>,
+        database_type: DatabaseType,
+    ) -> Option<String> {
+        self.to_predicate(params)
+            .map(|predicate| predicate.to_sql(dialect(database_type)))
+    }
+
+    /// Converts the expression into a [`Predicate`] of a statement, pushing its
+    /// values to the `params`, so its parameters are placed after the ones
+    /// already present.
+    ///
+    /// Returns [`None`] if the expression [`Self::is_empty`]
+    pub fn to_predicate(&self, params: &mut Vec<&'a dyn QueryParameter<'a>>) -> Option<Predicate> {
+        match self {
+            Self::Compare(column, op, value) => {
+                params.push(*value);
+                Some(Predicate::Compare {
+                    column: column.to_string(),
+                    operator: op.as_str(),
+                    case_insensitive: op.is_case_insensitive(),
+                    value: params.len(),
+                })
+            }
+            Self::Condition(column, condition) => {
+                let predicate = condition.predicate(column, params.len() + 1);
+                params.extend(condition.params());
+                Some(predicate)
+            }
+            Self::In(column, values, negated) => {
+                let first = params.len() + 1;
+                params.extend(values);
+                Some(Predicate::In {
+                    column: column.to_string(),
+                    values: (first..=params.len()).collect(),
+                    negated: *negated,
+                })
+            }
+            Self::And(exprs) => Self::group_to_predicate(exprs, params).map(Predicate::And),
+            Self::Or(exprs) => Self::group_to_predicate(exprs, params).map(Predicate::Or),
+            Self::Not(expr) => expr
+                .to_predicate(params)
+                .map(|predicate| Predicate::Not(Box::new(predicate))),
+            Self::InSubquery(column, select, subquery_params) => {
+                let mut subquery = select.clone();
+                subquery.shift_placeholders(params.len());
+                params.extend(subquery_params);
+                Some(Predicate::InSubquery {
+                    column: column.to_string(),
+                    subquery: Box::new(subquery),
+                })
+            }
+            Self::Exists(select, subquery_params) => {
+                let mut subquery = select.clone();
+                subquery.shift_placeholders(params.len());
+                params.extend(subquery_params);
+                Some(Predicate::Exists(Box::new(subquery)))
+            }
+        }
+    }
+
+    /// Converts the expressions of a group, skipping the ones without content
+    fn group_to_predicate(
+        exprs: &[Expr<'a>],
+        params: &mut Vec<&'a dyn QueryParameter<'a>>,
+    ) -> Option<Vec<Predicate>> {
+        let predicates = exprs
+            .iter()
+            .filter_map(|expr| expr.to_predicate(params))
+            .collect::<Vec<Predicate>>();
+
+        if predicates.is_empty() {
+            None
+        } else {
+            Some(predicates)
+        }
+    }
+
+    fn as_params<Q: QueryParameter<'a>>(values: &'a [Q]) -> Vec<&'a dyn QueryParameter<'a>> {
+        values
+            .iter()
+            .map(|value| value as &'a dyn QueryParameter<'a>)
+            .collect()
+    }
+}
+
+/// Negates the expression
+pub fn not(expr: Expr<'_>) -> Expr<'_> {
+    Expr::Not(Box::new(expr))
+}
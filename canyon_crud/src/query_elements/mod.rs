@@ -1,3 +1,7 @@
+pub mod dialect;
+pub mod expr;
 pub mod operators;
+pub mod parameters;
 pub mod query;
 pub mod query_builder;
+pub mod statement;
@@ -1,5 +1,13 @@
+use crate::{bounds::QueryParameter, query_elements::statement::Predicate};
+
 pub trait Operator {
     fn as_str(&self) -> &'static str;
+
+    /// Returns `true` if the comparison ignores the case of the values, so it's
+    /// rendered with the syntax of the targeted database instead of [`Self::as_str`]
+    fn is_case_insensitive(&self) -> bool {
+        false
+    }
 }
 
 /// Enumerated type for represent the comparison operations
@@ -17,6 +25,13 @@ pub enum Comp {
     Lt,
     /// Operator "=<" less or equals than value
     LtEq,
+    /// Operator "LIKE" matches the pattern
+    Like,
+    /// Operator "NOT LIKE" does not match the pattern
+    NotLike,
+    /// Operator "ILIKE" matches the pattern, case insensitive. On `SqlServer`
+    /// is emulated by comparing both sides with `LOWER()`
+    ILike,
 }
 impl Operator for Comp {
     fn as_str(&self) -> &'static str {
@@ -27,6 +42,129 @@ impl Operator for Comp {
             Self::GtEq => " >= ",
             Self::Lt => " < ",
             Self::LtEq => " <= ",
+            Self::Like => " LIKE ",
+            Self::NotLike => " NOT LIKE ",
+            Self::ILike => " ILIKE ",
+        }
+    }
+
+    fn is_case_insensitive(&self) -> bool {
+        matches!(self, Self::ILike)
+    }
+}
+
+/// Enumerated type for represent the conditions over a column
+/// in SQL sentences that doesn't compare it against a single value
+pub enum Condition<'a> {
+    /// "IS NULL" the column has no value
+    IsNull,
+    /// "IS NOT NULL" the column has some value
+    IsNotNull,
+    /// "BETWEEN a AND b" the value of the column is in the inclusive range
+    Between(&'a dyn QueryParameter<'a>, &'a dyn QueryParameter<'a>),
+}
+impl<'a> Condition<'a> {
+    /// Generates the predicate of the condition over the `column`, where `first_param`
+    /// is the position of the first parameter of the values of the condition
+    pub fn predicate(&self, column: &str, first_param: usize) -> Predicate {
+        let column = column.to_string();
+        match self {
+            Self::IsNull => Predicate::IsNull {
+                column,
+                negated: false,
+            },
+            Self::IsNotNull => Predicate::IsNull {
+                column,
+                negated: true,
+            },
+            Self::Between(_, _) => Predicate::Between {
+                column,
+                from: first_param,
+                to: first_param + 1,
+            },
+        }
+    }
+
+    /// The values that must be bound to the placeholders of the condition
+    pub fn params(&self) -> Vec<&'a dyn QueryParameter<'a>> {
+        match self {
+            Self::IsNull | Self::IsNotNull => Vec::new(),
+            Self::Between(from, to) => vec![*from, *to],
+        }
+    }
+}
+
+/// Enumerated type for represent the aggregate functions
+/// in SQL sentences
+pub enum Aggregate {
+    /// Function "COUNT", the number of non null values
+    Count,
+    /// Function "SUM", the sum of the values
+    Sum,
+    /// Function "AVG", the average of the values
+    Avg,
+    /// Function "MIN", the minimum value
+    Min,
+    /// Function "MAX", the maximum value
+    Max,
+}
+impl Aggregate {
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            Self::Count => "COUNT",
+            Self::Sum => "SUM",
+            Self::Avg => "AVG",
+            Self::Min => "MIN",
+            Self::Max => "MAX",
+        }
+    }
+
+    /// Generates the SQL of the aggregate function applied over the `column`.
+    ///
+    /// The results of a `COUNT` are always a `BIGINT`, and an `AVG` is always
+    /// calculated over `FLOAT` values, so they can be retrieved as an `i64` and
+    /// an `f64` no matter the database
+    pub fn as_sql(&self, column: &str) -> String {
+        match *self {
+            Self::Count => format!("CAST(COUNT({column}) AS BIGINT)"),
+            Self::Avg => format!("AVG(CAST({column} AS FLOAT))"),
+            _ => format!("{}({column})", self.as_str()),
+        }
+    }
+}
+
+/// Enumerated type for represent the direction of the ordering
+/// of an `ORDER BY` SQL clause
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Order {
+    /// Ascending order, the default one of the databases
+    Asc,
+    /// Descending order
+    Desc,
+}
+impl Order {
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            Self::Asc => "",
+            Self::Desc => " DESC",
+        }
+    }
+}
+
+/// Enumerated type for represent where the `NULL` values are placed
+/// by an `ORDER BY` SQL clause
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Nulls {
+    /// The `NULL` values goes before the non `NULL` ones
+    First,
+    /// The `NULL` values goes after the non `NULL` ones
+    Last,
+}
+impl Nulls {
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            Self::First => "NULLS FIRST",
+            Self::Last => "NULLS LAST",
         }
     }
 }
@@ -0,0 +1,412 @@
+//! Parsing of the placeholders of the SQL statements.
+//!
+//! Canyon generates its statements with the `PostgreSQL` positional placeholders
+//! (`$1`, `$2`...), that must be translated for the other databases, and allows
+//! the users to write their raw statements with named parameters (`:name` or `@name`).
+//!
+//! The parser skips the string literals, the `"quoted"` and `[bracketed]` identifiers,
+//! the comments and the `PostgreSQL` dollar-quoted strings, so only the real
+//! placeholders are rewritten.
+//! `::` casts and `@@` system variables are not taken as named parameters.
+
+use crate::bounds::QueryParameter;
+
+/// A placeholder found in a SQL statement
+enum Placeholder<'s> {
+    /// The digits of a `$n` placeholder
+    Positional(&'s str),
+    /// The name of a `:name` or `@name` placeholder
+    Named(&'s str),
+}
+
+/// Represents a raw statement written with named parameters, already
+/// translated into the positional placeholders used by Canyon.
+///
+/// Every occurrence of the same name gets the same position, so the value
+/// is only bound once
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NamedStatement {
+    sql: String,
+    names: Vec<String>,
+}
+
+impl NamedStatement {
+    pub fn new(stmt: &str) -> Self {
+        let mut names: Vec<String> = Vec::new();
+
+        let sql = rewrite_placeholders(stmt, |placeholder| match placeholder {
+            Placeholder::Positional(_) => None,
+            Placeholder::Named(name) => {
+                let position = match names.iter().position(|n| n == name) {
+                    Some(idx) => idx + 1,
+                    None => {
+                        names.push(name.to_string());
+                        names.len()
+                    }
+                };
+                Some(format!("${position}"))
+            }
+        });
+
+        Self { sql, names }
+    }
+
+    /// The statement with the positional placeholders
+    pub fn sql(&self) -> &str {
+        &self.sql
+    }
+
+    /// The names of the parameters, in the order of their positions
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// Sorts the values of the named parameters by the position of every name
+    /// in the statement.
+    ///
+    /// Fails if some parameter of the statement does not have a value, or if some
+    /// value does not belong to any parameter of the statement
+    pub fn bind<'a>(
+        &self,
+        params: &[(&str, &'a dyn QueryParameter<'a>)],
+    ) -> Result<Vec<&'a dyn QueryParameter<'a>>, Box<dyn std::error::Error + Send + Sync + 'static>>
+    {
+        if let Some((unknown, _)) = params
+            .iter()
+            .find(|(name, _)| !self.names.iter().any(|n| n == name))
+        {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("The statement does not contain a parameter named `{unknown}`"),
+            )
+            .into());
+        }
+
+        self.names
+            .iter()
+            .map(|name| {
+                params
+                    .iter()
+                    .find(|(n, _)| n == name)
+                    .map(|(_, value)| *value)
+                    .ok_or_else(|| {
+                        std::io::Error::new(
+                            std::io::ErrorKind::InvalidInput,
+                            format!("No value provided for the parameter named `{name}`"),
+                        )
+                        .into()
+                    })
+            })
+            .collect()
+    }
+}
+
+/// Translates the `$n` placeholders of the statement into the `@Pn` ones
+/// used by `SqlServer`
+pub fn to_sqlserver_placeholders(stmt: &str) -> String {
+    rewrite_placeholders(stmt, |placeholder| match placeholder {
+        Placeholder::Positional(position) => Some(format!("@P{position}")),
+        Placeholder::Named(_) => None,
+    })
+}
+
+/// Renumbers the `$n` placeholders of the statement as `$(n + offset)`, so it can
+/// be embedded into another statement that already has `offset` parameters
+pub fn shift_placeholders(stmt: &str, offset: usize) -> String {
+    rewrite_placeholders(stmt, |placeholder| match placeholder {
+        Placeholder::Positional(position) => position
+            .parse::<usize>()
+            .ok()
+            .map(|position| format!("${}", position + offset)),
+        Placeholder::Named(_) => None,
+    })
+}
+
+/// Replaces the placeholders of the statement, both the `$n` and the `@Pn` ones,
+/// by the SQL literals of their values. Intended for the logs, so the statement
+/// can be read, or copied into a database client, with the values in place.
+///
+/// The placeholders without a value are kept as is
+pub fn inline_params(stmt: &str, params: &[&dyn QueryParameter<'_>]) -> String {
+    rewrite_placeholders(stmt, |placeholder| {
+        let position = match placeholder {
+            Placeholder::Positional(position) => position,
+            Placeholder::Named(name) => name.strip_prefix('P')?,
+        };
+        position
+            .parse::<usize>()
+            .ok()
+            .and_then(|position| params.get(position.checked_sub(1)?))
+            .map(|value| value.as_sql_literal())
+    })
+}
+
+/// Masks the content of the string literals of the statement, so it can be
+/// reported without leaking the values written inline on the raw statements.
+/// The values of the parameters are never part of the statement
+pub fn sanitize_statement(stmt: &str) -> String {
+    let mut sanitized = String::with_capacity(stmt.len());
+    let mut in_literal = false;
+    let mut chars = stmt.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (c, in_literal) {
+            ('\'', false) => {
+                sanitized.push_str("'?");
+                in_literal = true;
+            }
+            // An escaped quote doesn't close the literal
+            ('\'', true) if chars.next_if_eq(&'\'').is_some() => {}
+            ('\'', true) => {
+                sanitized.push('\'');
+                in_literal = false;
+            }
+            (_, true) => {}
+            (c, false) => sanitized.push(c),
+        }
+    }
+
+    sanitized
+}
+
+/// Walks over the statement, calling `replace` for every placeholder found
+/// outside the literals and comments. The placeholder is replaced by the returned
+/// value, or kept as is if it's [`None`]
+fn rewrite_placeholders<'s>(
+    stmt: &'s str,
+    mut replace: impl FnMut(Placeholder<'s>) -> Option<String>,
+) -> String {
+    let bytes = stmt.as_bytes();
+    let mut rewritten = String::with_capacity(stmt.len());
+    let mut copied = 0;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let next = bytes.get(i + 1).copied();
+        let (placeholder, end) = match bytes[i] {
+            quote @ (b'\'' | b'"') => {
+                i = bytes[i + 1..]
+                    .iter()
+                    .position(|b| *b == quote)
+                    .map_or(bytes.len(), |p| i + p + 2);
+                continue;
+            }
+            // A `[bracketed]` identifier of `SqlServer`, unless it's the subscript
+            // of a `PostgreSQL` array, that follows the expression that it indexes
+            b'[' if !i.checked_sub(1).is_some_and(|prev| {
+                is_identifier_byte(&bytes[prev]) || b"])".contains(&bytes[prev])
+            }) =>
+            {
+                i = bytes[i + 1..]
+                    .iter()
+                    .position(|b| *b == b']')
+                    .map_or(bytes.len(), |p| i + p + 2);
+                continue;
+            }
+            b'-' if next == Some(b'-') => {
+                i = bytes[i..]
+                    .iter()
+                    .position(|b| *b == b'\n')
+                    .map_or(bytes.len(), |p| i + p + 1);
+                continue;
+            }
+            b'/' if next == Some(b'*') => {
+                i = stmt[i + 2..].find("*/").map_or(bytes.len(), |p| i + p + 4);
+                continue;
+            }
+            b'$' => {
+                let digits = count_while(&bytes[i + 1..], |b| b.is_ascii_digit());
+                if digits > 0 {
+                    let end = i + 1 + digits;
+                    (Placeholder::Positional(&stmt[i + 1..end]), end)
+                } else {
+                    i = dollar_quoted_end(stmt, i).unwrap_or(i + 1);
+                    continue;
+                }
+            }
+            prefix @ (b':' | b'@') => {
+                if next == Some(prefix) {
+                    // `::` casts and `@@` system variables
+                    i += 2 + count_while(&bytes[i + 2..], is_identifier_byte);
+                    continue;
+                }
+                let len = count_while(&bytes[i + 1..], is_identifier_byte);
+                if len == 0 || bytes[i + 1].is_ascii_digit() {
+                    i += 1;
+                    continue;
+                }
+                let end = i + 1 + len;
+                (Placeholder::Named(&stmt[i + 1..end]), end)
+            }
+            _ => {
+                i += 1;
+                continue;
+            }
+        };
+
+        if let Some(replacement) = replace(placeholder) {
+            rewritten.push_str(&stmt[copied..i]);
+            rewritten.push_str(&replacement);
+            copied = end;
+        }
+        i = end;
+    }
+
+    rewritten.push_str(&stmt[copied..]);
+    rewritten
+}
+
+/// If there's a `PostgreSQL` dollar-quoted string (`$tag$ ... $tag$`) starting
+/// at `start`, returns the index after its end
+fn dollar_quoted_end(stmt: &str, start: usize) -> Option<usize> {
+    let bytes = stmt.as_bytes();
+    let tag_len = count_while(&bytes[start + 1..], is_identifier_byte);
+    if bytes.get(start + 1 + tag_len) != Some(&b'$') {
+        return None;
+    }
+
+    let tag = &stmt[start..start + tag_len + 2];
+    let body = start + tag.len();
+    Some(
+        stmt[body..]
+            .find(tag)
+            .map_or(bytes.len(), |p| body + p + tag.len()),
+    )
+}
+
+fn count_while(bytes: &[u8], predicate: impl Fn(&u8) -> bool) -> usize {
+    bytes.iter().take_while(|b| predicate(b)).count()
+}
+
+fn is_identifier_byte(b: &u8) -> bool {
+    b.is_ascii_alphanumeric() || *b == b'_'
+}
+
+#[cfg(test)]
+mod parameters_parsing {
+    use super::*;
+
+    /// Named parameters are translated to positional ones, reusing the position
+    /// of the repeated names
+    #[test]
+    fn named_parameters_to_positional() {
+        let stmt = NamedStatement::new(
+            "SELECT * FROM league WHERE id = :league_id OR ext_id > @ext AND id <> :league_id",
+        );
+
+        assert_eq!(
+            stmt.sql(),
+            "SELECT * FROM league WHERE id = $1 OR ext_id > $2 AND id <> $1"
+        );
+        assert_eq!(stmt.names(), ["league_id", "ext"]);
+    }
+
+    /// Literals, comments, casts and system variables are left untouched
+    #[test]
+    fn named_parameters_skips_literals_and_comments() {
+        let stmt = NamedStatement::new(
+            "SELECT ':no', \"@no\", x::text, @@ROWCOUNT, $tag$ :no $tag$ -- :no\n\
+             FROM t /* @no */ WHERE a = :yes",
+        );
+
+        assert_eq!(
+            stmt.sql(),
+            "SELECT ':no', \"@no\", x::text, @@ROWCOUNT, $tag$ :no $tag$ -- :no\n\
+             FROM t /* @no */ WHERE a = $1"
+        );
+        assert_eq!(stmt.names(), ["yes"]);
+    }
+
+    /// The `"quoted"` identifiers are left untouched, even with a colon inside
+    #[test]
+    fn named_parameters_skips_quoted_identifiers() {
+        let stmt = NamedStatement::new("SELECT \"a:b\", \"@c\"\"d\" FROM t WHERE \"a:b\" = :a");
+
+        assert_eq!(
+            stmt.sql(),
+            "SELECT \"a:b\", \"@c\"\"d\" FROM t WHERE \"a:b\" = $1"
+        );
+        assert_eq!(stmt.names(), ["a"]);
+    }
+
+    /// The `[bracketed]` identifiers of `SqlServer` are left untouched, but not
+    /// the subscripts of the `PostgreSQL` arrays
+    #[test]
+    fn named_parameters_skips_bracketed_identifiers() {
+        let stmt = NamedStatement::new(
+            "SELECT [:id], [@P1] FROM [dbo].[t] WHERE [:id] = :id AND tags[:idx] = $1",
+        );
+
+        assert_eq!(
+            stmt.sql(),
+            "SELECT [:id], [@P1] FROM [dbo].[t] WHERE [:id] = $1 AND tags[$2] = $1"
+        );
+        assert_eq!(stmt.names(), ["id", "idx"]);
+        assert_eq!(
+            to_sqlserver_placeholders("SELECT [$1] FROM t WHERE a = $1 AND b[$2] = 1"),
+            "SELECT [$1] FROM t WHERE a = @P1 AND b[@P2] = 1"
+        );
+    }
+
+    /// Only the real placeholders are rewritten for `SqlServer`
+    #[test]
+    fn positional_placeholders_to_sqlserver() {
+        assert_eq!(
+            to_sqlserver_placeholders(
+                "UPDATE t SET price = '$10', note = 'it''s $2' WHERE a = $1 AND b = $12"
+            ),
+            "UPDATE t SET price = '$10', note = 'it''s $2' WHERE a = @P1 AND b = @P12"
+        );
+    }
+
+    /// The placeholders of an embedded statement are renumbered
+    #[test]
+    fn shift_positional_placeholders() {
+        assert_eq!(
+            shift_placeholders("SELECT id FROM t WHERE a = $1 AND b <> '$1' OR c = $2", 3),
+            "SELECT id FROM t WHERE a = $4 AND b <> '$1' OR c = $5"
+        );
+    }
+
+    /// Every parameter must have a value, and every value a parameter
+    #[test]
+    fn bind_named_parameters() {
+        let stmt = NamedStatement::new("SELECT * FROM t WHERE a = :a AND b = :b");
+
+        let bound = stmt.bind(&[("b", &"x"), ("a", &1)]);
+        assert_eq!(bound.map(|values| values.len()).ok(), Some(2));
+
+        assert!(stmt.bind(&[("a", &1)]).is_err());
+        assert!(stmt.bind(&[("a", &1), ("b", &2), ("c", &3)]).is_err());
+    }
+
+    /// The values are inlined as literals for both kinds of positional placeholders
+    #[test]
+    fn inline_parameters_as_literals() {
+        let name: Option<String> = None;
+        let params: &[&dyn QueryParameter<'_>] = &[&1, &"it's", &name];
+
+        assert_eq!(
+            inline_params(
+                "SELECT * FROM t WHERE a = $1 AND b = $2 OR c = $3 -- $1",
+                params
+            ),
+            "SELECT * FROM t WHERE a = 1 AND b = 'it''s' OR c = NULL -- $1"
+        );
+        assert_eq!(
+            inline_params("UPDATE t SET b = @P2 WHERE a = @P1 AND c = @P4", params),
+            "UPDATE t SET b = 'it''s' WHERE a = 1 AND c = @P4"
+        );
+    }
+
+    /// The literals are masked, but not the rest of the statement
+    #[test]
+    fn sanitize_string_literals() {
+        assert_eq!(
+            sanitize_statement(
+                "SELECT * FROM t WHERE a = 'it''s secret' AND b = $1 AND \"c\" = 'x'"
+            ),
+            "SELECT * FROM t WHERE a = '?' AND b = $1 AND \"c\" = '?'"
+        );
+    }
+}
@@ -1,12 +1,23 @@
-use std::fmt::Debug;
+use std::{fmt::Debug, marker::PhantomData, sync::OnceLock};
 
 use crate::{
-    bounds::{FieldIdentifier, FieldValueIdentifier, QueryParameter},
+    bounds::{FieldIdentifier, FieldValueIdentifier, QueryParameter, ScalarValue, TableMetadata},
     crud::{CrudOperations, Transaction},
-    mapper::RowMapper,
-    query_elements::query::Query,
-    Operator,
+    explain::{explain, QueryPlan},
+    interceptor::entity_name,
+    mapper::{PrefixedRowMapper, RowMapper},
+    query_elements::{
+        dialect::{datasource_dialect, dialect, Dialect},
+        expr::Expr,
+        parameters::inline_params,
+        statement::{Clause, Computed, Delete, OrderKey, Predicate, Select, Statement, Update},
+    },
+    soft_delete::{alive_rows, SoftDelete, SoftDeleteScope},
+    timestamps::{ClientTime, TimestampSource},
+    tracking::changed_columns,
+    Aggregate, Condition, Nulls, Operator, Order,
 };
+use canyon_connection::canyon_database_connector::DatabaseType;
 
 /// Contains the elements that makes part of the formal declaration
 /// of the behaviour of the Canyon-SQL QueryBuilder
@@ -50,6 +61,15 @@ pub mod ops {
         /// with the same lifetime as self
         fn read_sql(&'a self) -> &'a str;
 
+        /// Generates the SQL of the query for the `database_type`, returning it
+        /// along with the values of its parameters, without executing anything
+        fn to_sql(&self, database_type: DatabaseType) -> (String, Vec<&'a dyn QueryParameter<'a>>);
+
+        /// Returns the SQL of the query for the database of its datasource, with
+        /// the values of the parameters inlined as literals. Intended for the logs,
+        /// not for being executed
+        fn debug_sql(&self) -> String;
+
         /// Public interface for append the content of an slice to the end of
         /// the underlying SQL sentece.
         ///
@@ -62,9 +82,9 @@ pub mod ops {
         /// Generates a `WHERE` SQL clause for constraint the query.
         ///
         /// * `column` - A [`FieldValueIdentifier`] that will provide the target
-        /// column name and the value for the filter
+        ///   column name and the value for the filter
         /// * `op` - Any element that implements [`Operator`] for create the comparison
-        /// or equality binary operator
+        ///   or equality binary operator
         fn r#where<Z: FieldValueIdentifier<'a, T>>(
             &mut self,
             column: Z,
@@ -76,9 +96,9 @@ pub mod ops {
         /// Generates an `AND` SQL clause for constraint the query.
         ///
         /// * `column` - A [`FieldValueIdentifier`] that will provide the target
-        /// column name and the value for the filter
+        ///   column name and the value for the filter
         /// * `op` - Any element that implements [`Operator`] for create the comparison
-        /// or equality binary operator
+        ///   or equality binary operator
         fn and<Z: FieldValueIdentifier<'a, T>>(
             &mut self,
             column: Z,
@@ -89,10 +109,10 @@ pub mod ops {
         /// the filter in conjunction with an `IN` operator that will ac
         ///
         /// * `column` - A [`FieldIdentifier`] that will provide the target
-        /// column name for the filter, based on the variant that represents
-        /// the field name that maps the targeted column name
+        ///   column name for the filter, based on the variant that represents
+        ///   the field name that maps the targeted column name
         /// * `values` - An array of [`QueryParameter`] with the values to filter
-        /// inside the `IN` operator
+        ///   inside the `IN` operator
         fn and_values_in<Z, Q>(&mut self, column: Z, values: &'a [Q]) -> &mut Self
         where
             Z: FieldIdentifier<T>,
@@ -102,10 +122,10 @@ pub mod ops {
         /// the filter in conjunction with an `IN` operator that will ac
         ///
         /// * `column` - A [`FieldIdentifier`] that will provide the target
-        /// column name for the filter, based on the variant that represents
-        /// the field name that maps the targeted column name
+        ///   column name for the filter, based on the variant that represents
+        ///   the field name that maps the targeted column name
         /// * `values` - An array of [`QueryParameter`] with the values to filter
-        /// inside the `IN` operator
+        ///   inside the `IN` operator
         fn or_values_in<Z, Q>(&mut self, r#or: Z, values: &'a [Q]) -> &mut Self
         where
             Z: FieldIdentifier<T>,
@@ -114,19 +134,128 @@ pub mod ops {
         /// Generates an `OR` SQL clause for constraint the query.
         ///
         /// * `column` - A [`FieldValueIdentifier`] that will provide the target
-        /// column name and the value for the filter
+        ///   column name and the value for the filter
         /// * `op` - Any element that implements [`Operator`] for create the comparison
-        /// or equality binary operator
+        ///   or equality binary operator
         fn or<Z: FieldValueIdentifier<'a, T>>(&mut self, column: Z, op: impl Operator)
             -> &mut Self;
 
-        /// Generates a `ORDER BY` SQL clause for constraint the query.
+        /// Generates an `AND` SQL clause for constraint the query that will create
+        /// the filter in conjunction with a `NOT IN` operator
+        ///
+        /// * `column` - A [`FieldIdentifier`] that will provide the target
+        ///   column name for the filter
+        /// * `values` - An array of [`QueryParameter`] with the values that
+        ///   the column must not match
+        fn and_not_values_in<Z, Q>(&mut self, column: Z, values: &'a [Q]) -> &mut Self
+        where
+            Z: FieldIdentifier<T>,
+            Q: QueryParameter<'a>;
+
+        /// Generates an `OR` SQL clause for constraint the query that will create
+        /// the filter in conjunction with a `NOT IN` operator
+        ///
+        /// * `column` - A [`FieldIdentifier`] that will provide the target
+        ///   column name for the filter
+        /// * `values` - An array of [`QueryParameter`] with the values that
+        ///   the column must not match
+        fn or_not_values_in<Z, Q>(&mut self, column: Z, values: &'a [Q]) -> &mut Self
+        where
+            Z: FieldIdentifier<T>,
+            Q: QueryParameter<'a>;
+
+        /// Generates a `WHERE` SQL clause with a [`Condition`] over a column, like
+        /// the ones that checks for `NULL` values or ranges
+        ///
+        /// * `column` - A [`FieldIdentifier`] that will provide the target
+        ///   column name for the filter
+        /// * `condition` - The [`Condition`] that the column must satisfy
+        fn where_condition<Z: FieldIdentifier<T>>(
+            &mut self,
+            column: Z,
+            condition: Condition<'a>,
+        ) -> &mut Self;
+
+        /// Generates an `AND` SQL clause with a [`Condition`] over a column
+        ///
+        /// * `column` - A [`FieldIdentifier`] that will provide the target
+        ///   column name for the filter
+        /// * `condition` - The [`Condition`] that the column must satisfy
+        fn and_condition<Z: FieldIdentifier<T>>(
+            &mut self,
+            column: Z,
+            condition: Condition<'a>,
+        ) -> &mut Self;
+
+        /// Generates an `OR` SQL clause with a [`Condition`] over a column
+        ///
+        /// * `column` - A [`FieldIdentifier`] that will provide the target
+        ///   column name for the filter
+        /// * `condition` - The [`Condition`] that the column must satisfy
+        fn or_condition<Z: FieldIdentifier<T>>(
+            &mut self,
+            column: Z,
+            condition: Condition<'a>,
+        ) -> &mut Self;
+
+        /// Generates a `WHERE` SQL clause with the predicates of the [`Expr`].
+        ///
+        /// Nothing is generated if the expression does not contain any condition
+        ///
+        /// * `expr` - The [`Expr`] that the rows must satisfy
+        fn where_expr(&mut self, expr: Expr<'a>) -> &mut Self;
+
+        /// Generates an `AND` SQL clause with the predicates of the [`Expr`],
+        /// grouped between parentheses
+        ///
+        /// * `expr` - The [`Expr`] that the rows must satisfy
+        fn and_expr(&mut self, expr: Expr<'a>) -> &mut Self;
+
+        /// Generates an `OR` SQL clause with the predicates of the [`Expr`],
+        /// grouped between parentheses
+        ///
+        /// * `expr` - The [`Expr`] that the rows must satisfy
+        fn or_expr(&mut self, expr: Expr<'a>) -> &mut Self;
+
+        /// Generates a `WHERE` SQL clause that checks that the column matches some
+        /// of the rows retrieved by the `subquery`, that must select only one column.
+        ///
+        /// The parameters of the subquery are merged and renumbered into the query
+        ///
+        /// * `column` - A [`FieldIdentifier`] that will provide the target
+        ///   column name for the filter
+        /// * `subquery` - The [`SelectQueryBuilder`] of the subquery
+        fn where_in_subquery<Z, U>(
+            &mut self,
+            column: Z,
+            subquery: &SelectQueryBuilder<'a, U>,
+        ) -> &mut Self
+        where
+            Z: FieldIdentifier<T>,
+            U: CrudOperations<U> + Transaction<U> + RowMapper<U>,
+        {
+            self.where_expr(Expr::in_subquery(column, subquery))
+        }
+
+        /// Generates a `WHERE EXISTS` SQL clause with the `subquery`.
+        ///
+        /// The parameters of the subquery are merged and renumbered into the query
+        ///
+        /// * `subquery` - The [`SelectQueryBuilder`] of the subquery
+        fn where_exists<U>(&mut self, subquery: &SelectQueryBuilder<'a, U>) -> &mut Self
+        where
+            U: CrudOperations<U> + Transaction<U> + RowMapper<U>,
+        {
+            self.where_expr(Expr::exists(subquery))
+        }
+
+        /// Generates a `ORDER BY` SQL clause for sort the results of the query.
+        /// Successive calls adds more keys to the same clause.
         ///
         /// * `order_by` - A [`FieldIdentifier`] that will provide the target
-        /// column name
-        /// * `desc` - a boolean indicating if the generated `ORDER_BY` must be
-        /// in ascending or descending order
-        fn order_by<Z: FieldIdentifier<T>>(&mut self, order_by: Z, desc: bool) -> &mut Self;
+        ///   column name
+        /// * `order` - The [`Order`] (ascending or descending) of the column
+        fn order_by<Z: FieldIdentifier<T>>(&mut self, order_by: Z, order: Order) -> &mut Self;
     }
 }
 
@@ -136,8 +265,14 @@ pub struct QueryBuilder<'a, T>
 where
     T: CrudOperations<T> + Transaction<T> + RowMapper<T>,
 {
-    query: Query<'a, T>,
+    statement: Statement,
+    /// The SQL of the statement rendered for the database of the datasource,
+    /// generated on the first read after the last change of the statement
+    sql: OnceLock<String>,
+    /// The values of the parameters of the statement
+    params: Vec<&'a dyn QueryParameter<'a>>,
     datasource_name: &'a str,
+    _phantom: PhantomData<T>,
 }
 
 unsafe impl<'a, T> Send for QueryBuilder<'a, T> where
@@ -154,10 +289,13 @@ where
     T: CrudOperations<T> + Transaction<T> + RowMapper<T>,
 {
     /// Returns a new instance of the [`QueryBuilder`]
-    pub fn new(query: Query<'a, T>, datasource_name: &'a str) -> Self {
+    pub fn new(statement: Statement, datasource_name: &'a str) -> Self {
         Self {
-            query,
+            statement,
+            sql: OnceLock::new(),
+            params: Vec::new(),
             datasource_name,
+            _phantom: PhantomData,
         }
     }
 
@@ -166,13 +304,11 @@ where
     #[allow(clippy::question_mark)]
     pub async fn query(
         &'a mut self,
-    ) -> Result<Vec<T>, Box<(dyn std::error::Error + Sync + Send + 'static)>> {
+    ) -> Result<Vec<T>, Box<dyn std::error::Error + Sync + Send + 'static>> {
         // Close the query, we are ready to go
-        self.query.sql.push(';');
-
         let result = T::query(
-            self.query.sql.clone(),
-            self.query.params.to_vec(),
+            format!("{};", self.sql()?),
+            self.params.to_vec(),
             self.datasource_name,
         )
         .await;
@@ -184,45 +320,160 @@ where
         }
     }
 
-    pub fn r#where<Z: FieldValueIdentifier<'a, T>>(&mut self, r#where: Z, op: impl Operator) {
-        let (column_name, value) = r#where.value();
+    /// Launches the generated query against the database targeted
+    /// by the selected datasource, mapping the results into instances
+    /// of the `R` type instead of `T`
+    #[allow(clippy::question_mark)]
+    pub async fn query_as<R: RowMapper<R>>(
+        &'a mut self,
+    ) -> Result<Vec<R>, Box<dyn std::error::Error + Sync + Send + 'static>> {
+        // Close the query, we are ready to go
+        let result = T::query(
+            format!("{};", self.sql()?),
+            self.params.to_vec(),
+            self.datasource_name,
+        )
+        .await?;
 
-        let where_ = String::from(" WHERE ")
-            + column_name
-            + op.as_str()
-            + "$"
-            + &(self.query.params.len() + 1).to_string();
+        Ok(result.get_entities_as::<R>())
+    }
 
-        self.query.sql.push_str(&where_);
-        self.query.params.push(value);
+    /// Launches the generated query against the database targeted
+    /// by the selected datasource, retrieving the value of the first
+    /// column of the first row returned
+    pub async fn query_scalar<V: ScalarValue>(
+        &'a mut self,
+    ) -> Result<Option<V>, Box<dyn std::error::Error + Sync + Send + 'static>> {
+        // Close the query, we are ready to go
+        T::query(
+            format!("{};", self.sql()?),
+            self.params.to_vec(),
+            self.datasource_name,
+        )
+        .await
+        .and_then(|result| result.get_scalar::<V>())
     }
 
-    pub fn and<Z: FieldValueIdentifier<'a, T>>(&mut self, r#and: Z, op: impl Operator) {
-        let (column_name, value) = r#and.value();
+    /// Returns the type of the database targeted by the datasource of the query
+    pub fn database_type(&self) -> Result<DatabaseType, Box<dyn std::error::Error + Sync + Send>> {
+        self.dialect().map(|dialect| dialect.database_type())
+    }
+
+    /// Returns the [`Dialect`] of the database targeted by the datasource of the query,
+    /// or an error if the datasource isn't declared in the configuration file
+    pub fn dialect(
+        &self,
+    ) -> Result<&'static dyn Dialect, Box<dyn std::error::Error + Sync + Send>> {
+        datasource_dialect(self.datasource_name)
+    }
+
+    /// The SQL of the query for the database targeted by its datasource.
+    ///
+    /// The SQL is rendered on the first read after a change of the query.
+    /// When the datasource can't be resolved (it isn't declared, or there's
+    /// no configuration file at all), it's rendered for `PostgreSQL`, while
+    /// launching the query reports the error instead
+    pub fn read_sql(&self) -> &str {
+        self.sql.get_or_init(|| {
+            self.statement
+                .render(self.datasource_name)
+                .unwrap_or_else(|_| self.statement.to_sql(dialect(DatabaseType::PostgreSql)))
+        })
+    }
 
-        let and_ = String::from(" AND ")
-            + column_name
-            + op.as_str()
-            + "$"
-            + &(self.query.params.len() + 1).to_string()
-            + " ";
+    /// Renders the SQL of the statement for the database targeted by the datasource,
+    /// failing if the datasource can't be resolved
+    fn sql(&self) -> Result<String, Box<dyn std::error::Error + Sync + Send>> {
+        self.statement.render(self.datasource_name)
+    }
+
+    /// Generates the SQL of the statement for the `database_type` and returns it
+    /// along with the values of its parameters
+    pub fn to_sql(&self, database_type: DatabaseType) -> (String, Vec<&'a dyn QueryParameter<'a>>) {
+        (
+            self.statement.to_sql(dialect(database_type)),
+            self.params.clone(),
+        )
+    }
+
+    /// The SQL of the query with the values of its parameters inlined as literals
+    pub fn debug_sql(&self) -> String {
+        inline_params(self.read_sql(), &self.params)
+    }
 
-        self.query.sql.push_str(&and_);
-        self.query.params.push(value);
+    /// Discards the SQL rendered before a change of the statement
+    fn invalidate_sql(&mut self) {
+        self.sql.take();
+    }
+
+    /// Appends the `clause` to the statement of the query
+    fn push_clause(&mut self, clause: Clause) {
+        self.statement
+            .clauses_mut()
+            .expect("The query builders only holds statements that accept clauses")
+            .push(clause);
+        self.invalidate_sql();
+    }
+
+    /// Appends raw SQL to the end of the query
+    pub fn push_sql(&mut self, sql: &str) {
+        self.push_clause(Clause::Raw(sql.to_string()));
+    }
+
+    pub fn r#where<Z: FieldValueIdentifier<'a, T>>(&mut self, r#where: Z, op: impl Operator) {
+        let (column_name, value) = r#where.value();
+        let predicate = self.comparison(column_name, op, value);
+        self.push_clause(Clause::Where(predicate));
+    }
+
+    pub fn and<Z: FieldValueIdentifier<'a, T>>(&mut self, r#and: Z, op: impl Operator) {
+        let (column_name, value) = r#and.value();
+        let predicate = self.comparison(column_name, op, value);
+        self.push_clause(Clause::And(predicate));
     }
 
     pub fn or<Z: FieldValueIdentifier<'a, T>>(&mut self, r#and: Z, op: impl Operator) {
         let (column_name, value) = r#and.value();
+        let predicate = self.comparison(column_name, op, value);
+        self.push_clause(Clause::Or(predicate));
+    }
 
-        let and_ = String::from(" OR ")
-            + column_name
-            + op.as_str()
-            + "$"
-            + &(self.query.params.len() + 1).to_string()
-            + " ";
+    /// Generates the comparison of the `column` against the `value`, that
+    /// becomes the next parameter of the query
+    fn comparison(
+        &mut self,
+        column: &str,
+        op: impl Operator,
+        value: &'a dyn QueryParameter<'a>,
+    ) -> Predicate {
+        self.params.push(value);
+        Predicate::Compare {
+            column: column.to_string(),
+            operator: op.as_str(),
+            case_insensitive: op.is_case_insensitive(),
+            value: self.params.len(),
+        }
+    }
 
-        self.query.sql.push_str(&and_);
-        self.query.params.push(value);
+    pub fn where_condition<Z: FieldIdentifier<T>>(&mut self, column: Z, condition: Condition<'a>) {
+        let predicate = self.condition(column.as_str(), condition);
+        self.push_clause(Clause::Where(predicate));
+    }
+
+    pub fn and_condition<Z: FieldIdentifier<T>>(&mut self, column: Z, condition: Condition<'a>) {
+        let predicate = self.condition(column.as_str(), condition);
+        self.push_clause(Clause::And(predicate));
+    }
+
+    pub fn or_condition<Z: FieldIdentifier<T>>(&mut self, column: Z, condition: Condition<'a>) {
+        let predicate = self.condition(column.as_str(), condition);
+        self.push_clause(Clause::Or(predicate));
+    }
+
+    fn condition(&mut self, column: &str, condition: Condition<'a>) -> Predicate {
+        let predicate = condition.predicate(column, self.params.len() + 1);
+        self.params.extend(condition.params());
+        predicate
     }
 
     pub fn and_values_in<Z, Q>(&mut self, r#and: Z, values: &'a [Q])
@@ -230,72 +481,114 @@ where
         Z: FieldIdentifier<T>,
         Q: QueryParameter<'a>,
     {
-        if values.is_empty() {
-            return;
-        }
-
-        self.query
-            .sql
-            .push_str(&format!(" AND {} IN (", r#and.as_str()));
-
-        let mut counter = 1;
-        values.iter().for_each(|qp| {
-            if values.len() != counter {
-                self.query
-                    .sql
-                    .push_str(&format!("${}, ", self.query.params.len()));
-                counter += 1;
-            } else {
-                self.query
-                    .sql
-                    .push_str(&format!("${}", self.query.params.len()));
-            }
-            self.query.params.push(qp)
-        });
+        self.push_values_in(Clause::And, r#and.as_str(), values, false);
+    }
 
-        self.query.sql.push_str(") ");
+    pub fn or_values_in<Z, Q>(&mut self, r#or: Z, values: &'a [Q])
+    where
+        Z: FieldIdentifier<T>,
+        Q: QueryParameter<'a>,
+    {
+        self.push_values_in(Clause::Or, r#or.as_str(), values, false);
     }
 
-    fn or_values_in<Z, Q>(&mut self, r#or: Z, values: &'a [Q])
+    pub fn and_not_values_in<Z, Q>(&mut self, r#and: Z, values: &'a [Q])
     where
         Z: FieldIdentifier<T>,
         Q: QueryParameter<'a>,
     {
-        if values.is_empty() {
-            return;
-        }
-
-        self.query
-            .sql
-            .push_str(&format!(" OR {} IN (", r#or.as_str()));
-
-        let mut counter = 1;
-        values.iter().for_each(|qp| {
-            if values.len() != counter {
-                self.query
-                    .sql
-                    .push_str(&format!("${}, ", self.query.params.len()));
-                counter += 1;
-            } else {
-                self.query
-                    .sql
-                    .push_str(&format!("${}", self.query.params.len()));
-            }
-            self.query.params.push(qp)
-        });
+        self.push_values_in(Clause::And, r#and.as_str(), values, true);
+    }
+
+    pub fn or_not_values_in<Z, Q>(&mut self, r#or: Z, values: &'a [Q])
+    where
+        Z: FieldIdentifier<T>,
+        Q: QueryParameter<'a>,
+    {
+        self.push_values_in(Clause::Or, r#or.as_str(), values, true);
+    }
+
+    fn push_values_in<Q: QueryParameter<'a>>(
+        &mut self,
+        clause: fn(Predicate) -> Clause,
+        column: &str,
+        values: &'a [Q],
+        negated: bool,
+    ) {
+        let first = self.params.len() + 1;
+        values.iter().for_each(|qp| self.params.push(qp));
+        self.push_clause(clause(Predicate::In {
+            column: column.to_string(),
+            values: (first..=self.params.len()).collect(),
+            negated,
+        }));
+    }
+
+    pub fn where_expr(&mut self, expr: Expr<'a>) {
+        self.push_expr(Clause::Where, expr);
+    }
+
+    pub fn and_expr(&mut self, expr: Expr<'a>) {
+        self.push_expr(Clause::And, expr);
+    }
+
+    pub fn or_expr(&mut self, expr: Expr<'a>) {
+        self.push_expr(Clause::Or, expr);
+    }
+
+    fn push_expr(&mut self, clause: fn(Predicate) -> Clause, expr: Expr<'a>) {
+        if let Some(predicate) = expr.to_predicate(&mut self.params) {
+            self.push_clause(clause(predicate));
+        }
+    }
 
-        self.query.sql.push_str(") ");
+    #[inline]
+    pub fn order_by<Z: FieldIdentifier<T>>(&mut self, order_by: Z, order: Order) {
+        self.push_order_by(order_by.as_str(), order, None);
     }
 
     #[inline]
-    pub fn order_by<Z: FieldIdentifier<T>>(&mut self, order_by: Z, desc: bool) {
-        self.query.sql.push_str(
-            &(format!(
-                " ORDER BY {}{}",
-                order_by.as_str(),
-                if desc { " DESC " } else { "" }
-            )),
-        );
+    pub fn order_by_nulls<Z: FieldIdentifier<T>>(
+        &mut self,
+        order_by: Z,
+        order: Order,
+        nulls: Nulls,
+    ) {
+        self.push_order_by(order_by.as_str(), order, Some(nulls));
+    }
+
+    /// Adds a key to the `ORDER BY` clause, that it's opened unless it's
+    /// already the last clause of the query
+    fn push_order_by(&mut self, expression: &str, order: Order, nulls: Option<Nulls>) {
+        let key = OrderKey {
+            expression: expression.to_string(),
+            order,
+            nulls,
+        };
+
+        let clauses = self
+            .statement
+            .clauses_mut()
+            .expect("The query builders only holds statements that accept clauses");
+        // The keys are merged into the same clause, even if other ones were pushed after it
+        match clauses.iter_mut().find_map(|clause| match clause {
+            Clause::OrderBy(keys) => Some(keys),
+            _ => None,
+        }) {
+            Some(keys) => {
+                keys.push(key);
+                self.invalidate_sql();
+            }
+            None => self.push_clause(Clause::OrderBy(vec![key])),
+        }
+    }
+
+    /// The *SELECT* statement of the query
+    fn select_mut(&mut self) -> &mut Select {
+        match &mut self.statement {
+            Statement::Select(select) => select,
+            _ => unreachable!("Only the select query builders changes the projection"),
+        }
     }
 }
 
@@ -305,6 +598,8 @@ where
     T: CrudOperations<T> + Transaction<T> + RowMapper<T>,
 {
     _inner: QueryBuilder<'a, T>,
+    /// The `#[soft_delete]` column of the entity, if any
+    soft_delete: Option<(&'static str, SoftDelete)>,
 }
 
 impl<'a, T> SelectQueryBuilder<'a, T>
@@ -314,10 +609,40 @@ where
     /// Generates a new public instance of the [`SelectQueryBuilder`]
     pub fn new(table_schema_data: &str, datasource_name: &'a str) -> Self {
         Self {
-            _inner: QueryBuilder::<T>::new(
-                Query::new(format!("SELECT * FROM {table_schema_data}")),
-                datasource_name,
-            ),
+            _inner: QueryBuilder::<T>::new(Select::new(table_schema_data).into(), datasource_name),
+            soft_delete: None,
+        }
+    }
+
+    /// Declares the `#[soft_delete]` column of the entity, so the query only
+    /// retrieves the rows that aren't deleted
+    #[doc(hidden)]
+    pub fn with_soft_delete(mut self, column: &'static str, soft_delete: SoftDelete) -> Self {
+        self.soft_delete = Some((column, soft_delete));
+        self.set_soft_delete_scope(SoftDeleteScope::Alive);
+        self
+    }
+
+    /// Retrieves the soft deleted rows along with the alive ones.
+    ///
+    /// Does nothing if the entity has no `#[soft_delete]` field
+    pub fn with_deleted(&mut self) -> &mut Self {
+        self.set_soft_delete_scope(SoftDeleteScope::WithDeleted);
+        self
+    }
+
+    /// Retrieves only the soft deleted rows.
+    ///
+    /// Does nothing if the entity has no `#[soft_delete]` field
+    pub fn only_deleted(&mut self) -> &mut Self {
+        self.set_soft_delete_scope(SoftDeleteScope::OnlyDeleted);
+        self
+    }
+
+    fn set_soft_delete_scope(&mut self, scope: SoftDeleteScope) {
+        if let Some((column, soft_delete)) = self.soft_delete {
+            self._inner.select_mut().scope = scope.predicate(column, soft_delete);
+            self._inner.invalidate_sql();
         }
     }
 
@@ -326,10 +651,275 @@ where
     #[inline]
     pub async fn query(
         &'a mut self,
-    ) -> Result<Vec<T>, Box<(dyn std::error::Error + Sync + Send + 'static)>> {
+    ) -> Result<Vec<T>, Box<dyn std::error::Error + Sync + Send + 'static>> {
         self._inner.query().await
     }
 
+    /// Launches the generated query to the database pointed by the
+    /// selected datasource, mapping every row into a `R` type.
+    ///
+    /// Useful when the query does not retrieve the columns of `T`, like
+    /// the ones that only selects some [`Self::columns`] or the ones that
+    /// groups the results with aggregate functions. `R` can be any type
+    /// deriving `CanyonMapper`, being an entity or not.
+    #[inline]
+    pub async fn query_as<R: RowMapper<R>>(
+        &'a mut self,
+    ) -> Result<Vec<R>, Box<dyn std::error::Error + Sync + Send + 'static>> {
+        self._inner.query_as::<R>().await
+    }
+
+    /// Selects only the provided columns, instead of all the columns of
+    /// the table. Successive calls adds more columns to the query.
+    ///
+    /// The results must be retrieved with [`Self::query_as`], into some type
+    /// that contains a field for every selected column
+    ///
+    /// * `columns` - The [`FieldIdentifier`]s of the columns to retrieve
+    pub fn columns<Z: FieldIdentifier<T>>(&mut self, columns: &[Z]) -> &mut Self {
+        self.extend_projection(columns.iter().map(|c| c.as_str().to_string()));
+        self
+    }
+
+    /// Adds an SQL expression to the columns retrieved by the query, under
+    /// the name of the `alias`, so it can be mapped into the field with the
+    /// same name of the type used in [`Self::query_as`]
+    ///
+    /// * `expression` - The raw SQL expression, p.e. `UPPER(name)`
+    /// * `alias` - The name of the resulting column
+    pub fn column_as(&mut self, expression: &str, alias: &str) -> &mut Self {
+        self.extend_projection([format!("{expression} AS {alias}")]);
+        self
+    }
+
+    /// Adds an aggregate function over the `column` to the columns retrieved
+    /// by the query, under the name of the `alias`, so the results can be mapped
+    /// into a type that contains a field with the same name with [`Self::query_as`]
+    ///
+    /// * `function` - The [`Aggregate`] function to apply
+    /// * `column` - A [`FieldIdentifier`] that will provide the target column name
+    /// * `alias` - The name of the resulting column
+    pub fn aggregate<Z: FieldIdentifier<T>>(
+        &mut self,
+        function: Aggregate,
+        column: Z,
+        alias: &str,
+    ) -> &mut Self {
+        self.extend_projection([format!("{} AS {alias}", function.as_sql(column.as_str()))]);
+        self
+    }
+
+    /// Generates a `GROUP BY` SQL clause with the provided columns, that
+    /// are also added to the columns retrieved by the query.
+    ///
+    /// * `columns` - The [`FieldIdentifier`]s of the columns to group by
+    pub fn group_by<Z: FieldIdentifier<T>>(&mut self, columns: &[Z]) -> &mut Self {
+        if columns.is_empty() {
+            return self;
+        }
+        let columns = columns
+            .iter()
+            .map(|c| c.as_str().to_string())
+            .collect::<Vec<String>>();
+
+        self.extend_projection(columns.clone());
+        self._inner.push_clause(Clause::GroupBy(columns));
+        self
+    }
+
+    /// Generates a `HAVING` SQL clause for constraint the groups of the query,
+    /// filtering by the result of an aggregate function. Further conditions
+    /// can be chained with the `and` and `or` methods.
+    ///
+    /// * `function` - The [`Aggregate`] function to apply
+    /// * `column` - A [`FieldIdentifier`] that will provide the target column name
+    /// * `op` - Any element that implements [`Operator`] for create the comparison
+    ///   or equality binary operator
+    /// * `value` - The [`QueryParameter`] to compare with
+    pub fn having<Z, Q>(
+        &mut self,
+        function: Aggregate,
+        column: Z,
+        op: impl Operator,
+        value: &'a Q,
+    ) -> &mut Self
+    where
+        Z: FieldIdentifier<T>,
+        Q: QueryParameter<'a>,
+    {
+        let having = self
+            ._inner
+            .comparison(&function.as_sql(column.as_str()), op, value);
+        self._inner.push_clause(Clause::Having(having));
+        self
+    }
+
+    /// Same as [`ops::QueryBuilder::order_by`], but placing the `NULL` values
+    /// of the column first or last
+    ///
+    /// * `order_by` - A [`FieldIdentifier`] that will provide the target column name
+    /// * `order` - The [`Order`] (ascending or descending) of the column
+    /// * `nulls` - Where the `NULL` values are placed. On `SqlServer`, that doesn't
+    ///   support `NULLS FIRST` or `NULLS LAST`, it's emulated with a `CASE`
+    pub fn order_by_nulls<Z: FieldIdentifier<T>>(
+        &mut self,
+        order_by: Z,
+        order: Order,
+        nulls: Nulls,
+    ) -> &mut Self {
+        self._inner.order_by_nulls(order_by, order, nulls);
+        self
+    }
+
+    /// Adds an SQL expression as a key of the `ORDER BY` clause
+    ///
+    /// * `expression` - The raw SQL expression, p.e. `LENGTH(name)`
+    /// * `order` - The [`Order`] (ascending or descending) of the expression
+    pub fn order_by_expr(&mut self, expression: &str, order: Order) -> &mut Self {
+        self._inner.push_order_by(expression, order, None);
+        self
+    }
+
+    /// Adds an aggregate function over the `column` as a key of the `ORDER BY`
+    /// clause, for sort the groups of the query
+    ///
+    /// * `function` - The [`Aggregate`] function to apply
+    /// * `column` - A [`FieldIdentifier`] that will provide the target column name
+    /// * `order` - The [`Order`] (ascending or descending) of the results
+    pub fn order_by_aggregate<Z: FieldIdentifier<T>>(
+        &mut self,
+        function: Aggregate,
+        column: Z,
+        order: Order,
+    ) -> &mut Self {
+        self._inner
+            .push_order_by(&function.as_sql(column.as_str()), order, None);
+        self
+    }
+
+    /// Counts the non `NULL` values of the `column` for the rows that
+    /// matches the query
+    pub async fn count<Z: FieldIdentifier<T>>(
+        &'a mut self,
+        column: Z,
+    ) -> Result<i64, Box<dyn std::error::Error + Sync + Send + 'static>> {
+        self.aggregate_scalar::<i64, Z>(Aggregate::Count, column)
+            .await
+            .map(|count| count.unwrap_or_default())
+    }
+
+    /// Sums the values of the `column` for the rows that matches the query,
+    /// returning [`None`] if there's no values to sum
+    pub async fn sum<V: ScalarValue, Z: FieldIdentifier<T>>(
+        &'a mut self,
+        column: Z,
+    ) -> Result<Option<V>, Box<dyn std::error::Error + Sync + Send + 'static>> {
+        self.aggregate_scalar::<V, Z>(Aggregate::Sum, column).await
+    }
+
+    /// Calculates the average of the values of the `column` for the rows that
+    /// matches the query, returning [`None`] if there's no values
+    pub async fn avg<Z: FieldIdentifier<T>>(
+        &'a mut self,
+        column: Z,
+    ) -> Result<Option<f64>, Box<dyn std::error::Error + Sync + Send + 'static>> {
+        self.aggregate_scalar::<f64, Z>(Aggregate::Avg, column)
+            .await
+    }
+
+    /// Retrieves the minimum value of the `column` for the rows that
+    /// matches the query, returning [`None`] if there's no values
+    pub async fn min<V: ScalarValue, Z: FieldIdentifier<T>>(
+        &'a mut self,
+        column: Z,
+    ) -> Result<Option<V>, Box<dyn std::error::Error + Sync + Send + 'static>> {
+        self.aggregate_scalar::<V, Z>(Aggregate::Min, column).await
+    }
+
+    /// Retrieves the maximum value of the `column` for the rows that
+    /// matches the query, returning [`None`] if there's no values
+    pub async fn max<V: ScalarValue, Z: FieldIdentifier<T>>(
+        &'a mut self,
+        column: Z,
+    ) -> Result<Option<V>, Box<dyn std::error::Error + Sync + Send + 'static>> {
+        self.aggregate_scalar::<V, Z>(Aggregate::Max, column).await
+    }
+
+    /// Replaces the columns retrieved by the query with the aggregate function
+    /// over the `column`, and launches it, retrieving the resulting value
+    async fn aggregate_scalar<V: ScalarValue, Z: FieldIdentifier<T>>(
+        &'a mut self,
+        function: Aggregate,
+        column: Z,
+    ) -> Result<Option<V>, Box<dyn std::error::Error + Sync + Send + 'static>> {
+        let aggregate = function.as_sql(column.as_str());
+        let aggregate = match V::SQL_TYPE {
+            Some(sql_type) if !matches!(function, Aggregate::Count | Aggregate::Avg) => {
+                format!("CAST({aggregate} AS {sql_type})")
+            }
+            _ => aggregate,
+        };
+        self._inner.select_mut().projection = vec![aggregate];
+        self._inner.invalidate_sql();
+
+        self._inner.query_scalar::<V>().await
+    }
+
+    /// Retrieves the plan that the database would follow to run the query,
+    /// with its exact statement and parameters, but without executing it
+    pub async fn explain(
+        &self,
+    ) -> Result<QueryPlan, Box<dyn std::error::Error + Sync + Send + 'static>> {
+        explain(
+            self._inner.sql()?,
+            &self._inner.params,
+            self._inner.datasource_name,
+            entity_name::<T>(),
+            false,
+        )
+        .await
+    }
+
+    /// Executes the query and retrieves the plan followed by the database,
+    /// with the real number of rows processed by every node
+    pub async fn explain_analyze(
+        &self,
+    ) -> Result<QueryPlan, Box<dyn std::error::Error + Sync + Send + 'static>> {
+        explain(
+            self._inner.sql()?,
+            &self._inner.params,
+            self._inner.datasource_name,
+            entity_name::<T>(),
+            true,
+        )
+        .await
+    }
+
+    /// The statement of the query and its parameters, for embed it into another query
+    pub(crate) fn statement_and_params(&self) -> (&Select, &[&'a dyn QueryParameter<'a>]) {
+        match &self._inner.statement {
+            Statement::Select(select) => (select, &self._inner.params),
+            _ => unreachable!("A select query builder always holds a select statement"),
+        }
+    }
+
+    /// Adds the `columns` to the ones retrieved by the query, replacing
+    /// the default of all the columns of the table
+    fn extend_projection(&mut self, columns: impl IntoIterator<Item = String>) {
+        self._inner.select_mut().projection.extend(columns);
+        self._inner.invalidate_sql();
+    }
+
+    /// Adds a join with the `join_table` to the query
+    fn push_join(&mut self, kind: &'static str, join_table: &str, col1: &str, col2: &str) {
+        self._inner.push_clause(Clause::Join {
+            kind,
+            table: join_table.to_string(),
+            on: format!("{col1} = {col2}"),
+            scope: None,
+        });
+    }
+
     /// Adds a *LEFT JOIN* SQL statement to the underlying
     /// [`Query`] holded by the [`QueryBuilder`], where:
     ///
@@ -337,76 +927,452 @@ where
     /// * `col1` - The left side of the ON operator for the join
     /// * `col2` - The right side of the ON operator for the join
     ///
-    /// > Note: The order on the column parameters is irrelevant
-    pub fn left_join(&mut self, join_table: &str, col1: &str, col2: &str) -> &mut Self {
-        self._inner
-            .query
-            .sql
-            .push_str(&format!(" LEFT JOIN {join_table} ON {col1} = {col2}"));
-        self
+    /// > Note: The order on the column parameters is irrelevant
+    pub fn left_join(&mut self, join_table: &str, col1: &str, col2: &str) -> &mut Self {
+        self.push_join("LEFT JOIN", join_table, col1, col2);
+        self
+    }
+
+    /// Adds a *RIGHT JOIN* SQL statement to the underlying
+    /// [`Query`] holded by the [`QueryBuilder`], where:
+    ///
+    /// * `join_table` - The table target of the join operation
+    /// * `col1` - The left side of the ON operator for the join
+    /// * `col2` - The right side of the ON operator for the join
+    ///
+    /// > Note: The order on the column parameters is irrelevant
+    pub fn inner_join(&mut self, join_table: &str, col1: &str, col2: &str) -> &mut Self {
+        self.push_join("INNER JOIN", join_table, col1, col2);
+        self
+    }
+
+    /// Adds a *RIGHT JOIN* SQL statement to the underlying
+    /// [`Query`] holded by the [`QueryBuilder`], where:
+    ///
+    /// * `join_table` - The table target of the join operation
+    /// * `col1` - The left side of the ON operator for the join
+    /// * `col2` - The right side of the ON operator for the join
+    ///
+    /// > Note: The order on the column parameters is irrelevant
+    pub fn right_join(&mut self, join_table: &str, col1: &str, col2: &str) -> &mut Self {
+        self.push_join("RIGHT JOIN", join_table, col1, col2);
+        self
+    }
+
+    /// Adds a *FULL JOIN* SQL statement to the underlying
+    /// [`Query`] holded by the [`QueryBuilder`], where:
+    ///
+    /// * `join_table` - The table target of the join operation
+    /// * `col1` - The left side of the ON operator for the join
+    /// * `col2` - The right side of the ON operator for the join
+    ///
+    /// > Note: The order on the column parameters is irrelevant
+    pub fn full_join(&mut self, join_table: &str, col1: &str, col2: &str) -> &mut Self {
+        self.push_join("FULL JOIN", join_table, col1, col2);
+        self
+    }
+
+    /// Joins the entity `U` with an *INNER JOIN*, through the `#[foreign_key]`
+    /// that relates both entities, mapping every row into a `(T, U)` tuple.
+    ///
+    /// Returns an error if there's no `#[foreign_key]` that relates both entities,
+    /// or if the query already has filters, projections, orders or a soft delete
+    /// scope, since the join generates a new query where the columns of both
+    /// tables are aliased to avoid name collisions
+    pub fn inner_join_entity<U>(
+        self,
+    ) -> Result<JoinSelectQueryBuilder<'a, T, U, Inner>, Box<dyn std::error::Error + Sync + Send>>
+    where
+        T: PrefixedRowMapper<T> + TableMetadata,
+        U: CrudOperations<U> + Transaction<U> + PrefixedRowMapper<U> + TableMetadata,
+    {
+        self.typed_join()
+    }
+
+    /// Joins the entity `U` with a *LEFT JOIN*, through the `#[foreign_key]`
+    /// that relates both entities, mapping every row into a `(T, Option<U>)` tuple.
+    ///
+    /// Returns an error if there's no `#[foreign_key]` that relates both entities,
+    /// or if the query already has filters, projections, orders or a soft delete
+    /// scope, since the join generates a new query where the columns of both
+    /// tables are aliased to avoid name collisions
+    pub fn left_join_entity<U>(
+        self,
+    ) -> Result<JoinSelectQueryBuilder<'a, T, U, Left>, Box<dyn std::error::Error + Sync + Send>>
+    where
+        T: PrefixedRowMapper<T> + TableMetadata,
+        U: CrudOperations<U> + Transaction<U> + PrefixedRowMapper<U> + TableMetadata,
+    {
+        self.typed_join()
+    }
+
+    /// Generates the [`JoinSelectQueryBuilder`] of a typed join, failing if the
+    /// query was already constrained, since its constraints would be lost
+    fn typed_join<U, J>(
+        self,
+    ) -> Result<JoinSelectQueryBuilder<'a, T, U, J>, Box<dyn std::error::Error + Sync + Send>>
+    where
+        T: PrefixedRowMapper<T> + TableMetadata,
+        U: CrudOperations<U> + Transaction<U> + PrefixedRowMapper<U> + TableMetadata,
+        J: JoinKind,
+    {
+        let (select, params) = self.statement_and_params();
+        let alive = self.soft_delete.and_then(|(column, soft_delete)| {
+            SoftDeleteScope::Alive.predicate(column, soft_delete)
+        });
+        if !select.clauses.is_empty()
+            || !select.projection.is_empty()
+            || !params.is_empty()
+            || select.scope != alive
+        {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!(
+                    "The join of the tables `{}` and `{}` must be generated before any filter, \
+                    projection, order or soft delete scope of the query",
+                    T::TABLE_NAME,
+                    U::TABLE_NAME
+                ),
+            )
+            .into());
+        }
+        JoinSelectQueryBuilder::new(self._inner.datasource_name)
+    }
+}
+
+/// The kind of the join of a [`JoinSelectQueryBuilder`], that determines
+/// how the joined entity it's retrieved
+pub trait JoinKind {
+    /// The SQL of the join
+    const SQL: &'static str;
+    /// The type of the joined entity in the results
+    type Joined<U>;
+
+    /// Wraps the joined entity, that it's [`None`] when the row has not
+    /// a related entity. Returns [`None`] if the join requires it
+    fn joined<U>(entity: Option<U>) -> Option<Self::Joined<U>>;
+}
+
+/// Marker for the *INNER JOIN*, where every row has the joined entity
+#[derive(Debug, Clone, Copy)]
+pub struct Inner;
+impl JoinKind for Inner {
+    const SQL: &'static str = "INNER JOIN";
+    type Joined<U> = U;
+
+    fn joined<U>(entity: Option<U>) -> Option<U> {
+        entity
+    }
+}
+
+/// Marker for the *LEFT JOIN*, where the joined entity may not exist
+#[derive(Debug, Clone, Copy)]
+pub struct Left;
+impl JoinKind for Left {
+    const SQL: &'static str = "LEFT JOIN";
+    type Joined<U> = Option<U>;
+
+    fn joined<U>(entity: Option<U>) -> Option<Option<U>> {
+        Some(entity)
+    }
+}
+
+/// Contains the operations of the *SELECT* SQL statements that joins two
+/// entities, `T` and `U`, related by a `#[foreign_key]` annotation in any
+/// of them.
+///
+/// The columns of both tables are aliased with a prefix, so the results
+/// can be mapped into `(T, U)` tuples (`(T, Option<U>)` for the left joins),
+/// and the filters are qualified with the table of the entity of the field.
+#[derive(Debug, Clone)]
+pub struct JoinSelectQueryBuilder<'a, T, U, J>
+where
+    T: CrudOperations<T> + Transaction<T> + RowMapper<T>,
+{
+    _inner: QueryBuilder<'a, T>,
+    _joined: PhantomData<(U, J)>,
+}
+
+impl<'a, T, U, J> JoinSelectQueryBuilder<'a, T, U, J>
+where
+    T: CrudOperations<T> + Transaction<T> + PrefixedRowMapper<T> + TableMetadata,
+    U: CrudOperations<U> + Transaction<U> + PrefixedRowMapper<U> + TableMetadata,
+    J: JoinKind,
+{
+    /// The prefix of the aliased columns of `T`
+    const LEFT_PREFIX: &'static str = "t0_";
+    /// The prefix of the aliased columns of `U`
+    const RIGHT_PREFIX: &'static str = "t1_";
+    /// The alias of the column that tells if the row has a joined entity
+    const JOINED_FLAG: &'static str = "canyon_joined";
+
+    /// Generates a new public instance of the [`JoinSelectQueryBuilder`]
+    ///
+    /// Returns an error if there's no `#[foreign_key]` that relates both entities
+    pub fn new(datasource_name: &'a str) -> Result<Self, Box<dyn std::error::Error + Sync + Send>> {
+        let Some((on, joined_column)) = Self::join_condition() else {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!(
+                    "There's no foreign key that relates the tables `{}` and `{}`",
+                    T::TABLE_NAME,
+                    U::TABLE_NAME
+                ),
+            )
+            .into());
+        };
+
+        let mut select = Select::new(T::TABLE_NAME);
+        select.projection = T::COLUMNS
+            .iter()
+            .map(|c| format!("{}.{c} AS {}{c}", T::TABLE_NAME, Self::LEFT_PREFIX))
+            .chain(
+                U::COLUMNS
+                    .iter()
+                    .map(|c| format!("{}.{c} AS {}{c}", U::TABLE_NAME, Self::RIGHT_PREFIX)),
+            )
+            .chain([format!(
+                "CASE WHEN {joined_column} IS NULL THEN 0 ELSE 1 END AS {}",
+                Self::JOINED_FLAG
+            )])
+            .collect();
+        // The soft deleted rows of both entities are left out, like on their own queries
+        select.clauses.push(Clause::Join {
+            kind: J::SQL,
+            table: U::TABLE_NAME.to_string(),
+            on,
+            scope: alive_rows::<U>(),
+        });
+        select.scope = alive_rows::<T>();
+
+        Ok(Self {
+            _inner: QueryBuilder::<T>::new(select.into(), datasource_name),
+            _joined: PhantomData,
+        })
+    }
+
+    /// Returns the `ON` condition of the join, and the column of `U` that it's
+    /// part of it, looking for a foreign key of `T` that points to `U`, or the reverse
+    fn join_condition() -> Option<(String, String)> {
+        let (t, u) = (T::TABLE_NAME, U::TABLE_NAME);
+
+        if let Some((column, _, referenced)) = T::FOREIGN_KEYS
+            .iter()
+            .find(|(_, table, _)| same_table(table, u))
+        {
+            let joined_column = format!("{u}.{referenced}");
+            return Some((format!("{t}.{column} = {joined_column}"), joined_column));
+        }
+
+        U::FOREIGN_KEYS
+            .iter()
+            .find(|(_, table, _)| same_table(table, t))
+            .map(|(column, _, referenced)| {
+                let joined_column = format!("{u}.{column}");
+                (format!("{joined_column} = {t}.{referenced}"), joined_column)
+            })
+    }
+
+    /// Launches the generated query to the database pointed by the
+    /// selected datasource, mapping every row into a tuple with
+    /// both entities
+    pub async fn query(
+        &'a mut self,
+    ) -> Result<Vec<(T, J::Joined<U>)>, Box<dyn std::error::Error + Sync + Send + 'static>> {
+        // Close the query, we are ready to go
+        let result = T::query(
+            format!("{};", self._inner.sql()?),
+            self._inner.params.to_vec(),
+            self._inner.datasource_name,
+        )
+        .await?;
+
+        let rows = match result.get_active_ds() {
+            DatabaseType::PostgreSql => result
+                .postgres
+                .iter()
+                .map(|row| {
+                    let joined = row.get::<&str, i32>(Self::JOINED_FLAG) == 1;
+                    (
+                        T::deserialize_postgresql_with_prefix(row, Self::LEFT_PREFIX),
+                        J::joined(joined.then(|| {
+                            U::deserialize_postgresql_with_prefix(row, Self::RIGHT_PREFIX)
+                        })),
+                    )
+                })
+                .collect::<Vec<_>>(),
+            DatabaseType::SqlServer => result
+                .sqlserver
+                .iter()
+                .map(|row| {
+                    let joined = row.get::<i32, &str>(Self::JOINED_FLAG) == Some(1);
+                    (
+                        T::deserialize_sqlserver_with_prefix(row, Self::LEFT_PREFIX),
+                        J::joined(joined.then(|| {
+                            U::deserialize_sqlserver_with_prefix(row, Self::RIGHT_PREFIX)
+                        })),
+                    )
+                })
+                .collect::<Vec<_>>(),
+        };
+
+        // A misconfigured join may retrieve a row without the entity that it requires
+        rows.into_iter()
+            .map(|(entity, joined)| match joined {
+                Some(joined) => Ok((entity, joined)),
+                None => Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!(
+                        "The {} of the tables `{}` and `{}` retrieved a row without the joined entity",
+                        J::SQL,
+                        T::TABLE_NAME,
+                        U::TABLE_NAME
+                    ),
+                )
+                .into()),
+            })
+            .collect()
+    }
+
+    /// Returns a read-only reference to the underlying SQL sentence
+    pub fn read_sql(&self) -> &str {
+        self._inner.read_sql()
+    }
+
+    /// Generates the SQL of the query for the `database_type`, returning it
+    /// along with the values of its parameters, without executing anything
+    pub fn to_sql(&self, database_type: DatabaseType) -> (String, Vec<&'a dyn QueryParameter<'a>>) {
+        self._inner.to_sql(database_type)
+    }
+
+    /// Returns the SQL of the query with the values of the parameters inlined
+    /// as literals. Intended for the logs, not for being executed
+    pub fn debug_sql(&self) -> String {
+        self._inner.debug_sql()
+    }
+
+    /// Generates a `WHERE` SQL clause for constraint the query, over a column
+    /// of any of the joined entities
+    ///
+    /// * `column` - A [`FieldValueIdentifier`] of `T` or `U` that will provide
+    ///   the target column name and the value for the filter
+    /// * `op` - Any element that implements [`Operator`] for create the comparison
+    ///   or equality binary operator
+    pub fn r#where<E, Z>(&mut self, column: Z, op: impl Operator) -> &mut Self
+    where
+        E: CrudOperations<E> + Transaction<E> + RowMapper<E> + TableMetadata,
+        Z: FieldValueIdentifier<'a, E>,
+    {
+        self.push_filter::<E, Z>(Clause::Where, column, op)
+    }
+
+    /// Generates an `AND` SQL clause for constraint the query, over a column
+    /// of any of the joined entities
+    ///
+    /// * `column` - A [`FieldValueIdentifier`] of `T` or `U` that will provide
+    ///   the target column name and the value for the filter
+    /// * `op` - Any element that implements [`Operator`] for create the comparison
+    ///   or equality binary operator
+    pub fn and<E, Z>(&mut self, column: Z, op: impl Operator) -> &mut Self
+    where
+        E: CrudOperations<E> + Transaction<E> + RowMapper<E> + TableMetadata,
+        Z: FieldValueIdentifier<'a, E>,
+    {
+        self.push_filter::<E, Z>(Clause::And, column, op)
     }
 
-    /// Adds a *RIGHT JOIN* SQL statement to the underlying
-    /// [`Query`] holded by the [`QueryBuilder`], where:
+    /// Generates an `OR` SQL clause for constraint the query, over a column
+    /// of any of the joined entities
     ///
-    /// * `join_table` - The table target of the join operation
-    /// * `col1` - The left side of the ON operator for the join
-    /// * `col2` - The right side of the ON operator for the join
+    /// * `column` - A [`FieldValueIdentifier`] of `T` or `U` that will provide
+    ///   the target column name and the value for the filter
+    /// * `op` - Any element that implements [`Operator`] for create the comparison
+    ///   or equality binary operator
+    pub fn or<E, Z>(&mut self, column: Z, op: impl Operator) -> &mut Self
+    where
+        E: CrudOperations<E> + Transaction<E> + RowMapper<E> + TableMetadata,
+        Z: FieldValueIdentifier<'a, E>,
+    {
+        self.push_filter::<E, Z>(Clause::Or, column, op)
+    }
+
+    /// Generates a `ORDER BY` SQL clause, over a column of any of the joined entities.
+    /// Successive calls adds more keys to the same clause.
     ///
-    /// > Note: The order on the column parameters is irrelevant
-    pub fn inner_join(&mut self, join_table: &str, col1: &str, col2: &str) -> &mut Self {
-        self._inner
-            .query
-            .sql
-            .push_str(&format!(" INNER JOIN {join_table} ON {col1} = {col2}"));
+    /// * `order_by` - A [`FieldIdentifier`] of `T` or `U` that will provide the
+    ///   target column name
+    /// * `order` - The [`Order`] (ascending or descending) of the column
+    pub fn order_by<E, Z>(&mut self, order_by: Z, order: Order) -> &mut Self
+    where
+        E: CrudOperations<E> + Transaction<E> + RowMapper<E> + TableMetadata,
+        Z: FieldIdentifier<E>,
+    {
+        let column = format!("{}.{}", E::TABLE_NAME, order_by.as_str());
+        self._inner.push_order_by(&column, order, None);
         self
     }
 
-    /// Adds a *RIGHT JOIN* SQL statement to the underlying
-    /// [`Query`] holded by the [`QueryBuilder`], where:
-    ///
-    /// * `join_table` - The table target of the join operation
-    /// * `col1` - The left side of the ON operator for the join
-    /// * `col2` - The right side of the ON operator for the join
+    /// Same as [`Self::order_by`], but placing the `NULL` values of the column
+    /// first or last
     ///
-    /// > Note: The order on the column parameters is irrelevant
-    pub fn right_join(&mut self, join_table: &str, col1: &str, col2: &str) -> &mut Self {
-        self._inner
-            .query
-            .sql
-            .push_str(&format!(" RIGHT JOIN {join_table} ON {col1} = {col2}"));
+    /// * `nulls` - Where the `NULL` values are placed. Emulated on `SqlServer`
+    pub fn order_by_nulls<E, Z>(&mut self, order_by: Z, order: Order, nulls: Nulls) -> &mut Self
+    where
+        E: CrudOperations<E> + Transaction<E> + RowMapper<E> + TableMetadata,
+        Z: FieldIdentifier<E>,
+    {
+        let column = format!("{}.{}", E::TABLE_NAME, order_by.as_str());
+        self._inner.push_order_by(&column, order, Some(nulls));
         self
     }
 
-    /// Adds a *FULL JOIN* SQL statement to the underlying
-    /// [`Query`] holded by the [`QueryBuilder`], where:
-    ///
-    /// * `join_table` - The table target of the join operation
-    /// * `col1` - The left side of the ON operator for the join
-    /// * `col2` - The right side of the ON operator for the join
-    ///
-    /// > Note: The order on the column parameters is irrelevant
-    pub fn full_join(&mut self, join_table: &str, col1: &str, col2: &str) -> &mut Self {
-        self._inner
-            .query
-            .sql
-            .push_str(&format!(" FULL JOIN {join_table} ON {col1} = {col2}"));
+    fn push_filter<E, Z>(
+        &mut self,
+        clause: fn(Predicate) -> Clause,
+        column: Z,
+        op: impl Operator,
+    ) -> &mut Self
+    where
+        E: CrudOperations<E> + Transaction<E> + RowMapper<E> + TableMetadata,
+        Z: FieldValueIdentifier<'a, E>,
+    {
+        let (column_name, value) = column.value();
+        let comparison =
+            self._inner
+                .comparison(&format!("{}.{column_name}", E::TABLE_NAME), op, value);
+        self._inner.push_clause(clause(comparison));
         self
     }
 }
 
+/// Compares the name of two tables, ignoring the schema and the case
+pub(crate) fn same_table(a: &str, b: &str) -> bool {
+    let table = |name: &str| name.rsplit('.').next().unwrap_or(name).to_lowercase();
+    table(a) == table(b)
+}
+
 impl<'a, T> ops::QueryBuilder<'a, T> for SelectQueryBuilder<'a, T>
 where
     T: Debug + CrudOperations<T> + Transaction<T> + RowMapper<T> + Send,
 {
     #[inline]
     fn read_sql(&'a self) -> &'a str {
-        self._inner.query.sql.as_str()
+        self._inner.read_sql()
+    }
+
+    #[inline]
+    fn to_sql(&self, database_type: DatabaseType) -> (String, Vec<&'a dyn QueryParameter<'a>>) {
+        self._inner.to_sql(database_type)
+    }
+
+    #[inline]
+    fn debug_sql(&self) -> String {
+        self._inner.debug_sql()
     }
 
     #[inline(always)]
     fn push_sql(&mut self, sql: &str) {
-        self._inner.query.sql.push_str(sql);
+        self._inner.push_sql(sql);
     }
 
     #[inline]
@@ -452,8 +1418,76 @@ where
     }
 
     #[inline]
-    fn order_by<Z: FieldIdentifier<T>>(&mut self, order_by: Z, desc: bool) -> &mut Self {
-        self._inner.order_by(order_by, desc);
+    fn and_not_values_in<Z, Q>(&mut self, r#and: Z, values: &'a [Q]) -> &mut Self
+    where
+        Z: FieldIdentifier<T>,
+        Q: QueryParameter<'a>,
+    {
+        self._inner.and_not_values_in(and, values);
+        self
+    }
+
+    #[inline]
+    fn or_not_values_in<Z, Q>(&mut self, r#or: Z, values: &'a [Q]) -> &mut Self
+    where
+        Z: FieldIdentifier<T>,
+        Q: QueryParameter<'a>,
+    {
+        self._inner.or_not_values_in(or, values);
+        self
+    }
+
+    #[inline]
+    fn where_condition<Z: FieldIdentifier<T>>(
+        &mut self,
+        column: Z,
+        condition: Condition<'a>,
+    ) -> &mut Self {
+        self._inner.where_condition(column, condition);
+        self
+    }
+
+    #[inline]
+    fn and_condition<Z: FieldIdentifier<T>>(
+        &mut self,
+        column: Z,
+        condition: Condition<'a>,
+    ) -> &mut Self {
+        self._inner.and_condition(column, condition);
+        self
+    }
+
+    #[inline]
+    fn or_condition<Z: FieldIdentifier<T>>(
+        &mut self,
+        column: Z,
+        condition: Condition<'a>,
+    ) -> &mut Self {
+        self._inner.or_condition(column, condition);
+        self
+    }
+
+    #[inline]
+    fn where_expr(&mut self, expr: Expr<'a>) -> &mut Self {
+        self._inner.where_expr(expr);
+        self
+    }
+
+    #[inline]
+    fn and_expr(&mut self, expr: Expr<'a>) -> &mut Self {
+        self._inner.and_expr(expr);
+        self
+    }
+
+    #[inline]
+    fn or_expr(&mut self, expr: Expr<'a>) -> &mut Self {
+        self._inner.or_expr(expr);
+        self
+    }
+
+    #[inline]
+    fn order_by<Z: FieldIdentifier<T>>(&mut self, order_by: Z, order: Order) -> &mut Self {
+        self._inner.order_by(order_by, order);
         self
     }
 }
@@ -461,13 +1495,19 @@ where
 /// Contains the specific database operations of the *UPDATE* SQL statements.
 ///  
 /// * `set` - To construct a new `SET` clause to determine the columns to
-/// update with the provided values
+///   update with the provided values
 #[derive(Debug, Clone)]
 pub struct UpdateQueryBuilder<'a, T>
 where
     T: CrudOperations<T> + Transaction<T> + RowMapper<T>,
 {
     _inner: QueryBuilder<'a, T>,
+    /// The `#[updated_at]` columns of the entity, assigned on every `set`
+    updated_at: Vec<(&'static str, TimestampSource)>,
+    /// If the assignments comes from the `changes` of an entity, so `set` can't add more
+    from_changes: bool,
+    /// The misuse of the builder found while building it, returned when it's launched
+    invalid: Option<&'static str>,
 }
 
 impl<'a, T> UpdateQueryBuilder<'a, T>
@@ -478,22 +1518,195 @@ where
     pub fn new(table_schema_data: &str, datasource_name: &'a str) -> Self {
         Self {
             _inner: QueryBuilder::<T>::new(
-                Query::new(format!("UPDATE {table_schema_data}")),
+                Update::new(table_schema_data, &[]).into(),
                 datasource_name,
             ),
+            updated_at: Vec::new(),
+            from_changes: false,
+            invalid: None,
         }
     }
 
+    /// Declares an `#[updated_at]` column of the entity, that the `set` assigns
+    /// to the current time of the `source`, unless it's explicitly set
+    #[doc(hidden)]
+    pub fn with_updated_at(mut self, column: &'static str, source: TimestampSource) -> Self {
+        self.updated_at.push((column, source));
+        self
+    }
+
     /// Launches the generated query to the database pointed by the
-    /// selected datasource
+    /// selected datasource.
+    ///
+    /// Returns an error without querying the database if the update has no column
+    /// to assign, unless it comes from the `changes` of an entity without any
     #[inline]
     pub async fn query(
         &'a mut self,
-    ) -> Result<Vec<T>, Box<(dyn std::error::Error + Sync + Send + 'static)>> {
+    ) -> Result<Vec<T>, Box<dyn std::error::Error + Sync + Send + 'static>> {
+        if !self.should_launch()? {
+            return Ok(Vec::new());
+        }
         self._inner.query().await
     }
 
+    /// Launches the generated query to the database pointed by the selected
+    /// datasource, returning the number of updated rows.
+    ///
+    /// Returns an error without querying the database if the update has no column
+    /// to assign, unless it comes from the `changes` of an entity without any
+    pub async fn execute(&self) -> Result<u64, Box<dyn std::error::Error + Sync + Send + 'static>> {
+        if !self.should_launch()? {
+            return Ok(0);
+        }
+        T::execute(
+            self._inner.sql()?,
+            self._inner.params.to_vec(),
+            self._inner.datasource_name,
+        )
+        .await
+    }
+
+    /// Assigns the columns whose values changed between the `old` and the `new`
+    /// instances of the entity to the ones of `new`, filtering the row by its
+    /// primary key.
+    ///
+    /// The versioned entities also filters the row by the version of `old`, and moves
+    /// it forward by one. Nothing is assigned if there's no changes.
+    ///
+    /// Returns an error if the entity has no primary key, or if the update already
+    /// assigns or filters something, since it can't be combined with `set` or with
+    /// another call to `changes`
+    pub fn changes(
+        &mut self,
+        old: &'a T,
+        new: &'a T,
+    ) -> Result<&mut Self, Box<dyn std::error::Error + Sync + Send>>
+    where
+        T: TableMetadata,
+    {
+        let invalid = |msg: String| -> Box<dyn std::error::Error + Sync + Send> {
+            std::io::Error::new(std::io::ErrorKind::InvalidInput, msg).into()
+        };
+        if T::PRIMARY_KEY.is_empty() {
+            return Err(invalid(format!(
+                "The entity `{}` has no primary key to filter the changed row",
+                T::TABLE_NAME
+            )));
+        }
+        let update = match &mut self._inner.statement {
+            Statement::Update(update) => update,
+            _ => unreachable!("An update query builder always holds an update statement"),
+        };
+        if self.from_changes
+            || !update.assignments.is_empty()
+            || !update.computed.is_empty()
+            || !update.clauses.is_empty()
+        {
+            return Err(invalid(String::from(
+                "The changes of an entity can't be combined with other assignments \
+                or filters on the same update",
+            )));
+        }
+        self.from_changes = true;
+
+        let columns = changed_columns(old, new);
+        if columns.is_empty() {
+            return Ok(self);
+        }
+        let value_of = |entity: &'a T, column: &str| {
+            entity.column_value(column).ok_or_else(|| {
+                invalid(format!(
+                    "The entity `{}` has no value for the column `{column}`",
+                    T::TABLE_NAME
+                ))
+            })
+        };
+        let params = &mut self._inner.params;
+
+        for column in &columns {
+            params.push(value_of(new, column)?);
+            update.assignments.push((column.to_string(), params.len()));
+        }
+        Self::assign_updated_at(update, params, &self.updated_at);
+
+        let mut conditions = Vec::new();
+        for column in T::PRIMARY_KEY {
+            params.push(value_of(new, column)?);
+            conditions.push((*column, params.len()));
+        }
+        if let Some(version) = T::VERSION {
+            params.push(value_of(old, version)?);
+            conditions.push((version, params.len()));
+            update.computed.push((
+                version.to_string(),
+                Computed::Increment(version.to_string()),
+            ));
+        }
+        update
+            .clauses
+            .push(Clause::Where(Predicate::all_equal(&conditions)));
+
+        self._inner.invalidate_sql();
+        Ok(self)
+    }
+
+    /// Assigns the `#[updated_at]` columns that aren't already assigned to the
+    /// current time of their source, binding the one of the client as a parameter
+    fn assign_updated_at(
+        update: &mut Update,
+        params: &mut Vec<&'a dyn QueryParameter<'a>>,
+        updated_at: &[(&'static str, TimestampSource)],
+    ) {
+        for (column, source) in updated_at {
+            if update
+                .assignments
+                .iter()
+                .any(|(assigned, _)| assigned == column)
+            {
+                continue;
+            }
+            match source {
+                TimestampSource::Client => {
+                    params.push(&ClientTime);
+                    update.assignments.push((column.to_string(), params.len()));
+                }
+                TimestampSource::Database => update
+                    .computed
+                    .push((column.to_string(), Computed::CurrentTimestamp)),
+            }
+        }
+    }
+
+    /// Returns true if the statement has to be launched, false if it comes from the
+    /// `changes` of an entity without any, or an error if the builder was misused or
+    /// the statement doesn't assign any column
+    fn should_launch(&self) -> Result<bool, Box<dyn std::error::Error + Sync + Send + 'static>> {
+        let invalid = |msg: &str| -> Box<dyn std::error::Error + Sync + Send> {
+            std::io::Error::new(std::io::ErrorKind::InvalidInput, msg).into()
+        };
+        if let Some(msg) = self.invalid {
+            return Err(invalid(msg));
+        }
+        let has_assignments = match &self._inner.statement {
+            Statement::Update(update) => {
+                !update.assignments.is_empty() || !update.computed.is_empty()
+            }
+            _ => unreachable!("An update query builder always holds an update statement"),
+        };
+        if has_assignments {
+            Ok(true)
+        } else if self.from_changes {
+            Ok(false)
+        } else {
+            Err(invalid("The update has no column to assign"))
+        }
+    }
+
     /// Creates an SQL `SET` clause to especify the columns that must be updated in the sentence
+    ///
+    /// It can't be combined with the `changes` of an entity. The update fails with
+    /// an error when it's launched if they're combined
     pub fn set<Z, Q>(&mut self, columns: &'a [(Z, Q)]) -> &mut Self
     where
         Z: FieldIdentifier<T> + Clone,
@@ -502,7 +1715,18 @@ where
         if columns.is_empty() {
             return self;
         }
-        if self._inner.query.sql.contains("SET") {
+        let assignments = match &mut self._inner.statement {
+            Statement::Update(update) => &mut update.assignments,
+            _ => unreachable!("An update query builder always holds an update statement"),
+        };
+        if self.from_changes {
+            self.invalid = Some(
+                "The changes of an entity can't be combined with other assignments \
+                or filters on the same update",
+            );
+            return self;
+        }
+        if !assignments.is_empty() {
             panic!(
                 "\n{}",
                 String::from("\t[PANIC!] - Don't use chained calls of the .set(...) method. ")
@@ -511,24 +1735,15 @@ where
             )
         }
 
-        let cap = columns.len() * 50; // Reserving an enough initial capacity per set clause
-        let mut set_clause = String::with_capacity(cap);
-        set_clause.push_str(" SET ");
-
-        for (idx, column) in columns.iter().enumerate() {
-            set_clause.push_str(&format!(
-                "{} = ${}",
-                column.0.as_str(),
-                self._inner.query.params.len() + 1
-            ));
-
-            if idx < columns.len() - 1 {
-                set_clause.push_str(", ");
-            }
-            self._inner.query.params.push(&column.1);
+        for (column, value) in columns.iter() {
+            self._inner.params.push(value);
+            assignments.push((column.as_str().to_string(), self._inner.params.len()));
+        }
+        if let Statement::Update(update) = &mut self._inner.statement {
+            Self::assign_updated_at(update, &mut self._inner.params, &self.updated_at);
         }
 
-        self._inner.query.sql.push_str(&set_clause);
+        self._inner.invalidate_sql();
         self
     }
 }
@@ -539,12 +1754,22 @@ where
 {
     #[inline]
     fn read_sql(&'a self) -> &'a str {
-        self._inner.query.sql.as_str()
+        self._inner.read_sql()
+    }
+
+    #[inline]
+    fn to_sql(&self, database_type: DatabaseType) -> (String, Vec<&'a dyn QueryParameter<'a>>) {
+        self._inner.to_sql(database_type)
+    }
+
+    #[inline]
+    fn debug_sql(&self) -> String {
+        self._inner.debug_sql()
     }
 
     #[inline(always)]
     fn push_sql(&mut self, sql: &str) {
-        self._inner.query.sql.push_str(sql);
+        self._inner.push_sql(sql);
     }
 
     #[inline]
@@ -590,8 +1815,76 @@ where
     }
 
     #[inline]
-    fn order_by<Z: FieldIdentifier<T>>(&mut self, order_by: Z, desc: bool) -> &mut Self {
-        self._inner.order_by(order_by, desc);
+    fn and_not_values_in<Z, Q>(&mut self, r#and: Z, values: &'a [Q]) -> &mut Self
+    where
+        Z: FieldIdentifier<T>,
+        Q: QueryParameter<'a>,
+    {
+        self._inner.and_not_values_in(and, values);
+        self
+    }
+
+    #[inline]
+    fn or_not_values_in<Z, Q>(&mut self, r#or: Z, values: &'a [Q]) -> &mut Self
+    where
+        Z: FieldIdentifier<T>,
+        Q: QueryParameter<'a>,
+    {
+        self._inner.or_not_values_in(or, values);
+        self
+    }
+
+    #[inline]
+    fn where_condition<Z: FieldIdentifier<T>>(
+        &mut self,
+        column: Z,
+        condition: Condition<'a>,
+    ) -> &mut Self {
+        self._inner.where_condition(column, condition);
+        self
+    }
+
+    #[inline]
+    fn and_condition<Z: FieldIdentifier<T>>(
+        &mut self,
+        column: Z,
+        condition: Condition<'a>,
+    ) -> &mut Self {
+        self._inner.and_condition(column, condition);
+        self
+    }
+
+    #[inline]
+    fn or_condition<Z: FieldIdentifier<T>>(
+        &mut self,
+        column: Z,
+        condition: Condition<'a>,
+    ) -> &mut Self {
+        self._inner.or_condition(column, condition);
+        self
+    }
+
+    #[inline]
+    fn where_expr(&mut self, expr: Expr<'a>) -> &mut Self {
+        self._inner.where_expr(expr);
+        self
+    }
+
+    #[inline]
+    fn and_expr(&mut self, expr: Expr<'a>) -> &mut Self {
+        self._inner.and_expr(expr);
+        self
+    }
+
+    #[inline]
+    fn or_expr(&mut self, expr: Expr<'a>) -> &mut Self {
+        self._inner.or_expr(expr);
+        self
+    }
+
+    #[inline]
+    fn order_by<Z: FieldIdentifier<T>>(&mut self, order_by: Z, order: Order) -> &mut Self {
+        self._inner.order_by(order_by, order);
         self
     }
 }
@@ -600,7 +1893,7 @@ where
 /// *DELETE* SQL statements.
 ///  
 /// * `set` - To construct a new `SET` clause to determine the columns to
-/// update with the provided values
+///   update with the provided values
 #[derive(Debug, Clone)]
 pub struct DeleteQueryBuilder<'a, T>
 where
@@ -616,10 +1909,7 @@ where
     /// Generates a new public instance of the [`DeleteQueryBuilder`]
     pub fn new(table_schema_data: &str, datasource_name: &'a str) -> Self {
         Self {
-            _inner: QueryBuilder::<T>::new(
-                Query::new(format!("DELETE FROM {table_schema_data}")),
-                datasource_name,
-            ),
+            _inner: QueryBuilder::<T>::new(Delete::new(table_schema_data).into(), datasource_name),
         }
     }
 
@@ -628,7 +1918,7 @@ where
     #[inline]
     pub async fn query(
         &'a mut self,
-    ) -> Result<Vec<T>, Box<(dyn std::error::Error + Sync + Send + 'static)>> {
+    ) -> Result<Vec<T>, Box<dyn std::error::Error + Sync + Send + 'static>> {
         self._inner.query().await
     }
 }
@@ -639,12 +1929,22 @@ where
 {
     #[inline]
     fn read_sql(&'a self) -> &'a str {
-        self._inner.query.sql.as_str()
+        self._inner.read_sql()
+    }
+
+    #[inline]
+    fn to_sql(&self, database_type: DatabaseType) -> (String, Vec<&'a dyn QueryParameter<'a>>) {
+        self._inner.to_sql(database_type)
+    }
+
+    #[inline]
+    fn debug_sql(&self) -> String {
+        self._inner.debug_sql()
     }
 
     #[inline(always)]
     fn push_sql(&mut self, sql: &str) {
-        self._inner.query.sql.push_str(sql);
+        self._inner.push_sql(sql);
     }
 
     #[inline]
@@ -669,7 +1969,7 @@ where
         Z: FieldIdentifier<T>,
         Q: QueryParameter<'a>,
     {
-        self._inner.or_values_in(and, values);
+        self._inner.and_values_in(and, values);
         self
     }
 
@@ -690,8 +1990,76 @@ where
     }
 
     #[inline]
-    fn order_by<Z: FieldIdentifier<T>>(&mut self, order_by: Z, desc: bool) -> &mut Self {
-        self._inner.order_by(order_by, desc);
+    fn and_not_values_in<Z, Q>(&mut self, r#and: Z, values: &'a [Q]) -> &mut Self
+    where
+        Z: FieldIdentifier<T>,
+        Q: QueryParameter<'a>,
+    {
+        self._inner.and_not_values_in(and, values);
+        self
+    }
+
+    #[inline]
+    fn or_not_values_in<Z, Q>(&mut self, r#or: Z, values: &'a [Q]) -> &mut Self
+    where
+        Z: FieldIdentifier<T>,
+        Q: QueryParameter<'a>,
+    {
+        self._inner.or_not_values_in(or, values);
+        self
+    }
+
+    #[inline]
+    fn where_condition<Z: FieldIdentifier<T>>(
+        &mut self,
+        column: Z,
+        condition: Condition<'a>,
+    ) -> &mut Self {
+        self._inner.where_condition(column, condition);
+        self
+    }
+
+    #[inline]
+    fn and_condition<Z: FieldIdentifier<T>>(
+        &mut self,
+        column: Z,
+        condition: Condition<'a>,
+    ) -> &mut Self {
+        self._inner.and_condition(column, condition);
+        self
+    }
+
+    #[inline]
+    fn or_condition<Z: FieldIdentifier<T>>(
+        &mut self,
+        column: Z,
+        condition: Condition<'a>,
+    ) -> &mut Self {
+        self._inner.or_condition(column, condition);
+        self
+    }
+
+    #[inline]
+    fn where_expr(&mut self, expr: Expr<'a>) -> &mut Self {
+        self._inner.where_expr(expr);
+        self
+    }
+
+    #[inline]
+    fn and_expr(&mut self, expr: Expr<'a>) -> &mut Self {
+        self._inner.and_expr(expr);
+        self
+    }
+
+    #[inline]
+    fn or_expr(&mut self, expr: Expr<'a>) -> &mut Self {
+        self._inner.or_expr(expr);
+        self
+    }
+
+    #[inline]
+    fn order_by<Z: FieldIdentifier<T>>(&mut self, order_by: Z, order: Order) -> &mut Self {
+        self._inner.order_by(order_by, order);
         self
     }
 }
@@ -0,0 +1,916 @@
+//! The SQL statements generated by Canyon, as a tree of clauses and predicates.
+//!
+//! The query builders and the CRUD operations build a [`Statement`] instead of
+//! concatenating SQL, and it's rendered by the [`Dialect`] of the database targeted
+//! by the datasource, so the generated SQL is always the one of that database.
+//!
+//! The parameters are referenced by their position (starting at 1) in the values
+//! bound to the statement, and the dialect chooses how their placeholders are written.
+
+use crate::{
+    query_elements::{
+        dialect::{datasource_dialect, Dialect},
+        parameters::shift_placeholders,
+    },
+    Nulls, Order,
+};
+
+/// A complete SQL statement
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Statement {
+    Select(Select),
+    Insert(Insert),
+    Update(Update),
+    Delete(Delete),
+}
+
+impl Statement {
+    /// Generates the SQL of the statement in the syntax of the `dialect`
+    pub fn to_sql(&self, dialect: &dyn Dialect) -> String {
+        match self {
+            Self::Select(select) => select.to_sql(dialect),
+            Self::Insert(insert) => insert.to_sql(dialect),
+            Self::Update(update) => update.to_sql(dialect),
+            Self::Delete(delete) => delete.to_sql(dialect),
+        }
+    }
+
+    /// Generates the SQL of the statement for the database targeted by the
+    /// datasource, failing if it isn't declared in the configuration file.
+    /// No datasource means take the entry zero
+    pub fn render(
+        &self,
+        datasource_name: &str,
+    ) -> Result<String, Box<dyn std::error::Error + Send + Sync + 'static>> {
+        datasource_dialect(datasource_name).map(|dialect| self.to_sql(dialect))
+    }
+
+    /// The clauses that follows the main part of the statement, or [`None`]
+    /// for an *INSERT*, that does not accept them
+    pub fn clauses_mut(&mut self) -> Option<&mut Vec<Clause>> {
+        match self {
+            Self::Select(select) => Some(&mut select.clauses),
+            Self::Insert(_) => None,
+            Self::Update(update) => Some(&mut update.clauses),
+            Self::Delete(delete) => Some(&mut delete.clauses),
+        }
+    }
+}
+
+impl From<Select> for Statement {
+    fn from(select: Select) -> Self {
+        Self::Select(select)
+    }
+}
+
+impl From<Insert> for Statement {
+    fn from(insert: Insert) -> Self {
+        Self::Insert(insert)
+    }
+}
+
+impl From<Update> for Statement {
+    fn from(update: Update) -> Self {
+        Self::Update(update)
+    }
+}
+
+impl From<Delete> for Statement {
+    fn from(delete: Delete) -> Self {
+        Self::Delete(delete)
+    }
+}
+
+/// A *SELECT* statement. An empty projection retrieves every column
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Select {
+    pub projection: Vec<String>,
+    pub from: String,
+    pub clauses: Vec<Clause>,
+    /// A condition that every row must meet along with the filters of the
+    /// clauses, like the one that leaves out the soft deleted rows
+    pub scope: Option<Predicate>,
+}
+
+impl Select {
+    /// Selects every column of the `from` table
+    pub fn new(from: &str) -> Self {
+        Self {
+            projection: Vec::new(),
+            from: from.to_string(),
+            clauses: Vec::new(),
+            scope: None,
+        }
+    }
+
+    /// Replaces the columns retrieved by the statement
+    pub fn projection(mut self, projection: &[&str]) -> Self {
+        self.projection = projection.iter().map(|c| c.to_string()).collect();
+        self
+    }
+
+    /// Appends the `clause` to the statement
+    pub fn clause(mut self, clause: Clause) -> Self {
+        self.clauses.push(clause);
+        self
+    }
+
+    /// Restricts the rows of the table to the ones that meets the `scope`
+    pub fn scope(mut self, scope: Predicate) -> Self {
+        self.scope = Some(scope);
+        self
+    }
+
+    /// The clauses are rendered in the order that SQL requires them, no matter the
+    /// order that they were appended (see [`ordered_clauses`]).
+    ///
+    /// The `WHERE`, `AND` and `OR` clauses are merged with the scope into a single
+    /// `WHERE`, so it can't be bypassed by an `OR`. With raw SQL, that may contain
+    /// its own filters, the table is replaced by the subset of its rows in the scope
+    pub fn to_sql(&self, dialect: &dyn Dialect) -> String {
+        let projection = if self.projection.is_empty() {
+            String::from("*")
+        } else {
+            self.projection.join(", ")
+        };
+
+        let clauses = ordered_clauses(&self.clauses);
+        let (from, clauses) = match &self.scope {
+            Some(scope) if clauses.iter().any(|c| matches!(c, Clause::Raw(_))) => {
+                let alias = self.from.rsplit('.').next().unwrap_or(&self.from);
+                let from = format!(
+                    "(SELECT * FROM {} WHERE {}) AS {alias}",
+                    self.from,
+                    scope.to_sql(dialect)
+                );
+                (from, clauses_to_sql(&clauses, dialect))
+            }
+            Some(scope) => (
+                self.from.clone(),
+                clauses_to_sql(&scoped_clauses(&clauses, scope), dialect),
+            ),
+            None => (self.from.clone(), clauses_to_sql(&clauses, dialect)),
+        };
+
+        format!("SELECT {projection} FROM {from}{clauses}")
+    }
+
+    /// Moves the placeholders of the statement `offset` positions forward, for
+    /// embed it into another statement that already has `offset` parameters
+    pub fn shift_placeholders(&mut self, offset: usize) {
+        self.clauses
+            .iter_mut()
+            .for_each(|clause| clause.shift_placeholders(offset));
+    }
+}
+
+/// An *INSERT* statement of one or more rows, that optionally reads back
+/// some columns of the inserted rows
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Insert {
+    pub table: String,
+    pub columns: Vec<String>,
+    /// The positions of the parameters of every row, one per column
+    pub rows: Vec<Vec<usize>>,
+    /// The columns that are read back. Empty for not retrieve anything
+    pub returning: Vec<String>,
+}
+
+impl Insert {
+    /// Inserts `rows` rows with a value for every column, being the parameters
+    /// ordered row by row
+    pub fn new(table: &str, columns: &[&str], rows: usize) -> Self {
+        Self {
+            table: table.to_string(),
+            columns: columns.iter().map(|c| c.to_string()).collect(),
+            rows: (0..rows)
+                .map(|row| {
+                    (1..=columns.len())
+                        .map(|column| row * columns.len() + column)
+                        .collect()
+                })
+                .collect(),
+            returning: Vec::new(),
+        }
+    }
+
+    /// Reads back the `columns` of the inserted rows
+    pub fn returning(mut self, columns: &[&str]) -> Self {
+        self.returning = columns.iter().map(|c| c.to_string()).collect();
+        self
+    }
+
+    pub fn to_sql(&self, dialect: &dyn Dialect) -> String {
+        let mut sql = format!("INSERT INTO {} ({})", self.table, self.columns.join(", "));
+
+        let returning = !self.returning.is_empty();
+        if let Some(output) = dialect.output_clause(&self.returning).filter(|_| returning) {
+            sql.push(' ');
+            sql.push_str(&output);
+        }
+
+        let rows = self
+            .rows
+            .iter()
+            .map(|row| format!("({})", placeholders_to_sql(row, dialect)))
+            .collect::<Vec<String>>()
+            .join(", ");
+        sql.push_str(&format!(" VALUES {rows}"));
+
+        if let Some(returning) = dialect
+            .returning_clause(&self.returning)
+            .filter(|_| returning)
+        {
+            sql.push(' ');
+            sql.push_str(&returning);
+        }
+        sql
+    }
+}
+
+/// An *UPDATE* statement, that assigns to every column the value of a parameter,
+/// or a value computed when it's rendered
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Update {
+    pub table: String,
+    pub assignments: Vec<(String, usize)>,
+    /// The columns assigned to a [`Computed`] value, after the parameters
+    pub computed: Vec<(String, Computed)>,
+    pub clauses: Vec<Clause>,
+}
+
+/// A value assigned by an *UPDATE* without binding it as a parameter
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Computed {
+    /// The current time of the database, in UTC
+    CurrentTimestamp,
+    /// A value already written as an SQL literal
+    Literal(String),
+    /// A boolean literal, written as the database expects it
+    Boolean(bool),
+    Null,
+    /// The current value of the column plus one
+    Increment(String),
+}
+
+impl Computed {
+    pub fn to_sql(&self, dialect: &dyn Dialect) -> String {
+        match self {
+            Self::CurrentTimestamp => dialect.current_timestamp().to_string(),
+            Self::Literal(literal) => literal.clone(),
+            Self::Boolean(value) => dialect.boolean(*value).to_string(),
+            Self::Null => String::from("NULL"),
+            Self::Increment(column) => format!("{column} + 1"),
+        }
+    }
+}
+
+impl Update {
+    pub fn new(table: &str, assignments: &[(&str, usize)]) -> Self {
+        Self {
+            table: table.to_string(),
+            assignments: assignments
+                .iter()
+                .map(|(column, position)| (column.to_string(), *position))
+                .collect(),
+            computed: Vec::new(),
+            clauses: Vec::new(),
+        }
+    }
+
+    /// Assigns the `value` to the `column`
+    pub fn computed(mut self, column: &str, value: Computed) -> Self {
+        self.computed.push((column.to_string(), value));
+        self
+    }
+
+    /// Appends the `clause` to the statement
+    pub fn clause(mut self, clause: Clause) -> Self {
+        self.clauses.push(clause);
+        self
+    }
+
+    pub fn to_sql(&self, dialect: &dyn Dialect) -> String {
+        let mut sql = format!("UPDATE {}", self.table);
+        let assignments = self
+            .assignments
+            .iter()
+            .map(|(column, position)| format!("{column} = {}", dialect.placeholder(*position)))
+            .chain(
+                self.computed
+                    .iter()
+                    .map(|(column, value)| format!("{column} = {}", value.to_sql(dialect))),
+            )
+            .collect::<Vec<String>>();
+        if !assignments.is_empty() {
+            sql.push_str(&format!(" SET {}", assignments.join(", ")));
+        }
+        sql.push_str(&clauses_to_sql(&self.clauses, dialect));
+        sql
+    }
+}
+
+/// A *DELETE* statement
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Delete {
+    pub table: String,
+    pub clauses: Vec<Clause>,
+}
+
+impl Delete {
+    pub fn new(table: &str) -> Self {
+        Self {
+            table: table.to_string(),
+            clauses: Vec::new(),
+        }
+    }
+
+    /// Appends the `clause` to the statement
+    pub fn clause(mut self, clause: Clause) -> Self {
+        self.clauses.push(clause);
+        self
+    }
+
+    pub fn to_sql(&self, dialect: &dyn Dialect) -> String {
+        format!(
+            "DELETE FROM {}{}",
+            self.table,
+            clauses_to_sql(&self.clauses, dialect)
+        )
+    }
+}
+
+/// The clauses that follows the main part of a statement, rendered in the
+/// same order that they were added
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Clause {
+    /// `<kind> <table> ON <on>`, where `kind` is the type of the join, like `LEFT JOIN`.
+    /// The `scope` of the joined rows, like the one that leaves out the soft deleted
+    /// ones, it's part of the `ON`, so a left join doesn't lose the rows of the left side
+    Join {
+        kind: &'static str,
+        table: String,
+        on: String,
+        scope: Option<Predicate>,
+    },
+    Where(Predicate),
+    /// A filter chained to the previous ones with `AND`
+    And(Predicate),
+    /// A filter chained to the previous ones with `OR`
+    Or(Predicate),
+    GroupBy(Vec<String>),
+    Having(Predicate),
+    OrderBy(Vec<OrderKey>),
+    /// SQL written by the user, appended as is
+    Raw(String),
+}
+
+impl Clause {
+    pub fn to_sql(&self, dialect: &dyn Dialect) -> String {
+        match self {
+            Self::Join {
+                kind,
+                table,
+                on,
+                scope: None,
+            } => format!(" {kind} {table} ON {on}"),
+            Self::Join {
+                kind,
+                table,
+                on,
+                scope: Some(scope),
+            } => format!(
+                " {kind} {table} ON {on} AND {}",
+                scope.grouped_to_sql(dialect)
+            ),
+            Self::Where(predicate) => format!(" WHERE {}", predicate.to_sql(dialect)),
+            Self::And(predicate) => format!(" AND {}", predicate.grouped_to_sql(dialect)),
+            Self::Or(predicate) => format!(" OR {}", predicate.grouped_to_sql(dialect)),
+            Self::GroupBy(columns) => format!(" GROUP BY {}", columns.join(", ")),
+            Self::Having(predicate) => format!(" HAVING {}", predicate.to_sql(dialect)),
+            Self::OrderBy(keys) => format!(
+                " ORDER BY {}",
+                keys.iter()
+                    .map(|key| dialect.order_key(&key.expression, key.order, key.nulls))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+            Self::Raw(sql) => dialect.raw(sql),
+        }
+    }
+
+    fn shift_placeholders(&mut self, offset: usize) {
+        match self {
+            Self::Where(predicate)
+            | Self::And(predicate)
+            | Self::Or(predicate)
+            | Self::Having(predicate) => predicate.shift_placeholders(offset),
+            Self::Raw(sql) => *sql = shift_placeholders(sql, offset),
+            Self::Join { .. } | Self::GroupBy(_) | Self::OrderBy(_) => {}
+        }
+    }
+}
+
+/// A key of an `ORDER BY` clause
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrderKey {
+    pub expression: String,
+    pub order: Order,
+    pub nulls: Option<Nulls>,
+}
+
+/// A condition of the filters of a statement
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Predicate {
+    /// `<column><operator><value>`, where `operator` it's the SQL of an
+    /// [`crate::Operator`]. The case insensitive ones are rendered by the dialect
+    Compare {
+        column: String,
+        operator: &'static str,
+        case_insensitive: bool,
+        value: usize,
+    },
+    IsNull {
+        column: String,
+        negated: bool,
+    },
+    /// The boolean `column` is equal to the literal of the `value`
+    Is {
+        column: String,
+        value: bool,
+    },
+    Between {
+        column: String,
+        from: usize,
+        to: usize,
+    },
+    /// Without any value, no row matches the `IN`, and every row the `NOT IN`
+    In {
+        column: String,
+        values: Vec<usize>,
+        negated: bool,
+    },
+    InSubquery {
+        column: String,
+        subquery: Box<Select>,
+    },
+    Exists(Box<Select>),
+    And(Vec<Predicate>),
+    Or(Vec<Predicate>),
+    Not(Box<Predicate>),
+}
+
+impl Predicate {
+    /// Every column must be equal to the value of the parameter at its position
+    pub fn all_equal(columns: &[(&str, usize)]) -> Self {
+        let mut conditions = columns
+            .iter()
+            .map(|(column, position)| Self::Compare {
+                column: column.to_string(),
+                operator: " = ",
+                case_insensitive: false,
+                value: *position,
+            })
+            .collect::<Vec<Self>>();
+
+        if conditions.len() == 1 {
+            conditions.remove(0)
+        } else {
+            Self::And(conditions)
+        }
+    }
+
+    /// Returns `true` for the groups of more than one condition, that must be
+    /// wrapped with parentheses when they're nested
+    pub fn is_compound(&self) -> bool {
+        matches!(self, Self::And(predicates) | Self::Or(predicates) if predicates.len() > 1)
+    }
+
+    pub fn to_sql(&self, dialect: &dyn Dialect) -> String {
+        match self {
+            Self::Compare {
+                column,
+                operator,
+                case_insensitive,
+                value,
+            } => {
+                let placeholder = dialect.placeholder(*value);
+                if *case_insensitive {
+                    dialect.ilike(column, &placeholder)
+                } else {
+                    format!("{column}{operator}{placeholder}")
+                }
+            }
+            Self::IsNull { column, negated } => {
+                let not = if *negated { " NOT" } else { "" };
+                format!("{column} IS{not} NULL")
+            }
+            Self::Is { column, value } => format!("{column} = {}", dialect.boolean(*value)),
+            Self::Between { column, from, to } => format!(
+                "{column} BETWEEN {} AND {}",
+                dialect.placeholder(*from),
+                dialect.placeholder(*to)
+            ),
+            Self::In {
+                column,
+                values,
+                negated,
+            } if values.is_empty() => {
+                if *negated {
+                    "1 = 1".to_string()
+                } else {
+                    "1 = 0".to_string()
+                }
+            }
+            Self::In {
+                column,
+                values,
+                negated,
+            } => {
+                let not = if *negated { " NOT" } else { "" };
+                format!(
+                    "{column}{not} IN ({})",
+                    placeholders_to_sql(values, dialect)
+                )
+            }
+            Self::InSubquery { column, subquery } => {
+                format!("{column} IN ({})", subquery.to_sql(dialect))
+            }
+            Self::Exists(subquery) => format!("EXISTS ({})", subquery.to_sql(dialect)),
+            Self::And(predicates) => Self::group_to_sql(predicates, " AND ", dialect),
+            Self::Or(predicates) => Self::group_to_sql(predicates, " OR ", dialect),
+            Self::Not(predicate) => format!("NOT ({})", predicate.to_sql(dialect)),
+        }
+    }
+
+    /// Same as [`Self::to_sql`], but wrapping with parentheses the compound ones
+    fn grouped_to_sql(&self, dialect: &dyn Dialect) -> String {
+        if self.is_compound() {
+            format!("({})", self.to_sql(dialect))
+        } else {
+            self.to_sql(dialect)
+        }
+    }
+
+    fn group_to_sql(predicates: &[Predicate], separator: &str, dialect: &dyn Dialect) -> String {
+        predicates
+            .iter()
+            .map(|predicate| predicate.grouped_to_sql(dialect))
+            .collect::<Vec<String>>()
+            .join(separator)
+    }
+
+    fn shift_placeholders(&mut self, offset: usize) {
+        match self {
+            Self::Compare { value, .. } => *value += offset,
+            Self::IsNull { .. } | Self::Is { .. } => {}
+            Self::Between { from, to, .. } => {
+                *from += offset;
+                *to += offset;
+            }
+            Self::In { values, .. } => values.iter_mut().for_each(|value| *value += offset),
+            Self::InSubquery { subquery, .. } | Self::Exists(subquery) => {
+                subquery.shift_placeholders(offset)
+            }
+            Self::And(predicates) | Self::Or(predicates) => predicates
+                .iter_mut()
+                .for_each(|predicate| predicate.shift_placeholders(offset)),
+            Self::Not(predicate) => predicate.shift_placeholders(offset),
+        }
+    }
+}
+
+/// The `clauses` with their filters and the `scope` merged into a single `WHERE`,
+/// that goes before any grouping or ordering when there's no filters
+fn scoped_clauses(clauses: &[Clause], scope: &Predicate) -> Vec<Clause> {
+    // The `AND` and `OR` after the `HAVING` are conditions of the groups
+    let having = clauses
+        .iter()
+        .position(|clause| matches!(clause, Clause::Having(_)))
+        .unwrap_or(clauses.len());
+    let is_filter =
+        |clause: &Clause| matches!(clause, Clause::Where(_) | Clause::And(_) | Clause::Or(_));
+    let Some(position) = clauses[..having].iter().position(is_filter) else {
+        let position = clauses
+            .iter()
+            .position(|clause| !matches!(clause, Clause::Join { .. }))
+            .unwrap_or(clauses.len());
+        let mut clauses = clauses.to_vec();
+        clauses.insert(position, Clause::Where(scope.clone()));
+        return clauses;
+    };
+
+    // `AND` takes precedence over `OR`, so the filters are groups of
+    // conditions joined by `AND`, separated by every `OR`
+    let mut groups: Vec<Vec<Predicate>> = Vec::new();
+    let mut scoped = Vec::with_capacity(clauses.len());
+    for (index, clause) in clauses.iter().enumerate() {
+        match clause {
+            clause if index >= having => scoped.push(clause.clone()),
+            Clause::Where(filter) | Clause::And(filter) if !groups.is_empty() => {
+                groups.last_mut().unwrap().push(filter.clone())
+            }
+            Clause::Where(filter) | Clause::And(filter) | Clause::Or(filter) => {
+                groups.push(vec![filter.clone()])
+            }
+            clause => scoped.push(clause.clone()),
+        }
+    }
+    let mut groups = groups
+        .into_iter()
+        .map(|mut group| {
+            if group.len() == 1 {
+                group.remove(0)
+            } else {
+                Predicate::And(group)
+            }
+        })
+        .collect::<Vec<Predicate>>();
+    let filters = if groups.len() == 1 {
+        groups.remove(0)
+    } else {
+        Predicate::Or(groups)
+    };
+
+    let filters = match filters {
+        Predicate::And(mut filters) => {
+            filters.push(scope.clone());
+            filters
+        }
+        filters => vec![filters, scope.clone()],
+    };
+    scoped.insert(position, Clause::Where(Predicate::And(filters)));
+    scoped
+}
+
+/// The `clauses` in the order that SQL requires them: the joins, the filters, the
+/// `GROUP BY`, the `HAVING` and the `ORDER BY`. The `AND` and `OR` clauses follows
+/// the `WHERE` or the `HAVING` that precedes them, and the raw SQL the clause
+/// appended before it
+fn ordered_clauses(clauses: &[Clause]) -> Vec<Clause> {
+    let mut rank = 0;
+    let mut filters_rank = 1;
+    let mut ranked = clauses
+        .iter()
+        .map(|clause| {
+            rank = match clause {
+                Clause::Join { .. } => 0,
+                Clause::Where(_) => {
+                    filters_rank = 1;
+                    1
+                }
+                Clause::And(_) | Clause::Or(_) => filters_rank,
+                Clause::GroupBy(_) => 2,
+                Clause::Having(_) => {
+                    filters_rank = 3;
+                    3
+                }
+                Clause::OrderBy(_) => 4,
+                Clause::Raw(_) => rank,
+            };
+            (rank, clause.clone())
+        })
+        .collect::<Vec<(usize, Clause)>>();
+    // A stable sort, that keeps the order of the clauses of the same kind
+    ranked.sort_by_key(|(rank, _)| *rank);
+    ranked.into_iter().map(|(_, clause)| clause).collect()
+}
+
+/// The SQL of the `clauses`, where a compound `WHERE` is wrapped with parentheses
+/// when other filters follows it, like the ones of the `AND` and `OR` clauses
+/// that precedes the `HAVING`
+fn clauses_to_sql(clauses: &[Clause], dialect: &dyn Dialect) -> String {
+    let has_more_filters = clauses
+        .iter()
+        .take_while(|clause| !matches!(clause, Clause::Having(_)))
+        .any(|clause| matches!(clause, Clause::And(_) | Clause::Or(_)));
+
+    clauses
+        .iter()
+        .map(|clause| match clause {
+            Clause::Where(predicate) if has_more_filters => {
+                format!(" WHERE {}", predicate.grouped_to_sql(dialect))
+            }
+            clause => clause.to_sql(dialect),
+        })
+        .collect()
+}
+
+fn placeholders_to_sql(positions: &[usize], dialect: &dyn Dialect) -> String {
+    positions
+        .iter()
+        .map(|position| dialect.placeholder(*position))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::query_elements::dialect::{PostgreSqlDialect, SqlServerDialect};
+
+    #[test]
+    fn renders_the_insert_returning_clause_of_every_dialect() {
+        let insert = Insert::new("league", &["slug", "name"], 2).returning(&["id"]);
+
+        assert_eq!(
+            insert.to_sql(&PostgreSqlDialect),
+            "INSERT INTO league (slug, name) VALUES ($1, $2), ($3, $4) RETURNING id"
+        );
+        assert_eq!(
+            insert.to_sql(&SqlServerDialect),
+            "INSERT INTO league (slug, name) OUTPUT inserted.id VALUES (@P1, @P2), (@P3, @P4)"
+        );
+    }
+
+    #[test]
+    fn renders_the_placeholders_of_the_filters_of_every_dialect() {
+        let update =
+            Update::new("league", &[("name", 1)]).clause(Clause::Where(Predicate::all_equal(&[
+                ("id", 2),
+                ("ext_id", 3),
+            ])));
+
+        assert_eq!(
+            update.to_sql(&PostgreSqlDialect),
+            "UPDATE league SET name = $1 WHERE id = $2 AND ext_id = $3"
+        );
+        assert_eq!(
+            update.to_sql(&SqlServerDialect),
+            "UPDATE league SET name = @P1 WHERE id = @P2 AND ext_id = @P3"
+        );
+    }
+
+    #[test]
+    fn renders_the_computed_assignments_of_every_dialect() {
+        let update = Update::new("tournament", &[("slug", 1)])
+            .computed("updated_at", Computed::CurrentTimestamp)
+            .computed("reviewed_at", Computed::Literal(String::from("NULL")))
+            .computed("version", Computed::Increment(String::from("version")))
+            .clause(Clause::Where(Predicate::all_equal(&[
+                ("id", 2),
+                ("version", 3),
+            ])));
+
+        assert_eq!(
+            update.to_sql(&PostgreSqlDialect),
+            "UPDATE tournament SET slug = $1, updated_at = (CURRENT_TIMESTAMP AT TIME ZONE 'UTC'), \
+            reviewed_at = NULL, version = version + 1 WHERE id = $2 AND version = $3"
+        );
+        assert_eq!(
+            update.to_sql(&SqlServerDialect),
+            "UPDATE tournament SET slug = @P1, updated_at = SYSUTCDATETIME(), reviewed_at = NULL, \
+            version = version + 1 WHERE id = @P2 AND version = @P3"
+        );
+    }
+
+    #[test]
+    fn restricts_the_filters_of_the_selects_to_their_scope() {
+        let active = Predicate::IsNull {
+            column: String::from("deleted_at"),
+            negated: false,
+        };
+
+        let select = Select::new("league")
+            .clause(Clause::OrderBy(vec![OrderKey {
+                expression: String::from("id"),
+                order: Order::Asc,
+                nulls: None,
+            }]))
+            .scope(active.clone());
+        assert_eq!(
+            select.to_sql(&PostgreSqlDialect),
+            "SELECT * FROM league WHERE deleted_at IS NULL ORDER BY id"
+        );
+
+        let select = Select::new("league")
+            .clause(Clause::Where(Predicate::all_equal(&[("region", 1)])))
+            .clause(Clause::And(Predicate::all_equal(&[("slug", 2)])))
+            .clause(Clause::Or(Predicate::all_equal(&[("id", 3)])))
+            .scope(Predicate::Is {
+                column: String::from("deleted"),
+                value: false,
+            });
+        assert_eq!(
+            select.to_sql(&SqlServerDialect),
+            "SELECT * FROM league WHERE ((region = @P1 AND slug = @P2) OR id = @P3) AND deleted = 0"
+        );
+
+        let select = Select::new("league")
+            .clause(Clause::Where(Predicate::all_equal(&[("region", 1)])))
+            .clause(Clause::And(Predicate::all_equal(&[("slug", 2)])))
+            .scope(active.clone());
+        assert_eq!(
+            select.to_sql(&PostgreSqlDialect),
+            "SELECT * FROM league WHERE region = $1 AND slug = $2 AND deleted_at IS NULL"
+        );
+
+        let select = Select::new("public.league")
+            .clause(Clause::Raw(String::from(
+                " WHERE league.region = $1 OR league.id = $2",
+            )))
+            .scope(active);
+        assert_eq!(
+            select.to_sql(&SqlServerDialect),
+            "SELECT * FROM (SELECT * FROM public.league WHERE deleted_at IS NULL) AS league \
+            WHERE league.region = @P1 OR league.id = @P2"
+        );
+    }
+
+    #[test]
+    fn renders_the_clauses_of_the_selects_in_their_sql_order() {
+        let order_by = Clause::OrderBy(vec![OrderKey {
+            expression: String::from("COUNT(id)"),
+            order: Order::Desc,
+            nulls: None,
+        }]);
+        let having = Predicate::Compare {
+            column: String::from("COUNT(id)"),
+            operator: " > ",
+            case_insensitive: false,
+            value: 2,
+        };
+
+        let select = Select::new("league")
+            .projection(&["region", "COUNT(id) AS total"])
+            .clause(order_by)
+            .clause(Clause::Having(having))
+            .clause(Clause::Or(Predicate::all_equal(&[("MIN(id)", 3)])))
+            .clause(Clause::GroupBy(vec![String::from("region")]))
+            .clause(Clause::Where(Predicate::all_equal(&[("slug", 1)])))
+            .scope(Predicate::IsNull {
+                column: String::from("deleted_at"),
+                negated: false,
+            });
+
+        assert_eq!(
+            select.to_sql(&PostgreSqlDialect),
+            "SELECT region, COUNT(id) AS total FROM league \
+            WHERE slug = $1 AND deleted_at IS NULL GROUP BY region \
+            HAVING COUNT(id) > $2 OR MIN(id) = $3 ORDER BY COUNT(id) DESC"
+        );
+    }
+
+    #[test]
+    fn shifts_the_placeholders_of_the_subqueries() {
+        let mut subquery = Select::new("Tournament")
+            .projection(&["league"])
+            .clause(Clause::Where(Predicate::all_equal(&[("slug", 1)])))
+            .clause(Clause::Raw(String::from(" AND ext_id > $2")));
+        subquery.shift_placeholders(1);
+
+        let select = Select::new("league")
+            .clause(Clause::Where(Predicate::all_equal(&[("region", 1)])))
+            .clause(Clause::And(Predicate::InSubquery {
+                column: String::from("id"),
+                subquery: Box::new(subquery),
+            }));
+
+        assert_eq!(
+            select.to_sql(&SqlServerDialect),
+            "SELECT * FROM league WHERE region = @P1 AND id IN \
+            (SELECT league FROM Tournament WHERE slug = @P2 AND ext_id > @P3)"
+        );
+    }
+
+    #[test]
+    fn groups_the_compound_filters_followed_by_other_ones() {
+        let select = Select::new("league")
+            .clause(Clause::Where(Predicate::Or(vec![
+                Predicate::all_equal(&[("region", 1)]),
+                Predicate::all_equal(&[("slug", 2)]),
+            ])))
+            .clause(Clause::And(Predicate::all_equal(&[("id", 3)])));
+        assert_eq!(
+            select.to_sql(&PostgreSqlDialect),
+            "SELECT * FROM league WHERE (region = $1 OR slug = $2) AND id = $3"
+        );
+
+        let select = Select::new("league").clause(Clause::Where(Predicate::all_equal(&[
+            ("region", 1),
+            ("slug", 2),
+        ])));
+        assert_eq!(
+            select.to_sql(&PostgreSqlDialect),
+            "SELECT * FROM league WHERE region = $1 AND slug = $2"
+        );
+    }
+
+    #[test]
+    fn renders_the_in_filters_without_values() {
+        let values_in = |negated| Predicate::In {
+            column: String::from("id"),
+            values: vec![],
+            negated,
+        };
+
+        let select = Select::new("league")
+            .clause(Clause::Where(Predicate::all_equal(&[("region", 1)])))
+            .clause(Clause::And(values_in(false)))
+            .clause(Clause::Or(values_in(true)));
+        assert_eq!(
+            select.to_sql(&SqlServerDialect),
+            "SELECT * FROM league WHERE region = @P1 AND 1 = 0 OR 1 = 1"
+        );
+    }
+}
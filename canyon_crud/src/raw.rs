@@ -0,0 +1,55 @@
+//! Free functions for launch raw SQL statements against the database,
+//! without the need of having an entity (a type implementing
+//! [`CrudOperations`](crate::crud::CrudOperations)) to perform them.
+
+use std::fmt::Display;
+
+use crate::bounds::{QueryParameter, ScalarValue};
+use crate::crud::Transaction;
+use crate::mapper::RowMapper;
+
+/// Marker type for launch the raw queries through the [`Transaction`] trait,
+/// since the results are not tied to any entity
+struct RawQuery;
+
+impl Transaction<RawQuery> for RawQuery {}
+
+/// Launches the `stmt` against the database pointed by the `datasource_name`
+/// (the default one if it's empty), mapping every row of the result into a `R`.
+///
+/// `R` only needs to derive `CanyonMapper`, so it can be any type that
+/// holds the columns returned by the query, like the ones used for reports.
+pub async fn query_as<'a, R: RowMapper<R>>(
+    datasource_name: &'a str,
+    stmt: impl AsRef<str> + Display + Sync + Send + 'a,
+    params: impl AsRef<[&'a dyn QueryParameter<'a>]> + Sync + Send + 'a,
+) -> Result<Vec<R>, Box<dyn std::error::Error + Sync + Send + 'static>> {
+    RawQuery::query(stmt, params, datasource_name)
+        .await
+        .map(|result| result.get_entities_as::<R>())
+}
+
+/// Launches the `stmt` against the database pointed by the `datasource_name`
+/// (the default one if it's empty), returning the value of the first column
+/// of the first row, or [`None`] if there's no rows or the value is `NULL`
+pub async fn query_scalar<'a, V: ScalarValue>(
+    datasource_name: &'a str,
+    stmt: impl AsRef<str> + Display + Sync + Send + 'a,
+    params: impl AsRef<[&'a dyn QueryParameter<'a>]> + Sync + Send + 'a,
+) -> Result<Option<V>, Box<dyn std::error::Error + Sync + Send + 'static>> {
+    RawQuery::query(stmt, params, datasource_name)
+        .await
+        .and_then(|result| result.get_scalar::<V>())
+}
+
+/// Executes the `stmt` against the database pointed by the `datasource_name`
+/// (the default one if it's empty), returning the number of affected rows.
+///
+/// Intended for DDL and DML statements that does not retrieve any row
+pub async fn execute<'a>(
+    datasource_name: &'a str,
+    stmt: impl AsRef<str> + Display + Sync + Send + 'a,
+    params: impl AsRef<[&'a dyn QueryParameter<'a>]> + Sync + Send + 'a,
+) -> Result<u64, Box<dyn std::error::Error + Sync + Send + 'static>> {
+    RawQuery::execute(stmt, params, datasource_name).await
+}
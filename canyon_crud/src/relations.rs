@@ -0,0 +1,424 @@
+//! Eager loading of the entities related through a `#[foreign_key]`.
+//!
+//! The `search_*` methods generated for the foreign keys launches one query
+//! per entity, so listing a collection of entities with their relations ends
+//! in the classic N+1 queries problem. The [`EagerLoading`] operations retrieve
+//! the related entities of a whole collection with a single `IN (...)` query,
+//! and attach them to its owners in memory.
+//!
+//! Also holds the loaders and the helpers of the relation fields, the ones
+//! annotated with `#[has_many]` and `#[many_to_many]`.
+
+use std::collections::{HashMap, HashSet};
+
+use async_trait::async_trait;
+
+use crate::bounds::{QueryParameter, TableMetadata};
+use crate::crud::{CrudOperations, Transaction};
+use crate::mapper::RowMapper;
+use crate::query_elements::{
+    query_builder::same_table,
+    statement::{Clause, Delete, Insert, Predicate, Select, Statement},
+};
+use crate::soft_delete::alive_rows;
+
+/// The max number of values sent on every `IN (...)` query, keeping them
+/// far away of the limit of parameters per statement of the databases
+const IN_VALUES_PER_QUERY: usize = 1000;
+
+/// Loads the entities related to a collection of entities, avoiding to launch
+/// one query per element of the collection.
+///
+/// The relation is discovered by the `#[foreign_key]` annotations of the entities,
+/// so it must exists one between the tables of `T` and `U`.
+/// ```ignore
+/// // One query for the tournaments, and one for all their leagues
+/// let tournaments: Vec<(Tournament, Option<League>)> = Tournament::find_all()
+///     .await?
+///     .with::<League>()
+///     .await?;
+///
+/// // The reverse side, every league with its tournaments
+/// let leagues: Vec<(League, Vec<Tournament>)> = League::find_all()
+///     .await?
+///     .with_children::<Tournament>()
+///     .await?;
+/// ```
+#[async_trait]
+pub trait EagerLoading<T>: Sized {
+    /// Attaches to every entity the `U` entity that it's pointing with its foreign key
+    /// (the parent side of the relation), or [`None`] if it's `NULL` or doesn't exists
+    async fn with<U>(self) -> Result<Vec<(T, Option<U>)>, Box<dyn std::error::Error + Send + Sync>>
+    where
+        U: CrudOperations<U> + Transaction<U> + RowMapper<U> + TableMetadata + Clone + Send,
+    {
+        self.with_datasource::<U>("").await
+    }
+
+    /// Same as [`EagerLoading::with`], but querying the datasource passed in
+    async fn with_datasource<'a, U>(
+        self,
+        datasource_name: &'a str,
+    ) -> Result<Vec<(T, Option<U>)>, Box<dyn std::error::Error + Send + Sync>>
+    where
+        U: CrudOperations<U> + Transaction<U> + RowMapper<U> + TableMetadata + Clone + Send;
+
+    /// Attaches to every entity the `U` entities that are pointing to it through
+    /// their foreign key (the child side of the relation)
+    async fn with_children<U>(
+        self,
+    ) -> Result<Vec<(T, Vec<U>)>, Box<dyn std::error::Error + Send + Sync>>
+    where
+        U: CrudOperations<U> + Transaction<U> + RowMapper<U> + TableMetadata + Send,
+    {
+        self.with_children_datasource::<U>("").await
+    }
+
+    /// Same as [`EagerLoading::with_children`], but querying the datasource passed in
+    async fn with_children_datasource<'a, U>(
+        self,
+        datasource_name: &'a str,
+    ) -> Result<Vec<(T, Vec<U>)>, Box<dyn std::error::Error + Send + Sync>>
+    where
+        U: CrudOperations<U> + Transaction<U> + RowMapper<U> + TableMetadata + Send;
+}
+
+#[async_trait]
+impl<T> EagerLoading<T> for Vec<T>
+where
+    T: CrudOperations<T> + Transaction<T> + RowMapper<T> + TableMetadata + Send + Sync,
+{
+    async fn with_datasource<'a, U>(
+        self,
+        datasource_name: &'a str,
+    ) -> Result<Vec<(T, Option<U>)>, Box<dyn std::error::Error + Send + Sync>>
+    where
+        U: CrudOperations<U> + Transaction<U> + RowMapper<U> + TableMetadata + Clone + Send,
+    {
+        let (column, _, referenced_column) = find_foreign_key::<T, U>()?;
+
+        let parents = fetch_related::<T, U>(&self, column, referenced_column, datasource_name)
+            .await?
+            .into_iter()
+            .filter_map(|parent| {
+                parent
+                    .column_key(referenced_column)
+                    .map(|key| (key, parent))
+            })
+            .collect::<HashMap<_, _>>();
+
+        Ok(self
+            .into_iter()
+            .map(|entity| {
+                let parent = entity
+                    .column_key(column)
+                    .and_then(|key| parents.get(&key).cloned());
+                (entity, parent)
+            })
+            .collect())
+    }
+
+    async fn with_children_datasource<'a, U>(
+        self,
+        datasource_name: &'a str,
+    ) -> Result<Vec<(T, Vec<U>)>, Box<dyn std::error::Error + Send + Sync>>
+    where
+        U: CrudOperations<U> + Transaction<U> + RowMapper<U> + TableMetadata + Send,
+    {
+        let (column, _, referenced_column) = find_foreign_key::<U, T>()?;
+
+        let mut children: HashMap<String, Vec<U>> = HashMap::new();
+        for child in
+            fetch_related::<T, U>(&self, referenced_column, column, datasource_name).await?
+        {
+            if let Some(key) = child.column_key(column) {
+                children.entry(key).or_default().push(child);
+            }
+        }
+
+        Ok(self
+            .into_iter()
+            .map(|entity| {
+                let related = entity
+                    .column_key(referenced_column)
+                    .and_then(|key| children.remove(&key))
+                    .unwrap_or_default();
+                (entity, related)
+            })
+            .collect())
+    }
+}
+
+/// Retrieves the `U` entities that are pointing to the `entity` through their
+/// `#[foreign_key]`. It's the loader of the `#[has_many]` relation fields
+pub async fn load_has_many<'a, T, U>(
+    entity: &'a T,
+    datasource_name: &'a str,
+) -> Result<Vec<U>, Box<dyn std::error::Error + Send + Sync>>
+where
+    T: TableMetadata + Sync,
+    U: CrudOperations<U> + Transaction<U> + RowMapper<U> + TableMetadata,
+{
+    let (column, _, referenced_column) = find_foreign_key::<U, T>()?;
+    fetch_related::<T, U>(
+        std::slice::from_ref(entity),
+        referenced_column,
+        column,
+        datasource_name,
+    )
+    .await
+}
+
+/// The join table of a `#[many_to_many]` relation field, that links the entities
+/// of the annotated type `T` with the ones of the related type `U`.
+///
+/// The `source_column` is the one that points to the primary key of `T` and the
+/// `target_column` the one that points to the primary key of `U`. When they're
+/// not declared, the name of the table of each entity is used, following the
+/// same convention of the `#[foreign_key]` fields
+#[derive(Debug, Clone, Copy)]
+pub struct ManyToMany<'a> {
+    through: &'a str,
+    source_column: Option<&'a str>,
+    target_column: Option<&'a str>,
+}
+
+impl<'a> ManyToMany<'a> {
+    pub const fn new(
+        through: &'a str,
+        source_column: Option<&'a str>,
+        target_column: Option<&'a str>,
+    ) -> Self {
+        Self {
+            through,
+            source_column,
+            target_column,
+        }
+    }
+
+    /// Retrieves the `U` entities linked to the `entity` in the join table
+    pub async fn load<T, U>(
+        &self,
+        entity: &'a T,
+        datasource_name: &'a str,
+    ) -> Result<Vec<U>, Box<dyn std::error::Error + Send + Sync>>
+    where
+        T: TableMetadata + Sync,
+        U: CrudOperations<U> + Transaction<U> + RowMapper<U> + TableMetadata,
+    {
+        let (related, through) = (U::TABLE_NAME, self.through);
+        let mut select = Select::new(related)
+            .projection(&[&format!("{related}.*")])
+            .clause(Clause::Join {
+                kind: "INNER JOIN",
+                table: through.to_string(),
+                on: format!(
+                    "{through}.{} = {related}.{}",
+                    self.target_column::<U>(),
+                    single_primary_key::<U>()?
+                ),
+                scope: None,
+            })
+            .clause(Clause::Where(Predicate::all_equal(&[(
+                &format!("{through}.{}", self.source_column::<T>()),
+                1,
+            )])));
+        select.scope = alive_rows::<U>();
+        let stmt = Statement::from(select).render(datasource_name)?;
+
+        Ok(
+            U::query(stmt, [primary_key_value(entity)?], datasource_name)
+                .await?
+                .get_entities::<U>(),
+        )
+    }
+
+    /// Inserts in the join table the links between the `entity` and every `related`
+    /// one, returning the number of inserted rows
+    pub async fn add<T, U>(
+        &self,
+        entity: &'a T,
+        related: &'a [&'a U],
+        datasource_name: &'a str,
+    ) -> Result<u64, Box<dyn std::error::Error + Send + Sync>>
+    where
+        T: TableMetadata + Sync,
+        U: TableMetadata + Sync,
+    {
+        let value = primary_key_value(entity)?;
+        let mut inserted = 0;
+
+        for chunk in related_primary_keys(related)?.chunks(IN_VALUES_PER_QUERY) {
+            // Every row reuses the first parameter, the primary key of the `entity`
+            let stmt = Statement::from(Insert {
+                table: self.through.to_string(),
+                columns: vec![self.source_column::<T>(), self.target_column::<U>()],
+                rows: (2..=chunk.len() + 1).map(|idx| vec![1, idx]).collect(),
+                returning: Vec::new(),
+            })
+            .render(datasource_name)?;
+
+            let mut params = vec![value];
+            params.extend_from_slice(chunk);
+            inserted += crate::raw::execute(datasource_name, stmt, params).await?;
+        }
+
+        Ok(inserted)
+    }
+
+    /// Deletes from the join table the links between the `entity` and every `related`
+    /// one, returning the number of deleted rows
+    pub async fn remove<T, U>(
+        &self,
+        entity: &'a T,
+        related: &'a [&'a U],
+        datasource_name: &'a str,
+    ) -> Result<u64, Box<dyn std::error::Error + Send + Sync>>
+    where
+        T: TableMetadata + Sync,
+        U: TableMetadata + Sync,
+    {
+        let value = primary_key_value(entity)?;
+        let mut deleted = 0;
+
+        for chunk in related_primary_keys(related)?.chunks(IN_VALUES_PER_QUERY) {
+            let stmt = Statement::from(
+                Delete::new(self.through)
+                    .clause(Clause::Where(Predicate::all_equal(&[(
+                        &self.source_column::<T>(),
+                        1,
+                    )])))
+                    .clause(Clause::And(Predicate::In {
+                        column: self.target_column::<U>(),
+                        values: (2..=chunk.len() + 1).collect(),
+                        negated: false,
+                    })),
+            )
+            .render(datasource_name)?;
+
+            let mut params = vec![value];
+            params.extend_from_slice(chunk);
+            deleted += crate::raw::execute(datasource_name, stmt, params).await?;
+        }
+
+        Ok(deleted)
+    }
+
+    fn source_column<T: TableMetadata>(&self) -> String {
+        self.source_column
+            .map(String::from)
+            .unwrap_or_else(|| table_as_column(T::TABLE_NAME))
+    }
+
+    fn target_column<U: TableMetadata>(&self) -> String {
+        self.target_column
+            .map(String::from)
+            .unwrap_or_else(|| table_as_column(U::TABLE_NAME))
+    }
+}
+
+/// The name of a table without the schema, as it's used for the
+/// columns that points to it
+fn table_as_column(table: &str) -> String {
+    table.rsplit('.').next().unwrap_or(table).to_lowercase()
+}
+
+/// Retrieves the column of the primary key of `T`, that must be a single one
+/// to be referenced by the join table of a `#[many_to_many]` relation
+fn single_primary_key<T: TableMetadata>(
+) -> Result<&'static str, Box<dyn std::error::Error + Send + Sync>> {
+    match T::PRIMARY_KEY {
+        [pk] => Ok(pk),
+        _ => Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!(
+                "The entity of the table `{}` must have a single `#[primary_key]` field to be part of a many to many relation",
+                T::TABLE_NAME
+            ),
+        ).into()),
+    }
+}
+
+/// Retrieves the value of the primary key of the `entity`
+fn primary_key_value<T: TableMetadata>(
+    entity: &T,
+) -> Result<&dyn QueryParameter<'_>, Box<dyn std::error::Error + Send + Sync>> {
+    single_primary_key::<T>().map(|pk| {
+        entity
+            .column_value(pk)
+            .expect("The primary key is always a column of the entity")
+    })
+}
+
+/// Retrieves the values of the primary keys of the `related` entities
+fn related_primary_keys<'a, U: TableMetadata>(
+    related: &'a [&'a U],
+) -> Result<Vec<&'a dyn QueryParameter<'a>>, Box<dyn std::error::Error + Send + Sync>> {
+    related
+        .iter()
+        .map(|entity| primary_key_value(*entity))
+        .collect()
+}
+
+/// Looks for the `#[foreign_key]` of `C` that points to the table of `P`
+fn find_foreign_key<C: TableMetadata, P: TableMetadata>() -> Result<
+    &'static (&'static str, &'static str, &'static str),
+    Box<dyn std::error::Error + Send + Sync>,
+> {
+    C::FOREIGN_KEYS
+        .iter()
+        .find(|(_, table, _)| same_table(table, P::TABLE_NAME))
+        .ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!(
+                    "There's no foreign key between the tables `{}` and `{}`",
+                    C::TABLE_NAME,
+                    P::TABLE_NAME
+                ),
+            )
+            .into()
+        })
+}
+
+/// Retrieves the `U` entities whose `related_column` holds any of the values
+/// of the `column` of the `entities`, launching one query per chunk of values
+async fn fetch_related<'a, T, U>(
+    entities: &'a [T],
+    column: &str,
+    related_column: &str,
+    datasource_name: &'a str,
+) -> Result<Vec<U>, Box<dyn std::error::Error + Send + Sync>>
+where
+    T: TableMetadata + Sync,
+    U: CrudOperations<U> + Transaction<U> + RowMapper<U> + TableMetadata,
+{
+    let mut keys = HashSet::new();
+    let mut values: Vec<&'a dyn QueryParameter<'a>> = Vec::new();
+    for entity in entities {
+        if let (Some(key), Some(value)) = (entity.column_key(column), entity.column_value(column)) {
+            if keys.insert(key) {
+                values.push(value);
+            }
+        }
+    }
+
+    let mut related = Vec::new();
+    for chunk in values.chunks(IN_VALUES_PER_QUERY) {
+        let mut select = Select::new(U::TABLE_NAME).clause(Clause::Where(Predicate::In {
+            column: related_column.to_string(),
+            values: (1..=chunk.len()).collect(),
+            negated: false,
+        }));
+        select.scope = alive_rows::<U>();
+        let stmt = Statement::from(select).render(datasource_name)?;
+
+        related.extend(
+            U::query(stmt, chunk.to_vec(), datasource_name)
+                .await?
+                .get_entities::<U>(),
+        );
+    }
+
+    Ok(related)
+}
@@ -1,4 +1,8 @@
-use crate::{bounds::Row, crud::Transaction, mapper::RowMapper};
+use crate::{
+    bounds::{Row, ScalarValue},
+    crud::Transaction,
+    mapper::RowMapper,
+};
 use canyon_connection::{canyon_database_connector::DatabaseType, tiberius, tokio_postgres};
 use std::{fmt::Debug, marker::PhantomData};
 
@@ -48,6 +52,25 @@ impl<T> DatabaseResult<T> {
         }
     }
 
+    /// Returns a [`Vec<R>`] filled with instances of the type R, that can be any
+    /// type that implements [`RowMapper`], like the ones that only contains a
+    /// subset of the columns of the queried table, and not only the type T
+    /// that the query was made for.
+    pub fn get_entities_as<R: RowMapper<R>>(&self) -> Vec<R> {
+        match self.active_ds {
+            DatabaseType::PostgreSql => self
+                .postgres
+                .iter()
+                .map(|row| R::deserialize_postgresql(row))
+                .collect(),
+            DatabaseType::SqlServer => self
+                .sqlserver
+                .iter()
+                .map(|row| R::deserialize_sqlserver(row))
+                .collect(),
+        }
+    }
+
     fn map_from_postgresql<Z: RowMapper<T>>(&self) -> Vec<T>
     where
         T: Transaction<T>,
@@ -74,6 +97,21 @@ impl<T> DatabaseResult<T> {
         results
     }
 
+    /// Returns the value of the first column of the first row of the result, or [`None`]
+    /// if the result is empty or the value is `NULL`, failing if the value can't be
+    /// converted into `V`.
+    ///
+    /// Useful for retrieve the result of queries that returns a single value,
+    /// like the aggregate functions
+    pub fn get_scalar<V: ScalarValue>(
+        &self,
+    ) -> Result<Option<V>, Box<dyn std::error::Error + Sync + Send + 'static>> {
+        match self.active_ds {
+            DatabaseType::PostgreSql => self.postgres.first().map_or(Ok(None), V::from_postgresql),
+            DatabaseType::SqlServer => self.sqlserver.first().map_or(Ok(None), V::from_sqlserver),
+        }
+    }
+
     pub fn as_canyon_rows(&self) -> Vec<&dyn Row> {
         let mut results = Vec::new();
 
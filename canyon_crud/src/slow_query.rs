@@ -0,0 +1,194 @@
+//! Reports of the statements that takes longer than expected.
+//!
+//! Every datasource can declare a `slow_query_threshold_ms` in its properties on
+//! the `canyon.toml` file. The statements launched against it that exceeds that
+//! time are reported as a [`SlowQuery`] through the [`SlowQuerySink`] configured
+//! with [`set_slow_query_sink`], the standard error output by default.
+//!
+//! The values of the parameters are redacted, and only their types are reported,
+//! unless they're revealed with [`reveal_slow_query_values`].
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        RwLock,
+    },
+    time::Duration,
+};
+
+use canyon_connection::{canyon_database_connector::DatabaseType, get_datasource};
+
+use crate::{bounds::QueryParameter, query_elements::parameters::sanitize_statement};
+
+static SLOW_QUERY_SINK: RwLock<SlowQuerySink> = RwLock::new(SlowQuerySink::Stderr);
+static REVEAL_VALUES: AtomicBool = AtomicBool::new(false);
+
+/// A statement that took longer than the threshold of its datasource
+#[derive(Debug, Clone, PartialEq)]
+pub struct SlowQuery {
+    /// The name of the datasource targeted by the statement
+    pub datasource_name: &'static str,
+    /// The type of the database of the datasource
+    pub database_type: DatabaseType,
+    /// The type that launched the statement, usually the entity
+    pub entity: &'static str,
+    /// The statement, with the string literals masked
+    pub statement: String,
+    /// The Rust types of the values of the parameters
+    pub parameter_types: Vec<&'static str>,
+    /// The values of the parameters as SQL literals, only if they're
+    /// revealed with [`reveal_slow_query_values`]
+    pub parameter_values: Option<Vec<String>>,
+    /// The time that took the statement
+    pub elapsed: Duration,
+    /// The threshold of the datasource that the statement exceeded
+    pub threshold: Duration,
+}
+
+impl SlowQuery {
+    /// The revealed values of the parameters, or their types if they're redacted
+    pub fn parameters(&self) -> String {
+        self.parameter_values.as_ref().map_or_else(
+            || self.parameter_types.join(", "),
+            |values| values.join(", "),
+        )
+    }
+}
+
+impl std::fmt::Display for SlowQuery {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Slow query on the datasource {:?} ({:?}) launched by {}: {} ms (threshold of {} ms) - {} - Parameters: [{}]",
+            self.datasource_name,
+            self.database_type,
+            self.entity,
+            self.elapsed.as_millis(),
+            self.threshold.as_millis(),
+            self.statement,
+            self.parameters()
+        )
+    }
+}
+
+/// The destination of the [`SlowQuery`] reports
+pub enum SlowQuerySink {
+    /// Writes the reports on the standard error output
+    Stderr,
+    /// Emits the reports as `warn` records through the `log` facade
+    #[cfg(feature = "log")]
+    Log,
+    /// Emits the reports as `warn` events through `tracing`
+    #[cfg(feature = "tracing")]
+    Tracing,
+    /// Hands the reports to an user defined function
+    Callback(Box<dyn Fn(&SlowQuery) + Send + Sync>),
+}
+
+impl SlowQuerySink {
+    fn report(&self, slow_query: &SlowQuery) {
+        match self {
+            SlowQuerySink::Stderr => eprintln!("{slow_query}"),
+            #[cfg(feature = "log")]
+            SlowQuerySink::Log => log::warn!("{slow_query}"),
+            #[cfg(feature = "tracing")]
+            SlowQuerySink::Tracing => tracing::warn!(
+                datasource = slow_query.datasource_name,
+                db_type = ?slow_query.database_type,
+                entity = slow_query.entity,
+                statement = %slow_query.statement,
+                parameters = %slow_query.parameters(),
+                elapsed_ms = slow_query.elapsed.as_secs_f64() * 1000.0,
+                threshold_ms = slow_query.threshold.as_secs_f64() * 1000.0,
+                "Slow query"
+            ),
+            SlowQuerySink::Callback(callback) => callback(slow_query),
+        }
+    }
+}
+
+/// Replaces the destination of the [`SlowQuery`] reports
+pub fn set_slow_query_sink(sink: SlowQuerySink) {
+    *SLOW_QUERY_SINK
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = sink;
+}
+
+/// Includes the values of the parameters in the [`SlowQuery`] reports,
+/// that are redacted by default
+pub fn reveal_slow_query_values(reveal: bool) {
+    REVEAL_VALUES.store(reveal, Ordering::Relaxed);
+}
+
+/// Reports the statement if it took longer than the threshold of the datasource
+pub(crate) fn report_if_slow(
+    datasource_name: &str,
+    database_type: DatabaseType,
+    entity: &'static str,
+    stmt: &str,
+    params: &[&dyn QueryParameter<'_>],
+    elapsed: Duration,
+) {
+    // The statement was launched, so its datasource is always declared
+    let Ok(datasource) = get_datasource(datasource_name) else {
+        return;
+    };
+    let threshold = match datasource.properties.slow_query_threshold_ms {
+        Some(threshold) => Duration::from_millis(threshold),
+        None => return,
+    };
+    if elapsed <= threshold {
+        return;
+    }
+
+    let slow_query = SlowQuery {
+        datasource_name: datasource.name,
+        database_type,
+        entity,
+        statement: sanitize_statement(stmt),
+        parameter_types: params.iter().map(|param| param.type_name()).collect(),
+        parameter_values: REVEAL_VALUES
+            .load(Ordering::Relaxed)
+            .then(|| params.iter().map(|param| param.as_sql_literal()).collect()),
+        elapsed,
+        threshold,
+    };
+
+    SLOW_QUERY_SINK
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .report(&slow_query);
+}
+
+#[cfg(test)]
+mod reports {
+    use super::*;
+
+    fn slow_query(parameter_values: Option<Vec<String>>) -> SlowQuery {
+        SlowQuery {
+            datasource_name: "postgres_docker",
+            database_type: DatabaseType::PostgreSql,
+            entity: "League",
+            statement: sanitize_statement("SELECT * FROM league WHERE slug = 'lck' AND id > $1"),
+            parameter_types: vec!["i32"],
+            parameter_values,
+            elapsed: Duration::from_millis(320),
+            threshold: Duration::from_millis(250),
+        }
+    }
+
+    #[test]
+    fn redacts_the_values_of_the_parameters() {
+        assert_eq!(
+            slow_query(None).to_string(),
+            "Slow query on the datasource \"postgres_docker\" (PostgreSql) launched by League: \
+            320 ms (threshold of 250 ms) - SELECT * FROM league WHERE slug = '?' AND id > $1 - \
+            Parameters: [i32]"
+        );
+    }
+
+    #[test]
+    fn reveals_the_values_of_the_parameters() {
+        assert_eq!(slow_query(Some(vec![String::from("5")])).parameters(), "5");
+    }
+}
@@ -0,0 +1,160 @@
+//! Soft deletion of the entities.
+//!
+//! A `#[canyon_entity]` with a field annotated with `#[soft_delete]`, of type
+//! `Option<NaiveDateTime>` or `bool`, never removes its rows with the `delete`
+//! operation. Instead, it marks them as deleted, writing the current time of the
+//! database or `true` in the column, and the `find_all`, `find_by_pk`, `count` and
+//! `select_query` operations leave out the deleted rows. So do the typed joins, on
+//! both sides, the eager loading and the loaders of the relation fields with the
+//! related entities. Only the alive rows are marked by `delete`, so the time of
+//! a deletion is never overwritten.
+//!
+//! The deleted rows are still available through the [`SoftDeleteScope`] of the
+//! `SelectQueryBuilder`, with `with_deleted` and `only_deleted`, they can be marked
+//! back as alive with `restore`, and removed from the table with `force_delete`.
+//!
+//! The mark is computed by the statement itself, which is why `delete` and `restore`
+//! work on shared references. An instance holds the `#[soft_delete]` value of the
+//! query that loaded it, and `only_deleted` is the way to find out when a row was
+//! deleted.
+
+use crate::{
+    bounds::TableMetadata,
+    query_elements::statement::{Computed, Predicate},
+};
+
+/// How a `#[soft_delete]` column tells that a row was deleted, inferred from
+/// the type of the field
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SoftDelete {
+    /// An `Option<NaiveDateTime>` with the time of the deletion, `NULL` for the alive rows
+    Timestamp,
+    /// A `bool` that it's `true` for the deleted rows
+    Flag,
+}
+
+impl SoftDelete {
+    /// The condition met by the rows of the `column` that aren't deleted
+    pub fn alive(self, column: &str) -> Predicate {
+        self.predicate(column, false)
+    }
+
+    /// The condition met by the rows of the `column` that are deleted
+    pub fn deleted(self, column: &str) -> Predicate {
+        self.predicate(column, true)
+    }
+
+    /// The value assigned to the column by `delete`
+    pub fn mark(self) -> Computed {
+        match self {
+            Self::Timestamp => Computed::CurrentTimestamp,
+            Self::Flag => Computed::Boolean(true),
+        }
+    }
+
+    /// The value assigned to the column by `restore`
+    pub fn unmark(self) -> Computed {
+        match self {
+            Self::Timestamp => Computed::Null,
+            Self::Flag => Computed::Boolean(false),
+        }
+    }
+
+    fn predicate(self, column: &str, deleted: bool) -> Predicate {
+        let column = column.to_string();
+        match self {
+            Self::Timestamp => Predicate::IsNull {
+                column,
+                negated: deleted,
+            },
+            Self::Flag => Predicate::Is {
+                column,
+                value: deleted,
+            },
+        }
+    }
+}
+
+/// The rows of an entity with a `#[soft_delete]` field retrieved by a query
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SoftDeleteScope {
+    /// Only the rows that aren't deleted
+    #[default]
+    Alive,
+    /// Every row, deleted or not
+    WithDeleted,
+    /// Only the deleted rows
+    OnlyDeleted,
+}
+
+impl SoftDeleteScope {
+    /// The condition that restricts the rows of the `column` to the ones of the
+    /// scope, or [`None`] when every row is retrieved
+    pub fn predicate(self, column: &str, soft_delete: SoftDelete) -> Option<Predicate> {
+        match self {
+            Self::Alive => Some(soft_delete.alive(column)),
+            Self::WithDeleted => None,
+            Self::OnlyDeleted => Some(soft_delete.deleted(column)),
+        }
+    }
+}
+
+/// The condition met by the rows of `T` that aren't deleted, with its column
+/// qualified by the table, so it can be used on the statements that joins other
+/// tables. [`None`] for the entities without a `#[soft_delete]` field
+pub(crate) fn alive_rows<T: TableMetadata>() -> Option<Predicate> {
+    T::SOFT_DELETE
+        .map(|(column, soft_delete)| soft_delete.alive(&format!("{}.{column}", T::TABLE_NAME)))
+}
+
+#[cfg(test)]
+mod scopes {
+    use super::*;
+    use crate::query_elements::dialect::{PostgreSqlDialect, SqlServerDialect};
+
+    #[test]
+    fn filters_the_rows_of_every_scope() {
+        let sql = |scope: SoftDeleteScope, soft_delete, dialect| {
+            scope
+                .predicate("deleted_at", soft_delete)
+                .map(|predicate: Predicate| predicate.to_sql(dialect))
+        };
+
+        assert_eq!(
+            sql(
+                SoftDeleteScope::Alive,
+                SoftDelete::Timestamp,
+                &PostgreSqlDialect
+            ),
+            Some(String::from("deleted_at IS NULL"))
+        );
+        assert_eq!(
+            sql(
+                SoftDeleteScope::OnlyDeleted,
+                SoftDelete::Timestamp,
+                &SqlServerDialect
+            ),
+            Some(String::from("deleted_at IS NOT NULL"))
+        );
+        assert_eq!(
+            sql(SoftDeleteScope::Alive, SoftDelete::Flag, &SqlServerDialect),
+            Some(String::from("deleted_at = 0"))
+        );
+        assert_eq!(
+            sql(
+                SoftDeleteScope::OnlyDeleted,
+                SoftDelete::Flag,
+                &PostgreSqlDialect
+            ),
+            Some(String::from("deleted_at = TRUE"))
+        );
+        assert_eq!(
+            sql(
+                SoftDeleteScope::WithDeleted,
+                SoftDelete::Flag,
+                &PostgreSqlDialect
+            ),
+            None
+        );
+    }
+}
@@ -0,0 +1,104 @@
+//! Automatic timestamps of the entities.
+//!
+//! The fields of a `#[canyon_entity]` annotated with `#[created_at]` or
+//! `#[updated_at]`, of type `NaiveDateTime` (or `Option<NaiveDateTime>`), are filled
+//! with the current time, in UTC, by the CRUD operations: both of them by the `insert`,
+//! `insert_returning_all` and `multi_insert` operations, and the `#[updated_at]` ones
+//! by `update` and by the `set` of the `UpdateQueryBuilder`. The `#[created_at]`
+//! fields are never modified by the updates.
+//!
+//! The [`TimestampSource`] of the time is declared on the annotation, like
+//! `#[updated_at(source = "database")]`, being the client the default one. The
+//! migrations create the columns with the current time of the database as their
+//! default value, for both of them.
+//!
+//! `update` assigns the time of the client to the `#[updated_at]` fields of the
+//! entity before writing them, like the inserts. The `UpdateQueryBuilder`, and the
+//! `update_changes` operation built on it, binds it instead as a [`ClientTime`]
+//! parameter, taken when the statement is sent to the database. The time of the
+//! database is only known by the entity after reading the row again.
+
+use std::error::Error;
+
+use bytes::BytesMut;
+use canyon_connection::{
+    tiberius::{ColumnData, IntoSql},
+    tokio_postgres::types::{to_sql_checked, IsNull, ToSql, Type},
+};
+use chrono::{NaiveDateTime, Timelike, Utc};
+
+use crate::bounds::QueryParameter;
+
+/// Where the current time of the timestamps comes from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TimestampSource {
+    /// The time of the machine that runs the program, assigned to the fields
+    /// of the entity before it's sent to the database
+    #[default]
+    Client,
+    /// The time of the database. The columns are left out of the inserts, so
+    /// they takes their default value, that it's only read back into the entity
+    /// by `insert_returning_all`
+    Database,
+}
+
+/// The current time of the client, in UTC, truncated to microseconds, the
+/// precision of the timestamps on both databases
+pub fn now() -> NaiveDateTime {
+    let now = Utc::now().naive_utc();
+    now.with_nanosecond(now.nanosecond() / 1_000 * 1_000)
+        .unwrap_or(now)
+}
+
+/// The query parameter of the `#[updated_at]` columns whose source is the client,
+/// that binds its current time when the statement is sent to the database, so the
+/// updates doesn't need to hold the value
+#[derive(Debug, Clone, Copy)]
+pub struct ClientTime;
+
+impl ToSql for ClientTime {
+    fn to_sql(
+        &self,
+        ty: &Type,
+        out: &mut BytesMut,
+    ) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        now().to_sql(ty, out)
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        <NaiveDateTime as ToSql>::accepts(ty)
+    }
+
+    to_sql_checked!();
+}
+
+impl QueryParameter<'_> for ClientTime {
+    fn as_postgres_param(&self) -> &(dyn ToSql + Sync) {
+        self
+    }
+
+    fn as_sqlserver_param(&self) -> ColumnData<'_> {
+        now().into_sql()
+    }
+
+    fn as_sql_literal(&self) -> String {
+        now().as_sql_literal()
+    }
+}
+
+#[cfg(test)]
+mod sources {
+    use super::*;
+
+    #[test]
+    fn binds_the_current_time_of_the_client() {
+        assert_eq!(now().nanosecond() % 1_000, 0);
+        assert!(matches!(
+            ClientTime.as_sqlserver_param(),
+            ColumnData::DateTime2(Some(_))
+        ));
+
+        let literal = ClientTime.as_sql_literal();
+        assert!(literal.starts_with('\'') && literal.ends_with('\''));
+    }
+}
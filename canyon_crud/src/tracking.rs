@@ -0,0 +1,111 @@
+//! Dirty tracking of the entities.
+//!
+//! The `update` operation writes every column of the entity. When a copy of the
+//! entity is kept as a snapshot of the values read from the database, the columns
+//! modified since then are found by comparing both, so only they are written:
+//!
+//! - [`changed_columns`] lists the columns whose values differs between them
+//! - `diff(&old, &new)` generates an `UpdateQueryBuilder` that assigns the changed
+//!   columns of `new`, filtered by its primary key
+//! - `update_changes(&snapshot)` updates the entity writing only the changed columns,
+//!   without querying the database if there's none
+//!
+//! The primary key and the `#[version]` columns are never considered changes, but
+//! the version of the snapshot guards the write, and it's moved forward by one.
+//! Neither are the `#[created_at]` and `#[updated_at]` columns, managed by the CRUD
+//! operations, nor the `#[soft_delete]` one, only written by `delete` and `restore`,
+//! so the `#[updated_at]` columns aren't written when there's no other change.
+
+use crate::bounds::TableMetadata;
+
+/// The columns of the entity whose values differs between `old` and `new`, in
+/// the order of the fields, leaving out the primary key, the timestamps, the
+/// `#[soft_delete]` and the `#[version]` ones
+pub fn changed_columns<T: TableMetadata>(old: &T, new: &T) -> Vec<&'static str> {
+    T::COLUMNS
+        .iter()
+        .filter(|column| {
+            !T::PRIMARY_KEY.contains(column)
+                && !T::TIMESTAMPS.contains(column)
+                && T::SOFT_DELETE.map(|(soft_delete, _)| soft_delete) != Some(**column)
+                && T::VERSION != Some(**column)
+        })
+        .filter(|column| old.column_key(column) != new.column_key(column))
+        .copied()
+        .collect()
+}
+
+#[cfg(test)]
+mod changes {
+    use super::*;
+    use crate::bounds::QueryParameter;
+    use crate::soft_delete::SoftDelete;
+
+    struct League {
+        id: i32,
+        name: String,
+        region: Option<String>,
+        updated_at: String,
+        deleted_at: Option<String>,
+        version: i32,
+    }
+
+    impl TableMetadata for League {
+        const TABLE_NAME: &'static str = "league";
+        const COLUMNS: &'static [&'static str] = &[
+            "id",
+            "name",
+            "region",
+            "updated_at",
+            "deleted_at",
+            "version",
+        ];
+        const PRIMARY_KEY: &'static [&'static str] = &["id"];
+        const FOREIGN_KEYS: &'static [(&'static str, &'static str, &'static str)] = &[];
+        const TIMESTAMPS: &'static [&'static str] = &["updated_at"];
+        const SOFT_DELETE: Option<(&'static str, SoftDelete)> =
+            Some(("deleted_at", SoftDelete::Timestamp));
+        const VERSION: Option<&'static str> = Some("version");
+
+        fn column_value(&self, _column: &str) -> Option<&dyn QueryParameter<'_>> {
+            None
+        }
+
+        fn column_key(&self, column: &str) -> Option<String> {
+            match column {
+                "id" => Some(format!("{:?}", self.id)),
+                "name" => Some(format!("{:?}", self.name)),
+                "region" => self.region.as_ref().map(|value| format!("{value:?}")),
+                "updated_at" => Some(format!("{:?}", self.updated_at)),
+                "deleted_at" => self.deleted_at.as_ref().map(|value| format!("{value:?}")),
+                "version" => Some(format!("{:?}", self.version)),
+                _ => None,
+            }
+        }
+    }
+
+    #[test]
+    fn finds_the_changed_columns_besides_the_managed_ones() {
+        let old = League {
+            id: 1,
+            name: String::from("LCK"),
+            region: None,
+            updated_at: String::from("2023-11-19 00:00:00"),
+            deleted_at: None,
+            version: 3,
+        };
+        let mut new = League {
+            id: 2,
+            name: String::from("LCK"),
+            region: None,
+            updated_at: String::from("2023-11-26 00:00:00"),
+            deleted_at: Some(String::from("2023-11-26 00:00:00")),
+            version: 4,
+        };
+        assert!(changed_columns(&old, &new).is_empty());
+
+        new.region = Some(String::from("KOREA"));
+        new.name = String::from("LCK CL");
+        assert_eq!(changed_columns(&old, &new), ["name", "region"]);
+    }
+}
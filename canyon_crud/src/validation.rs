@@ -0,0 +1,279 @@
+//! Declarative validation of the fields of the entities.
+//!
+//! The fields of a `#[canyon_entity]` annotated with `#[validate(...)]` are
+//! checked by the [`Validate`] implementation generated by the `CanyonCrud`
+//! derive macro, that it's invoked by the `insert`, `insert_returning_all`,
+//! `multi_insert` and `update` operations before querying the database.
+//!
+//! The available rules are:
+//! - `length(min = 1, max = 64)`, the number of characters of a text
+//! - `range(min = 0, max = 100)`, the bounds of a number
+//! - `regex = "^[a-z-]+$"`, a pattern that the text must match
+//! - `email`, a text that looks like an email address
+//! - `custom = "path::to::function"`, a `fn(&T) -> Result<(), String>` for the type of the field
+//!
+//! The optional fields are only checked when they hold a value. The `length`
+//! and `range` rules are also created as `CHECK` constraints by the migrations.
+//! The `regex` rules are never created as constraints, since no database
+//! understands the syntax of the patterns of the `regex` crate as is.
+//!
+//! The `length` rule counts the characters of the text, while the `CHECK` of
+//! `SqlServer` counts its UTF-16 code units. A text with characters outside of
+//! the basic multilingual plane, like most emojis, may pass the validation and
+//! still be rejected by the constraint of a `SqlServer` database.
+
+use std::{error::Error, fmt::Display};
+
+pub use regex::Regex;
+
+/// Validates the values of the fields of an entity
+pub trait Validate {
+    /// Checks every rule of every field, returning all the broken ones
+    fn validate(&self) -> Result<(), ValidationErrors>;
+}
+
+/// A rule of the `#[validate]` annotation broken by the value of a field
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldError {
+    /// The name of the field
+    pub field: &'static str,
+    /// The name of the broken rule, like `length` or `email`
+    pub rule: &'static str,
+    /// Describes why the value breaks the rule
+    pub message: String,
+}
+
+/// The rules broken by the fields of an entity, returned by [`Validate::validate`].
+///
+/// It's also the error of the operations that validates the entities, so
+/// it can be recovered with `error.downcast_ref::<ValidationErrors>()`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidationErrors {
+    pub errors: Vec<FieldError>,
+}
+
+impl ValidationErrors {
+    /// Records that the `field` broke the `rule`, if the `check` failed
+    pub fn check(&mut self, field: &'static str, rule: &'static str, check: Result<(), String>) {
+        if let Err(message) = check {
+            self.errors.push(FieldError {
+                field,
+                rule,
+                message,
+            });
+        }
+    }
+
+    /// The errors of the `field`
+    pub fn field(&self, field: &str) -> Vec<&FieldError> {
+        self.errors
+            .iter()
+            .filter(|error| error.field == field)
+            .collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Returns `Ok` if there's no errors
+    pub fn into_result(self) -> Result<(), ValidationErrors> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err(self)
+        }
+    }
+}
+
+impl Display for ValidationErrors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Validation failed: {}",
+            self.errors
+                .iter()
+                .map(|error| format!("`{}` {}", error.field, error.message))
+                .collect::<Vec<String>>()
+                .join(", ")
+        )
+    }
+}
+
+impl Error for ValidationErrors {}
+
+/// The values that can be checked by the `length`, `regex` and `email` rules
+pub trait TextValue {
+    /// The text, or `None` if there's no value to check
+    fn text(&self) -> Option<&str>;
+}
+
+impl TextValue for String {
+    fn text(&self) -> Option<&str> {
+        Some(self)
+    }
+}
+
+impl TextValue for &str {
+    fn text(&self) -> Option<&str> {
+        Some(self)
+    }
+}
+
+impl TextValue for Option<String> {
+    fn text(&self) -> Option<&str> {
+        self.as_deref()
+    }
+}
+
+/// The values that can be checked by the `range` rule
+pub trait NumericValue {
+    /// The number, or `None` if there's no value to check
+    fn number(&self) -> Option<f64>;
+}
+
+macro_rules! impl_numeric_value {
+    ($($ty:ty),*) => {
+        $(
+            impl NumericValue for $ty {
+                fn number(&self) -> Option<f64> {
+                    Some(*self as f64)
+                }
+            }
+
+            impl NumericValue for Option<$ty> {
+                fn number(&self) -> Option<f64> {
+                    self.map(|value| value as f64)
+                }
+            }
+        )*
+    };
+}
+
+impl_numeric_value!(i8, i16, i32, i64, u8, u16, u32, u64, f32, f64);
+
+/// Checks that the number of characters of the `value` it's between `min` and `max`
+pub fn length(
+    value: &impl TextValue,
+    min: Option<usize>,
+    max: Option<usize>,
+) -> Result<(), String> {
+    let Some(length) = value.text().map(|text| text.chars().count()) else {
+        return Ok(());
+    };
+    match (min, max) {
+        (Some(min), _) if length < min => Err(format!(
+            "must have at least {min} characters, but it has {length}"
+        )),
+        (_, Some(max)) if length > max => Err(format!(
+            "must have at most {max} characters, but it has {length}"
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Checks that the `value` it's between `min` and `max`, both inclusive
+pub fn range(value: &impl NumericValue, min: Option<f64>, max: Option<f64>) -> Result<(), String> {
+    let Some(number) = value.number() else {
+        return Ok(());
+    };
+    match (min, max) {
+        (Some(min), _) if number < min => Err(format!("must be at least {min}, but it's {number}")),
+        (_, Some(max)) if number > max => Err(format!("must be at most {max}, but it's {number}")),
+        _ => Ok(()),
+    }
+}
+
+/// Checks that the `value` matches the `pattern`
+pub fn regex(value: &impl TextValue, pattern: &Regex) -> Result<(), String> {
+    match value.text() {
+        Some(text) if !pattern.is_match(text) => {
+            Err(format!("must match the pattern `{}`", pattern.as_str()))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Checks that the `value` looks like an email address, with a non empty
+/// local part, an `@` and a domain with at least one dot
+pub fn email(value: &impl TextValue) -> Result<(), String> {
+    let Some(text) = value.text() else {
+        return Ok(());
+    };
+    let valid = match text.split_once('@') {
+        Some((local, domain)) => {
+            !local.is_empty()
+                && !domain.contains('@')
+                && domain.split('.').all(|label| !label.is_empty())
+                && domain.contains('.')
+                && !text.chars().any(char::is_whitespace)
+        }
+        None => false,
+    };
+
+    if valid {
+        Ok(())
+    } else {
+        Err(String::from("must be a valid email address"))
+    }
+}
+
+#[cfg(test)]
+mod rules {
+    use super::*;
+
+    #[test]
+    fn checks_the_length_of_the_texts() {
+        assert!(length(&String::from("lec"), Some(1), Some(3)).is_ok());
+        assert!(length(&"ñandú", None, Some(5)).is_ok());
+        assert_eq!(
+            length(&String::new(), Some(1), None),
+            Err(String::from(
+                "must have at least 1 characters, but it has 0"
+            ))
+        );
+        assert!(length(&Some(String::from("worlds")), None, Some(3)).is_err());
+        assert!(length(&None::<String>, Some(1), Some(3)).is_ok());
+    }
+
+    #[test]
+    fn checks_the_range_of_the_numbers() {
+        assert!(range(&5, Some(0.0), Some(10.0)).is_ok());
+        assert_eq!(
+            range(&-1_i64, Some(0.0), None),
+            Err(String::from("must be at least 0, but it's -1"))
+        );
+        assert!(range(&Some(10.5_f64), None, Some(10.0)).is_err());
+        assert!(range(&None::<i32>, Some(0.0), Some(10.0)).is_ok());
+    }
+
+    #[test]
+    fn checks_the_patterns_and_the_emails() {
+        let slug = Regex::new("^[a-z0-9-]+$").unwrap();
+        assert!(regex(&"worlds-2023", &slug).is_ok());
+        assert!(regex(&"Worlds 2023", &slug).is_err());
+
+        assert!(email(&"player@canyon.gg").is_ok());
+        assert!(email(&Some(String::from("player@canyon"))).is_err());
+        assert!(email(&"@canyon.gg").is_err());
+        assert!(email(&"player @canyon.gg").is_err());
+        assert!(email(&"player@canyon..gg").is_err());
+    }
+
+    #[test]
+    fn collects_the_broken_rules() {
+        let mut errors = ValidationErrors::default();
+        errors.check("slug", "length", length(&"", Some(1), None));
+        errors.check("region", "length", length(&"EU", Some(1), None));
+        errors.check("email", "email", email(&"player"));
+
+        assert_eq!(errors.field("slug").len(), 1);
+        assert!(errors.field("region").is_empty());
+        assert_eq!(
+            errors.to_string(),
+            "Validation failed: `slug` must have at least 1 characters, but it has 0, \
+            `email` must be a valid email address"
+        );
+        assert!(errors.into_result().is_err());
+    }
+}
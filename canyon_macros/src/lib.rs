@@ -8,17 +8,23 @@ use canyon_connection::CANYON_TOKIO_RUNTIME;
 use proc_macro::{Span, TokenStream as CompilerTokenStream};
 use proc_macro2::{Ident, TokenStream};
 use quote::{quote, ToTokens};
-use syn::{DeriveInput, Fields, Type, Visibility};
+use syn::{DeriveInput, Field, Fields, Type, Visibility};
 
 use query_operations::{
     delete::{generate_delete_query_tokens, generate_delete_tokens},
-    insert::{generate_insert_tokens, generate_multiple_insert_tokens},
+    insert::{
+        generate_insert_returning_all_tokens, generate_insert_tokens,
+        generate_multiple_insert_tokens,
+    },
+    relations::generate_relations_tokens,
     select::{
         generate_count_tokens, generate_find_all_query_tokens, generate_find_all_tokens,
         generate_find_all_unchecked_tokens, generate_find_by_foreign_key_tokens,
         generate_find_by_pk_tokens, generate_find_by_reverse_foreign_key_tokens,
+        generate_primary_key_tokens, generate_table_metadata_tokens,
     },
     update::{generate_update_query_tokens, generate_update_tokens},
+    validate::generate_validate_tokens,
 };
 
 use canyon_macro::{parse_canyon_macro_attributes, wire_queries_to_execute};
@@ -27,6 +33,7 @@ use utils::{function_parser::FunctionParser, helpers, macro_tokens::MacroTokens}
 use canyon_observer::{
     manager::{
         entity::CanyonEntity,
+        field_annotation::EntityFieldAnnotation,
         manager_builder::{
             generate_enum_with_fields, generate_enum_with_fields_values, generate_user_struct,
         },
@@ -53,8 +60,8 @@ pub fn main(_meta: CompilerTokenStream, input: CompilerTokenStream) -> CompilerT
 
     // Parses the attributes declared in the arguments of this proc macro
     let attrs_parse_result = parse_canyon_macro_attributes(&attrs);
-    if attrs_parse_result.error.is_some() {
-        return attrs_parse_result.error.unwrap();
+    if let Some(error) = attrs_parse_result.error {
+        return error;
     }
 
     // Parses the function items that this attribute is attached to
@@ -172,6 +179,11 @@ pub fn querybuilder_fields(input: CompilerTokenStream) -> CompilerTokenStream {
 ///
 /// Also, it's the responsible of generate the tokens for all the `Crud` methods available over
 /// your type
+///
+/// The fields annotated with `#[has_many(Entity)]` or `#[many_to_many(Entity, through = "table")]`
+/// are relation fields, a `Vec<Entity>` that isn't mapped against any column, and that
+/// it's filled with the autogenerated `load_<field>` methods. The `#[many_to_many]` ones also
+/// have `add_<field>` and `remove_<field>` methods, that manages the rows of the join table
 #[proc_macro_attribute]
 pub fn canyon_entity(
     _meta: CompilerTokenStream,
@@ -260,8 +272,8 @@ pub fn canyon_entity(
     new_entity.user_table_name = table_name;
     new_entity.user_schema_name = schema_name;
 
-    // The entity fields
-    for field in entity.fields.iter() {
+    // The entity fields, without the relation ones, that aren't columns of the table
+    for field in entity.column_fields() {
         let mut new_entity_field = CanyonRegisterEntityField {
             field_name: field.name.to_string(),
             field_type: field.get_field_type_as_string().replace(' ', ""),
@@ -341,11 +353,23 @@ fn impl_crud_operations_trait_for_struct(
     // Builds a COUNT(*) query over some table
     let _count_tokens = generate_count_tokens(macro_data, &table_schema_data);
 
+    // Builds the PrimaryKey associated type and the primary_key() method
+    let _primary_key_tokens = generate_primary_key_tokens(macro_data);
+
+    // Builds the TableMetadata implementation
+    let _table_metadata_tokens = generate_table_metadata_tokens(macro_data, &table_schema_data);
+
+    // Builds the Validate implementation, with the rules of the `#[validate]` annotations
+    let _validate_tokens = generate_validate_tokens(macro_data);
+
     // Builds the find_by_pk() query
     let _find_by_pk_tokens = generate_find_by_pk_tokens(macro_data, &table_schema_data);
 
     // Builds the insert() query
     let _insert_tokens = generate_insert_tokens(macro_data, &table_schema_data);
+    // Builds the insert_returning_all() query
+    let _insert_returning_all_tokens =
+        generate_insert_returning_all_tokens(macro_data, &table_schema_data);
     // Builds the insert_multi() query
     let _insert_multi_tokens = generate_multiple_insert_tokens(macro_data, &table_schema_data);
 
@@ -380,7 +404,44 @@ fn impl_crud_operations_trait_for_struct(
         proc_macro2::Span::call_site(),
     );
 
+    // The loaders of the relation fields, and the helpers to manage the join tables
+    let _relations_tokens: Vec<(TokenStream, TokenStream)> = generate_relations_tokens(macro_data);
+    let relation_method_signatures = _relations_tokens.iter().map(|(sign, _)| sign);
+    let relation_method_implementations = _relations_tokens.iter().map(|(_, m_impl)| m_impl);
+
+    // The autogenerated name for the trait that holds the operations over the relation fields
+    let relations_trait_ident = proc_macro2::Ident::new(
+        &format!("{}RelationOperations", &ty.to_string()),
+        proc_macro2::Span::call_site(),
+    );
+
+    let relation_operations_tokens = if _relations_tokens.is_empty() {
+        quote! {}
+    } else {
+        quote! {
+            /// Hidden trait for generate the operations over the relation fields
+            /// (`#[has_many]` and `#[many_to_many]`) of the entity, that are
+            /// generated dynamically based on the annotated fields
+            #[canyon_sql::macros::async_trait]
+            pub trait #relations_trait_ident<#ty> {
+                #(#relation_method_signatures)*
+            }
+            #[canyon_sql::macros::async_trait]
+            impl #relations_trait_ident<#ty> for #ty
+                where #ty:
+                    std::fmt::Debug +
+                    canyon_sql::crud::CrudOperations<#ty> +
+                    canyon_sql::crud::RowMapper<#ty>
+            {
+                #(#relation_method_implementations)*
+            }
+        }
+    };
+
     let crud_operations_tokens = quote! {
+        // The primary key type and value impl
+        #_primary_key_tokens
+
         // The find_all_result impl
         #_find_all_tokens
 
@@ -399,6 +460,9 @@ fn impl_crud_operations_trait_for_struct(
         // The insert impl
         #_insert_tokens
 
+        // The insert that reads back the whole row impl
+        #_insert_returning_all_tokens
+
         // The insert of multiple entities impl
         #_insert_multi_tokens
 
@@ -424,6 +488,12 @@ fn impl_crud_operations_trait_for_struct(
 
             impl canyon_sql::crud::Transaction<#ty> for #ty {}
 
+            #_table_metadata_tokens
+
+            #_validate_tokens
+
+            #relation_operations_tokens
+
             /// Hidden trait for generate the foreign key operations available
             /// in Canyon without have to define them before hand in CrudOperations
             /// because it's just impossible with the actual system (where the methods
@@ -453,6 +523,12 @@ fn impl_crud_operations_trait_for_struct(
             }
 
             impl canyon_sql::crud::Transaction<#ty> for #ty {}
+
+            #_table_metadata_tokens
+
+            #_validate_tokens
+
+            #relation_operations_tokens
         }
     };
 
@@ -520,120 +596,183 @@ pub fn implement_row_mapper_for_type(input: proc_macro::TokenStream) -> proc_mac
     let ast: DeriveInput = syn::parse(input).unwrap();
 
     // Recovers the identifiers of the structs members
-    let fields = fields_with_types(match ast.data {
+    let struct_fields = match ast.data {
         syn::Data::Struct(ref s) => &s.fields,
         _ => {
             return syn::Error::new(ast.ident.span(), "CanyonMapper only works with Structs")
                 .to_compile_error()
                 .into()
         }
-    });
+    };
+    let fields = fields_with_types(struct_fields);
+
+    // The relation fields aren't columns, so they're empty until their entities are loaded
+    let relation_fields = struct_fields
+        .iter()
+        .filter(|field| is_relation_field(field))
+        .map(|field| {
+            let ident = field.ident.as_ref().unwrap();
+            quote! { #ident: Default::default() }
+        })
+        .collect::<Vec<_>>();
 
     // Here it's where the incoming values of the DatabaseResult are wired into a new
     // instance, mapping the fields of the type against the columns
-    let init_field_values = fields.iter().map(|(_vis, ident, _ty)| {
-        let ident_name = ident.to_string();
-        quote! {
-            #ident: row.try_get(#ident_name)
-                .expect(format!("Failed to retrieve the {} field", #ident_name).as_ref())
-        }
-    });
-
-    // TODO rework this ugly piece of code in the upcoming versions
-    let init_field_values_sqlserver = fields.iter().map(|(_vis, ident, ty)| {
-        let ident_name = ident.to_string();
-
-        if get_field_type_as_string(ty) == "String" {
-            quote! {
-                #ident: row.get::<&str, &str>(#ident_name)
-                    .expect(format!("Failed to retrieve the `{}` field", #ident_name).as_ref())
-                    .to_string()
-            }
-        } else if get_field_type_as_string(ty).replace(' ', "") == "Option<i64>" {
-            quote! {
-                #ident: row.get::<i64, &str>(#ident_name)
-            }
-        } else if get_field_type_as_string(ty).replace(' ', "") == "Option<i32>" {
-            quote! {
-                #ident: row.get::<i32, &str>(#ident_name)
-            }
-        } else if get_field_type_as_string(ty).replace(' ', "") == "Option<i16>" {
-            quote! {
-                #ident: row.get::<i16, &str>(#ident_name)
-            }
-        } else if get_field_type_as_string(ty).replace(' ', "") == "Option<f32>" {
-            quote! {
-                #ident: row.get::<f32, &str>(#ident_name)
-            }
-        } else if get_field_type_as_string(ty).replace(' ', "") == "Option<f64>" {
-            quote! {
-                #ident: row.get::<f64, &str>(#ident_name)
-            }
-        } else if get_field_type_as_string(ty).replace(' ', "") == "Option<String>" {
-            quote! {
-                #ident: row.get::<&str, &str>(#ident_name)
-                    .map( |x| x.to_owned() )
-            }
-        } else if get_field_type_as_string(ty) == "NaiveDate" {
-            quote! {
-                #ident: row.get::<canyon_sql::date_time::NaiveDate, &str>(#ident_name)
-                    .expect(format!("Failed to retrieve the `{}` field", #ident_name).as_ref())
-            }
-        } else if get_field_type_as_string(ty).replace(' ', "") == "Option<NaiveDate>" {
-            quote! {
-                #ident: row.get::<canyon_sql::date_time::NaiveDate, &str>(#ident_name)
-            }
-        } else if get_field_type_as_string(ty) == "NaiveTime" {
-            quote! {
-                #ident: row.get::<canyon_sql::date_time::NaiveTime, &str>(#ident_name)
-                    .expect(format!("Failed to retrieve the `{}` field", #ident_name).as_ref())
-            }
-        } else if get_field_type_as_string(ty).replace(' ', "") == "Option<NaiveTime>" {
-            quote! {
-                #ident: row.get::<canyon_sql::date_time::NaiveTime, &str>(#ident_name)
-            }
-        } else if get_field_type_as_string(ty) == "NaiveDateTime" {
+    let init_field_values = |prefixed: bool| {
+        fields.iter().map(move |(_vis, ident, _ty)| {
+            let ident_name = ident.to_string();
+            let column = mapper_column_index(&ident_name, prefixed);
             quote! {
-                #ident: row.get::<canyon_sql::date_time::NaiveDateTime, &str>(#ident_name)
-                    .expect(format!("Failed to retrieve the `{}` field", #ident_name).as_ref())
+                #ident: row.try_get(#column)
+                    .expect(format!("Failed to retrieve the {} field", #ident_name).as_ref())
             }
-        } else if get_field_type_as_string(ty).replace(' ', "") == "Option<NaiveDateTime>" {
-            quote! {
-                #ident: row.get::<canyon_sql::date_time::NaiveDateTime, &str>(#ident_name)
-            }
-        } else if get_field_type_as_string(ty) == "DateTime" {
-            quote! {
-                #ident: row.get::<canyon_sql::date_time::DateTime, &str>(#ident_name)
-                    .expect(format!("Failed to retrieve the `{}` field", #ident_name).as_ref())
-            }
-        } else if get_field_type_as_string(ty).replace(' ', "") == "Option<DateTime>" {
-            quote! {
-                #ident: row.get::<canyon_sql::date_time::DateTime, &str>(#ident_name)
-            }
-        } else {
-            quote! {
-                #ident: row.get::<#ty, &str>(#ident_name)
-                    .expect(format!("Failed to retrieve the `{}` field", #ident_name).as_ref())
+        })
+    };
+
+    // TODO rework this ugly piece of code in the upcoming versions
+    let init_field_values_sqlserver = |prefixed: bool| {
+        fields.iter().map(move |(_vis, ident, ty)| {
+            let ident_name = ident.to_string();
+            let column = mapper_column_index(&ident_name, prefixed);
+
+            if get_field_type_as_string(ty) == "String" {
+                quote! {
+                    #ident: row.get::<&str, &str>(#column)
+                        .expect(format!("Failed to retrieve the `{}` field", #ident_name).as_ref())
+                        .to_string()
+                }
+            } else if get_field_type_as_string(ty).replace(' ', "") == "Option<i64>" {
+                quote! {
+                    #ident: row.get::<i64, &str>(#column)
+                }
+            } else if get_field_type_as_string(ty).replace(' ', "") == "Option<i32>" {
+                quote! {
+                    #ident: row.get::<i32, &str>(#column)
+                }
+            } else if get_field_type_as_string(ty).replace(' ', "") == "Option<i16>" {
+                quote! {
+                    #ident: row.get::<i16, &str>(#column)
+                }
+            } else if get_field_type_as_string(ty).replace(' ', "") == "Option<f32>" {
+                quote! {
+                    #ident: row.get::<f32, &str>(#column)
+                }
+            } else if get_field_type_as_string(ty).replace(' ', "") == "Option<f64>" {
+                quote! {
+                    #ident: row.get::<f64, &str>(#column)
+                }
+            } else if get_field_type_as_string(ty).replace(' ', "") == "Option<String>" {
+                quote! {
+                    #ident: row.get::<&str, &str>(#column)
+                        .map( |x| x.to_owned() )
+                }
+            } else if get_field_type_as_string(ty) == "NaiveDate" {
+                quote! {
+                    #ident: row.get::<canyon_sql::date_time::NaiveDate, &str>(#column)
+                        .expect(format!("Failed to retrieve the `{}` field", #ident_name).as_ref())
+                }
+            } else if get_field_type_as_string(ty).replace(' ', "") == "Option<NaiveDate>" {
+                quote! {
+                    #ident: row.get::<canyon_sql::date_time::NaiveDate, &str>(#column)
+                }
+            } else if get_field_type_as_string(ty) == "NaiveTime" {
+                quote! {
+                    #ident: row.get::<canyon_sql::date_time::NaiveTime, &str>(#column)
+                        .expect(format!("Failed to retrieve the `{}` field", #ident_name).as_ref())
+                }
+            } else if get_field_type_as_string(ty).replace(' ', "") == "Option<NaiveTime>" {
+                quote! {
+                    #ident: row.get::<canyon_sql::date_time::NaiveTime, &str>(#column)
+                }
+            } else if get_field_type_as_string(ty) == "NaiveDateTime" {
+                quote! {
+                    #ident: row.get::<canyon_sql::date_time::NaiveDateTime, &str>(#column)
+                        .expect(format!("Failed to retrieve the `{}` field", #ident_name).as_ref())
+                }
+            } else if get_field_type_as_string(ty).replace(' ', "") == "Option<NaiveDateTime>" {
+                quote! {
+                    #ident: row.get::<canyon_sql::date_time::NaiveDateTime, &str>(#column)
+                }
+            } else if get_field_type_as_string(ty) == "DateTime" {
+                quote! {
+                    #ident: row.get::<canyon_sql::date_time::DateTime, &str>(#column)
+                        .expect(format!("Failed to retrieve the `{}` field", #ident_name).as_ref())
+                }
+            } else if get_field_type_as_string(ty).replace(' ', "") == "Option<DateTime>" {
+                quote! {
+                    #ident: row.get::<canyon_sql::date_time::DateTime, &str>(#column)
+                }
+            } else {
+                quote! {
+                    #ident: row.get::<#ty, &str>(#column)
+                        .expect(format!("Failed to retrieve the `{}` field", #ident_name).as_ref())
+                }
             }
-        }
-    });
+        })
+    };
 
     // The type of the Struct
     let ty = ast.ident;
 
+    let postgres_fields = init_field_values(false);
+    let postgres_fields_with_prefix = init_field_values(true);
+    let sqlserver_fields = init_field_values_sqlserver(false);
+    let sqlserver_fields_with_prefix = init_field_values_sqlserver(true);
+
+    // The `after_load` hook of the entity, if it implements the `CanyonHooks`
+    let after_load = quote! {
+        {
+            use canyon_sql::crud::hooks::{ImplementedHooks as _, MissingHooks as _};
+            (&canyon_sql::crud::hooks::HooksProbe::<#ty>::new()).after_load(&mut entity);
+        }
+    };
+
     let tokens = quote! {
         impl canyon_sql::crud::RowMapper<Self> for #ty
         {
             fn deserialize_postgresql(row: &canyon_sql::db_clients::tokio_postgres::Row) -> #ty {
-                Self {
-                    #(#init_field_values),*
-                }
+                let mut entity = Self {
+                    #(#postgres_fields,)*
+                    #(#relation_fields,)*
+                };
+                #after_load
+                entity
             }
 
             fn deserialize_sqlserver(row: &canyon_sql::db_clients::tiberius::Row) -> #ty {
-                Self {
-                    #(#init_field_values_sqlserver),*
-                }
+                let mut entity = Self {
+                    #(#sqlserver_fields,)*
+                    #(#relation_fields,)*
+                };
+                #after_load
+                entity
+            }
+        }
+
+        impl canyon_sql::crud::PrefixedRowMapper<Self> for #ty
+        {
+            fn deserialize_postgresql_with_prefix(
+                row: &canyon_sql::db_clients::tokio_postgres::Row,
+                prefix: &str
+            ) -> #ty {
+                let mut entity = Self {
+                    #(#postgres_fields_with_prefix,)*
+                    #(#relation_fields,)*
+                };
+                #after_load
+                entity
+            }
+
+            fn deserialize_sqlserver_with_prefix(
+                row: &canyon_sql::db_clients::tiberius::Row,
+                prefix: &str
+            ) -> #ty {
+                let mut entity = Self {
+                    #(#sqlserver_fields_with_prefix,)*
+                    #(#relation_fields,)*
+                };
+                #after_load
+                entity
             }
         }
     };
@@ -641,10 +780,30 @@ pub fn implement_row_mapper_for_type(input: proc_macro::TokenStream) -> proc_mac
     tokens.into()
 }
 
+/// Generates the index used by the `RowMapper` to retrieve the column of a field,
+/// that it's its name, or its name after a runtime `prefix` for the aliased columns
+fn mapper_column_index(ident_name: &str, prefixed: bool) -> TokenStream {
+    if prefixed {
+        quote! { format!("{}{}", prefix, #ident_name).as_str() }
+    } else {
+        quote! { #ident_name }
+    }
+}
+
+/// Returns true if the field holds the entities of a relation (`#[has_many]`
+/// or `#[many_to_many]`) instead of the value of a column
+fn is_relation_field(field: &Field) -> bool {
+    field
+        .attrs
+        .iter()
+        .any(EntityFieldAnnotation::is_relation_attribute)
+}
+
 /// Helper for generate the fields data for the Custom Derives Macros
 fn filter_fields(fields: &Fields) -> Vec<(Visibility, Ident)> {
     fields
         .iter()
+        .filter(|field| !is_relation_field(field))
         .map(|field| (field.vis.clone(), field.ident.as_ref().unwrap().clone()))
         .collect::<Vec<_>>()
 }
@@ -652,6 +811,7 @@ fn filter_fields(fields: &Fields) -> Vec<(Visibility, Ident)> {
 fn fields_with_types(fields: &Fields) -> Vec<(Visibility, Ident, Type)> {
     fields
         .iter()
+        .filter(|field| !is_relation_field(field))
         .map(|field| {
             (
                 field.vis.clone(),
@@ -1,6 +1,10 @@
 use proc_macro2::TokenStream;
 use quote::quote;
 
+use super::{
+    render_for_database_type, render_for_datasource, soft_delete_tokens, unsupported_operation,
+    with_hooks, Hooked,
+};
 use crate::utils::macro_tokens::MacroTokens;
 
 /// Generates the TokenStream for the __delete() CRUD operation
@@ -9,79 +13,246 @@ pub fn generate_delete_tokens(macro_data: &MacroTokens, table_schema_data: &Stri
     let ty = macro_data.ty;
 
     let fields = macro_data.get_struct_fields();
-    let pk = macro_data.get_primary_key_annotation();
+    let pk_indexes = macro_data.get_pk_indexes();
 
-    if let Some(primary_key) = pk {
-        let pk_field = fields
+    if !pk_indexes.is_empty() {
+        // A composite primary key matches the row by every column that composes it,
+        // and the versioned entities by its `#[version]` too, after the primary key
+        let version = macro_data.get_version_field();
+        let pk_conditions = pk_indexes
             .iter()
-            .find(|f| *f.to_string() == primary_key)
-            .expect(
-                "Something really bad happened finding the Ident for the pk field on the delete",
-            );
-        let pk_field_value =
-            quote! { &self.#pk_field as &dyn canyon_sql::crud::bounds::QueryParameter<'_> };
+            .map(|idx| &fields[*idx])
+            .chain(version)
+            .enumerate()
+            .map(|(placeholder, field)| {
+                let column = format!("{:?}", field.to_string());
+                let position = placeholder + 1;
+                quote! { (#column, #position) }
+            })
+            .collect::<Vec<TokenStream>>();
+        let pk_filter = quote! {
+            canyon_sql::query::statement::Clause::Where(
+                canyon_sql::query::statement::Predicate::all_equal(&[#(#pk_conditions),*])
+            )
+        };
+        let force_stmt = |render: TokenStream| {
+            quote! {
+                canyon_sql::query::statement::Statement::from(
+                    canyon_sql::query::statement::Delete::new(#table_schema_data).clause(#pk_filter)
+                )#render
+            }
+        };
 
-        quote! {
-            /// Deletes from a database entity the row that matches
-            /// the current instance of a T type, returning a result
-            /// indicating a possible failure querying the database.
-            async fn delete(&self) -> Result<(), Box<(dyn std::error::Error + Send + Sync + 'static)>> {
-                let stmt = format!("DELETE FROM {} WHERE {:?} = $1", #table_schema_data, #primary_key);
+        // The entities with a `#[soft_delete]` field marks the row instead of removing it,
+        // with the `value` of the `SoftDelete`
+        let soft_delete = macro_data
+            .get_soft_delete_field()
+            .map(|(field, soft_delete)| {
+                (
+                    format!("{:?}", field.to_string()),
+                    soft_delete_tokens(soft_delete),
+                )
+            });
+        // Only the alive rows are marked, and only the deleted ones are restored,
+        // so the time of the deletion is never overwritten. The versioned rows are
+        // matched by their version, that's left untouched, since only the mark changes
+        let mark_stmt = |value: TokenStream, state: TokenStream, render: TokenStream| {
+            let (column, soft_delete) = soft_delete.as_ref()?;
+            Some(quote! {
+                canyon_sql::query::statement::Statement::from(
+                    canyon_sql::query::statement::Update::new(#table_schema_data, &[])
+                        .computed(#column, #soft_delete.#value())
+                        .clause(#pk_filter)
+                        .clause(canyon_sql::query::statement::Clause::And(
+                            #soft_delete.#state(#column)
+                        ))
+                )#render
+            })
+        };
+        let stmt = |render: TokenStream| {
+            mark_stmt(quote! { mark }, quote! { alive }, render.clone())
+                .unwrap_or_else(|| force_stmt(render))
+        };
+        let default_stmt = stmt(render_for_datasource(quote! { "" }));
+        let datasource_stmt = stmt(render_for_datasource(quote! { datasource_name }));
+        let sql_stmt = stmt(render_for_database_type());
+        let force_default_stmt = force_stmt(render_for_datasource(quote! { "" }));
+        let force_datasource_stmt = force_stmt(render_for_datasource(quote! { datasource_name }));
+
+        let pk_fields = pk_indexes.iter().map(|idx| &fields[*idx]).chain(version);
+        let pk_field_values = quote! {
+            #(&self.#pk_fields as &dyn canyon_sql::crud::bounds::QueryParameter<'_>),*
+        };
 
+        // The versioned entities fails with a conflict if no row matches their version
+        let launch = |stmt: &TokenStream, datasource_name: &TokenStream| match version {
+            Some(version) => quote! {
+                let affected_rows = <#ty as canyon_sql::crud::Transaction<#ty>>::execute(
+                    #stmt,
+                    &[#pk_field_values],
+                    #datasource_name
+                ).await?;
+
+                canyon_sql::crud::locking::VersionConflict::check::<#ty>(
+                    i64::from(self.#version), affected_rows
+                ).map_err(|conflict| conflict.into())
+            },
+            None => quote! {
                 let result = <#ty as canyon_sql::crud::Transaction<#ty>>::query(
-                    stmt,
-                    &[#pk_field_value],
-                    ""
+                    #stmt,
+                    &[#pk_field_values],
+                    #datasource_name
                 ).await;
 
                 if let Err(error) = result {
                     Err(error)
                 } else { Ok(()) }
+            },
+        };
+
+        let restore = |datasource_name: TokenStream| match mark_stmt(
+            quote! { unmark },
+            quote! { deleted },
+            render_for_datasource(datasource_name.clone()),
+        ) {
+            Some(stmt) => launch(&stmt, &datasource_name),
+            None => unsupported_operation("restore", "soft_delete"),
+        };
+        let restore_datasource = restore(quote! { datasource_name });
+        let restore = restore(quote! { "" });
+
+        let hooked_delete = |stmt: &TokenStream, datasource_name: TokenStream| {
+            with_hooks(
+                ty,
+                "delete",
+                Hooked::One(quote! { self }),
+                quote! {},
+                launch(stmt, &datasource_name),
+            )
+        };
+        let hooked_delete_datasource = hooked_delete(&datasource_stmt, quote! { datasource_name });
+        let hooked_force_delete_datasource =
+            hooked_delete(&force_datasource_stmt, quote! { datasource_name });
+        let hooked_force_delete = hooked_delete(&force_default_stmt, quote! { "" });
+        let hooked_delete = hooked_delete(&default_stmt, quote! { "" });
+
+        quote! {
+            /// Returns the SQL of the `delete` operation for the `database_type`,
+            /// along with its parameters, without querying the database
+            fn delete_sql(&self, database_type: canyon_sql::crud::DatabaseType)
+                -> Result<
+                    (String, Vec<&dyn canyon_sql::crud::bounds::QueryParameter<'_>>),
+                    Box<dyn std::error::Error + Sync + std::marker::Send>
+                >
+            {
+                Ok((#sql_stmt, vec![#pk_field_values]))
+            }
+
+            /// Deletes from a database entity the row that matches
+            /// the current instance of a T type, returning a result
+            /// indicating a possible failure querying the database.
+            ///
+            /// The row is only marked as deleted if the entity has a `#[soft_delete]` field
+            async fn delete(&self) -> Result<(), Box<(dyn std::error::Error + Send + Sync + 'static)>> {
+                #hooked_delete
             }
 
             /// Deletes from a database entity the row that matches
             /// the current instance of a T type, returning a result
             /// indicating a possible failure querying the database with the specified datasource.
+            ///
+            /// The row is only marked as deleted if the entity has a `#[soft_delete]` field
             async fn delete_datasource<'a>(&self, datasource_name: &'a str)
                 -> Result<(), Box<(dyn std::error::Error + Send + Sync + 'static)>>
             {
-                let stmt = format!("DELETE FROM {} WHERE {:?} = $1", #table_schema_data, #primary_key);
+                #hooked_delete_datasource
+            }
 
-                let result = <#ty as canyon_sql::crud::Transaction<#ty>>::query(
-                    stmt,
-                    &[#pk_field_value],
-                    datasource_name
-                ).await;
+            /// Removes from a database entity the row that matches
+            /// the current instance of a T type, even if it has a `#[soft_delete]` field
+            async fn force_delete(&self) -> Result<(), Box<(dyn std::error::Error + Send + Sync + 'static)>> {
+                #hooked_force_delete
+            }
 
-                if let Err(error) = result {
-                    Err(error)
-                } else { Ok(()) }
+            /// Removes from a database entity the row that matches the current instance
+            /// of a T type, even if it has a `#[soft_delete]` field, with the specified datasource
+            async fn force_delete_datasource<'a>(&self, datasource_name: &'a str)
+                -> Result<(), Box<(dyn std::error::Error + Send + Sync + 'static)>>
+            {
+                #hooked_force_delete_datasource
+            }
+
+            /// Marks back as alive the soft deleted row that matches
+            /// the current instance of a T type
+            async fn restore(&self) -> Result<(), Box<(dyn std::error::Error + Send + Sync + 'static)>> {
+                #restore
+            }
+
+            /// Marks back as alive the soft deleted row that matches
+            /// the current instance of a T type with the specified datasource
+            async fn restore_datasource<'a>(&self, datasource_name: &'a str)
+                -> Result<(), Box<(dyn std::error::Error + Send + Sync + 'static)>>
+            {
+                #restore_datasource
             }
         }
     } else {
         // Delete operation over an instance isn't available without declaring a primary key.
         // The delete querybuilder variant must be used for the case when there's no pk declared
+        let unsupported_delete_sql = unsupported_operation("delete_sql", "primary_key");
+        let unsupported_delete = unsupported_operation("delete", "primary_key");
+        let unsupported_delete_datasource =
+            unsupported_operation("delete_datasource", "primary_key");
+        let unsupported_force_delete = unsupported_operation("force_delete", "primary_key");
+        let unsupported_force_delete_datasource =
+            unsupported_operation("force_delete_datasource", "primary_key");
+        let unsupported_restore = unsupported_operation("restore", "primary_key");
+        let unsupported_restore_datasource =
+            unsupported_operation("restore_datasource", "primary_key");
         quote! {
+            fn delete_sql(&self, database_type: canyon_sql::crud::DatabaseType)
+                -> Result<
+                    (String, Vec<&dyn canyon_sql::crud::bounds::QueryParameter<'_>>),
+                    Box<dyn std::error::Error + Sync + std::marker::Send>
+                >
+            {
+                #unsupported_delete_sql
+            }
+
             async fn delete(&self)
                 -> Result<(), Box<dyn std::error::Error + Sync + std::marker::Send>>
             {
-                Err(std::io::Error::new(
-                    std::io::ErrorKind::Unsupported,
-                    "You can't use the 'delete' method on a \
-                    CanyonEntity that does not have a #[primary_key] annotation. \
-                    If you need to perform an specific search, use the Querybuilder instead."
-                ).into_inner().unwrap())
+                #unsupported_delete
             }
 
             async fn delete_datasource<'a>(&self, datasource_name: &'a str)
                 -> Result<(), Box<dyn std::error::Error + Sync + std::marker::Send>>
             {
-                Err(std::io::Error::new(
-                    std::io::ErrorKind::Unsupported,
-                    "You can't use the 'delete_datasource' method on a \
-                    CanyonEntity that does not have a #[primary_key] annotation. \
-                    If you need to perform an specific search, use the Querybuilder instead."
-                ).into_inner().unwrap())
+                #unsupported_delete_datasource
+            }
+
+            async fn force_delete(&self)
+                -> Result<(), Box<dyn std::error::Error + Sync + std::marker::Send>>
+            {
+                #unsupported_force_delete
+            }
+
+            async fn force_delete_datasource<'a>(&self, datasource_name: &'a str)
+                -> Result<(), Box<dyn std::error::Error + Sync + std::marker::Send>>
+            {
+                #unsupported_force_delete_datasource
+            }
+
+            async fn restore(&self)
+                -> Result<(), Box<dyn std::error::Error + Sync + std::marker::Send>>
+            {
+                #unsupported_restore
+            }
+
+            async fn restore_datasource<'a>(&self, datasource_name: &'a str)
+                -> Result<(), Box<dyn std::error::Error + Sync + std::marker::Send>>
+            {
+                #unsupported_restore_datasource
             }
         }
     }
@@ -1,30 +1,36 @@
 use proc_macro2::TokenStream;
 use quote::quote;
 
+use super::{
+    fill_timestamps, insert_fields, render_for_database_type, render_for_datasource, with_hooks,
+    Hooked,
+};
 use crate::utils::macro_tokens::MacroTokens;
 
 /// Generates the TokenStream for the _insert_result() CRUD operation
 pub fn generate_insert_tokens(macro_data: &MacroTokens, table_schema_data: &String) -> TokenStream {
     let ty = macro_data.ty;
 
-    // Retrieves the fields of the Struct as a collection of Strings, already parsed
-    // the condition of remove the primary key if it's present and it's autoincremental
-    let insert_columns = macro_data.get_column_names_pk_parsed().join(", ");
+    // Retrieves the fields of the Struct that are sent to the database, with the
+    // position of the primary key, whose value is removed if it's present
+    let (fields, pk_index) = insert_fields(macro_data);
 
-    // Returns a String with the generic $x placeholder for the query parameters.
-    let placeholders = macro_data.placeholders_generator();
-
-    // Retrieves the fields of the Struct
-    let fields = macro_data.get_struct_fields();
+    // The fields of the Struct as a collection of Strings, already parsed
+    // the condition of remove the primary key if it's present
+    let insert_columns = macro_data
+        .get_column_names_pk_parsed()
+        .into_iter()
+        .filter(|column| fields.iter().any(|field| *column == format!("\"{field}\"")))
+        .collect::<Vec<_>>();
 
     let insert_values = fields.iter().map(|ident| {
         quote! { &self.#ident }
     });
-    let insert_values_cloned = insert_values.clone();
+    let insert_values_sql = insert_values.clone();
 
     let primary_key = macro_data.get_primary_key_annotation();
 
-    let remove_pk_value_from_fn_entry = if let Some(pk_index) = macro_data.get_pk_index() {
+    let remove_pk_value_from_fn_entry = if let Some(pk_index) = pk_index {
         quote! { values.remove(#pk_index) }
     } else {
         quote! {}
@@ -35,6 +41,22 @@ pub fn generate_insert_tokens(macro_data: &MacroTokens, table_schema_data: &Stri
         .into_iter()
         .find(|(i, _t)| Some(i.to_string()) == primary_key);
 
+    // The generated primary key is read back after the insert
+    let returning = pk_ident_type
+        .as_ref()
+        .map(|_| quote! { .returning(&[#primary_key]) });
+    let insert_stmt = |render: TokenStream| {
+        quote! {
+            canyon_sql::query::statement::Statement::from(
+                canyon_sql::query::statement::Insert::new(
+                    #table_schema_data, &[#(#insert_columns),*], 1
+                )#returning
+            )#render
+        }
+    };
+    let stmt = insert_stmt(render_for_datasource(quote! { datasource_name }));
+    let stmt_sql = insert_stmt(render_for_database_type());
+
     let insert_transaction = if let Some(pk_data) = &pk_ident_type {
         let pk_ident = &pk_data.0;
         let pk_type = &pk_data.1;
@@ -42,13 +64,7 @@ pub fn generate_insert_tokens(macro_data: &MacroTokens, table_schema_data: &Stri
         quote! {
             #remove_pk_value_from_fn_entry;
 
-            let stmt = format!(
-                "INSERT INTO {} ({}) VALUES ({}) RETURNING {}",
-                #table_schema_data,
-                #insert_columns,
-                #placeholders,
-                #primary_key
-            );
+            let stmt = #stmt;
 
             let result = <#ty as canyon_sql::crud::Transaction<#ty>>::query(
                 stmt,
@@ -83,13 +99,7 @@ pub fn generate_insert_tokens(macro_data: &MacroTokens, table_schema_data: &Stri
         }
     } else {
         quote! {
-            let stmt = format!(
-                "INSERT INTO {} ({}) VALUES ({})",
-                #table_schema_data,
-                #insert_columns,
-                #placeholders,
-                #primary_key
-            );
+            let stmt = #stmt;
 
             let result = <#ty as canyon_sql::crud::Transaction<#ty>>::query(
                 stmt,
@@ -105,7 +115,32 @@ pub fn generate_insert_tokens(macro_data: &MacroTokens, table_schema_data: &Stri
         }
     };
 
+    let timestamps = fill_timestamps(macro_data, quote! { entity });
+    let hooked_insert = with_hooks(
+        ty,
+        "insert",
+        Hooked::One(quote! { &mut *self }),
+        quote! {
+            #timestamps
+            canyon_sql::crud::validation::Validate::validate(&*entity)?;
+        },
+        quote! {
+            let mut values: Vec<&dyn canyon_sql::crud::bounds::QueryParameter<'_>> = vec![#(#insert_values),*];
+            #insert_transaction
+        },
+    );
+
     quote! {
+        /// Returns the SQL of the `insert` operation for the `database_type`,
+        /// along with its parameters, without querying the database
+        fn insert_sql(&self, database_type: canyon_sql::crud::DatabaseType)
+            -> (String, Vec<&dyn canyon_sql::crud::bounds::QueryParameter<'_>>)
+        {
+            let mut values: Vec<&dyn canyon_sql::crud::bounds::QueryParameter<'_>> = vec![#(#insert_values_sql),*];
+            #remove_pk_value_from_fn_entry;
+            (#stmt_sql, values)
+        }
+
         /// Inserts into a database entity the current data in `self`, generating a new
         /// entry (row), returning the `PRIMARY KEY` = `self.<pk_field>` with the specified
         /// datasource by it's `datasouce name`, defined in the configuration file.
@@ -148,8 +183,7 @@ pub fn generate_insert_tokens(macro_data: &MacroTokens, table_schema_data: &Stri
             -> Result<(), Box<dyn std::error::Error + Sync + std::marker::Send>>
         {
             let datasource_name = "";
-            let mut values: Vec<&dyn canyon_sql::crud::bounds::QueryParameter<'_>> = vec![#(#insert_values),*];
-            #insert_transaction
+            #hooked_insert
         }
 
         /// Inserts into a database entity the current data in `self`, generating a new
@@ -193,10 +227,132 @@ pub fn generate_insert_tokens(macro_data: &MacroTokens, table_schema_data: &Stri
         async fn insert_datasource<'a>(&mut self, datasource_name: &'a str)
             -> Result<(), Box<dyn std::error::Error + Sync + std::marker::Send>>
         {
-            let mut values: Vec<&dyn canyon_sql::crud::bounds::QueryParameter<'_>> = vec![#(#insert_values_cloned),*];
+            #hooked_insert
+        }
+
+    }
+}
+
+/// Generates the TokenStream for the _insert_returning_all() CRUD operation, which
+/// reads back every column of the new row after the insert, so the values generated
+/// by the database (defaults, sequences, computed columns...) are reassigned to `self`
+pub fn generate_insert_returning_all_tokens(
+    macro_data: &MacroTokens,
+    table_schema_data: &String,
+) -> TokenStream {
+    let ty = macro_data.ty;
+
+    // Retrieves the fields of the Struct that are sent to the database, with the
+    // position of the primary key, whose value is removed if it's present
+    let (fields, pk_index) = insert_fields(macro_data);
+
+    // The fields of the Struct as a collection of Strings, already parsed
+    // the condition of remove the primary key if it's present
+    let insert_columns = macro_data
+        .get_column_names_pk_parsed()
+        .into_iter()
+        .filter(|column| fields.iter().any(|field| *column == format!("\"{field}\"")))
+        .collect::<Vec<_>>();
+
+    let insert_values = fields.iter().map(|ident| {
+        quote! { &self.#ident }
+    });
+
+    let remove_pk_value_from_fn_entry = if let Some(pk_index) = pk_index {
+        quote! { values.remove(#pk_index); }
+    } else {
+        quote! {}
+    };
+
+    let insert_transaction = quote! {
+        #remove_pk_value_from_fn_entry
+
+        // Rendered as a `RETURNING *` clause on PostgreSQL and as an `OUTPUT inserted.*`
+        // one on SQL Server, so we are able to read the full row on both databases
+        let stmt = canyon_sql::query::statement::Statement::from(
+            canyon_sql::query::statement::Insert::new(
+                #table_schema_data, &[#(#insert_columns),*], 1
+            ).returning(&["*"])
+        ).render(datasource_name)?;
+
+        let result = <#ty as canyon_sql::crud::Transaction<#ty>>::query(
+            stmt,
+            values,
+            datasource_name
+        ).await;
+
+        match result?.get_entities::<#ty>().into_iter().next() {
+            Some(entity) => {
+                *self = entity;
+                Ok(())
+            },
+            None => Err(
+                std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    "No value found on the returning clause"
+                ).into()
+            )
+        }
+    };
+
+    let timestamps = fill_timestamps(macro_data, quote! { entity });
+    let hooked_insert = with_hooks(
+        ty,
+        "insert",
+        Hooked::One(quote! { &mut *self }),
+        quote! {
+            #timestamps
+            canyon_sql::crud::validation::Validate::validate(&*entity)?;
+        },
+        quote! {
+            let mut values: Vec<&dyn canyon_sql::crud::bounds::QueryParameter<'_>> = vec![#(#insert_values),*];
             #insert_transaction
+        },
+    );
+
+    quote! {
+        /// Inserts into a database entity the current data in `self`, generating a new
+        /// entry (row), and reads back *every* column of the inserted row, reassigning
+        /// all the fields of `self` with the values stored in the database.
+        ///
+        /// Unlike the `insert` operation, which only retrieves the `PRIMARY KEY`, this
+        /// one is useful when the table has columns with server side defaults, like
+        /// timestamps, sequences or generated columns, that otherwise will stay stale
+        /// in your instance.
+        ///
+        /// This operation returns a result type, indicating a possible failure querying the database.
+        ///
+        /// ## *Examples*
+        ///```
+        /// let mut lec: League = League {
+        ///     id: Default::default(),
+        ///     ext_id: 1,
+        ///     slug: "LEC".to_string(),
+        ///     name: "League Europe Champions".to_string(),
+        ///     region: "EU West".to_string(),
+        ///     image_url: "https://lec.eu".to_string(),
+        /// };
+        ///
+        /// lec.insert_returning_all().await.expect("Failed insert operation");
+        /// ```
+        async fn insert_returning_all<'a>(&mut self)
+            -> Result<(), Box<dyn std::error::Error + Sync + std::marker::Send>>
+        {
+            let datasource_name = "";
+            #hooked_insert
         }
 
+        /// Inserts into a database entity the current data in `self`, generating a new
+        /// entry (row), and reads back *every* column of the inserted row, reassigning
+        /// all the fields of `self` with the values stored in the database, with the
+        /// specified datasource by it's `datasouce name`, defined in the configuration file.
+        ///
+        /// This operation returns a result type, indicating a possible failure querying the database.
+        async fn insert_returning_all_datasource<'a>(&mut self, datasource_name: &'a str)
+            -> Result<(), Box<dyn std::error::Error + Sync + std::marker::Send>>
+        {
+            #hooked_insert
+        }
     }
 }
 
@@ -212,14 +368,17 @@ pub fn generate_multiple_insert_tokens(
 ) -> TokenStream {
     let ty = macro_data.ty;
 
-    // Retrieves the fields of the Struct as continuous String
-    let column_names = macro_data.get_struct_fields_as_strings();
+    // Retrieves the fields of the Struct that are sent to the database
+    let (fields, _) = insert_fields(macro_data);
+    let timestamps = fill_timestamps(macro_data, quote! { entity });
 
-    // Retrieves the fields of the Struct
-    let fields = macro_data.get_struct_fields();
+    // The column names are quote escaped for avoid the upper case column name mangling
+    let column_names = fields
+        .iter()
+        .map(|field| format!("\"{field}\""))
+        .collect::<Vec<String>>();
 
     let macro_fields = fields.iter().map(|field| quote! { &instance.#field });
-    let macro_fields_cloned = macro_fields.clone();
 
     let pk = macro_data.get_primary_key_annotation().unwrap_or_default();
 
@@ -232,67 +391,30 @@ pub fn generate_multiple_insert_tokens(
         let pk_ident = &pk_data.0;
         let pk_type = &pk_data.1;
 
-        quote! {
-            mapped_fields = #column_names
-                .split(", ")
-                .map( |column_name| format!("\"{}\"", column_name))
-                .collect::<Vec<String>>()
-                .join(", ");
-
-            let mut split = mapped_fields.split(", ")
-                .collect::<Vec<&str>>();
-
-            let pk_value_index = split.iter()
-                .position(|pk| *pk == format!("\"{}\"", #pk).as_str())
-                .expect("Error. No primary key found when should be there");
-            split.retain(|pk| *pk != format!("\"{}\"", #pk).as_str());
-            mapped_fields = split.join(", ").to_string();
-
-            let mut fields_placeholders = String::new();
-
-            let mut elements_counter = 0;
-            let mut values_counter = 1;
-            let values_arr_len = final_values.len();
+        // The primary key is generated by the database, so its value is removed
+        // from the values of every instance
+        let pk_index = fields
+            .iter()
+            .position(|field| *field == pk)
+            .expect("The primary key is always a field of the struct");
+        let insert_columns = column_names
+            .iter()
+            .enumerate()
+            .filter(|(idx, _)| *idx != pk_index)
+            .map(|(_, column)| column);
 
+        quote! {
             for vector in final_values.iter_mut() {
-                let mut inner_counter = 0;
-                fields_placeholders.push('(');
-                vector.remove(pk_value_index);
-
-                for _value in vector.iter() {
-                    if inner_counter < vector.len() - 1 {
-                        fields_placeholders.push_str(&("$".to_owned() + &values_counter.to_string() + ","));
-                    } else {
-                        fields_placeholders.push_str(&("$".to_owned() + &values_counter.to_string()));
-                    }
-
-                    inner_counter += 1;
-                    values_counter += 1;
-                }
-
-                elements_counter += 1;
-
-                if elements_counter < values_arr_len {
-                    fields_placeholders.push_str("), ");
-                } else {
-                    fields_placeholders.push(')');
-                }
+                vector.remove(#pk_index);
             }
 
-            let stmt = format!(
-                "INSERT INTO {} ({}) VALUES {} RETURNING {}",
-                #table_schema_data,
-                mapped_fields,
-                fields_placeholders,
-                #pk
-            );
-
-            let mut v_arr = Vec::new();
-            for arr in final_values.iter() {
-                for value in arr {
-                    v_arr.push(*value)
-                }
-            }
+            let stmt = canyon_sql::query::statement::Statement::from(
+                canyon_sql::query::statement::Insert::new(
+                    #table_schema_data, &[#(#insert_columns),*], final_values.len()
+                ).returning(&[#pk])
+            ).render(datasource_name)?;
+
+            let v_arr = final_values.into_iter().flatten().collect::<Vec<_>>();
 
             let result = <#ty as canyon_sql::crud::Transaction<#ty>>::query(
                 stmt,
@@ -333,58 +455,13 @@ pub fn generate_multiple_insert_tokens(
         }
     } else {
         quote! {
-            mapped_fields = #column_names
-                .split(", ")
-                .map( |column_name| format!("\"{}\"", column_name))
-                .collect::<Vec<String>>()
-                .join(", ");
-
-            let mut split = mapped_fields.split(", ")
-                .collect::<Vec<&str>>();
-
-            let mut fields_placeholders = String::new();
-
-            let mut elements_counter = 0;
-            let mut values_counter = 1;
-            let values_arr_len = final_values.len();
+            let stmt = canyon_sql::query::statement::Statement::from(
+                canyon_sql::query::statement::Insert::new(
+                    #table_schema_data, &[#(#column_names),*], final_values.len()
+                )
+            ).render(datasource_name)?;
 
-            for vector in final_values.iter_mut() {
-                let mut inner_counter = 0;
-                fields_placeholders.push('(');
-
-                for _value in vector.iter() {
-                    if inner_counter < vector.len() - 1 {
-                        fields_placeholders.push_str(&("$".to_owned() + &values_counter.to_string() + ","));
-                    } else {
-                        fields_placeholders.push_str(&("$".to_owned() + &values_counter.to_string()));
-                    }
-
-                    inner_counter += 1;
-                    values_counter += 1;
-                }
-
-                elements_counter += 1;
-
-                if elements_counter < values_arr_len {
-                    fields_placeholders.push_str("), ");
-                } else {
-                    fields_placeholders.push(')');
-                }
-            }
-
-            let stmt = format!(
-                "INSERT INTO {} ({}) VALUES {}",
-                #table_schema_data,
-                mapped_fields,
-                fields_placeholders
-            );
-
-            let mut v_arr = Vec::new();
-            for arr in final_values.iter() {
-                for value in arr {
-                    v_arr.push(*value)
-                }
-            }
+            let v_arr = final_values.into_iter().flatten().collect::<Vec<_>>();
 
             let result = <#ty as canyon_sql::crud::Transaction<#ty>>::query(
                 stmt,
@@ -399,6 +476,31 @@ pub fn generate_multiple_insert_tokens(
         }
     };
 
+    let hooked_multi_insert = with_hooks(
+        ty,
+        "insert",
+        Hooked::Each(quote! { instances }),
+        quote! {
+            #timestamps
+            canyon_sql::crud::validation::Validate::validate(&*entity)?;
+        },
+        quote! {
+            let mut final_values: Vec<Vec<&dyn QueryParameter<'_>>> = Vec::new();
+            for instance in instances.iter() {
+                let intermediate: &[&dyn QueryParameter<'_>] = &[#(#macro_fields),*];
+
+                let mut longer_lived: Vec<&dyn QueryParameter<'_>> = Vec::new();
+                for value in intermediate.into_iter() {
+                    longer_lived.push(*value)
+                }
+
+                final_values.push(longer_lived)
+            }
+
+            #multi_insert_transaction
+        },
+    );
+
     quote! {
         /// Inserts multiple instances of some type `T` into its related table.
         ///
@@ -439,21 +541,7 @@ pub fn generate_multiple_insert_tokens(
             use canyon_sql::crud::bounds::QueryParameter;
             let datasource_name = "";
 
-            let mut final_values: Vec<Vec<&dyn QueryParameter<'_>>> = Vec::new();
-            for instance in instances.iter() {
-                let intermediate: &[&dyn QueryParameter<'_>] = &[#(#macro_fields),*];
-
-                let mut longer_lived: Vec<&dyn QueryParameter<'_>> = Vec::new();
-                for value in intermediate.into_iter() {
-                    longer_lived.push(*value)
-                }
-
-                final_values.push(longer_lived)
-            }
-
-            let mut mapped_fields: String = String::new();
-
-            #multi_insert_transaction
+            #hooked_multi_insert
         }
 
         /// Inserts multiple instances of some type `T` into its related table with the specified
@@ -495,21 +583,7 @@ pub fn generate_multiple_insert_tokens(
         ) {
             use canyon_sql::crud::bounds::QueryParameter;
 
-            let mut final_values: Vec<Vec<&dyn QueryParameter<'_>>> = Vec::new();
-            for instance in instances.iter() {
-                let intermediate: &[&dyn QueryParameter<'_>] = &[#(#macro_fields_cloned),*];
-
-                let mut longer_lived: Vec<&dyn QueryParameter<'_>> = Vec::new();
-                for value in intermediate.into_iter() {
-                    longer_lived.push(*value)
-                }
-
-                final_values.push(longer_lived)
-            }
-
-            let mut mapped_fields: String = String::new();
-
-            #multi_insert_transaction
+            #hooked_multi_insert
         }
     }
 }
@@ -1,4 +1,165 @@
 pub mod delete;
 pub mod insert;
+pub mod relations;
 pub mod select;
 pub mod update;
+pub mod validate;
+
+use canyon_crud::{soft_delete::SoftDelete, timestamps::TimestampSource};
+use canyon_observer::manager::field_annotation::EntityFieldAnnotation;
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote};
+
+use crate::utils::macro_tokens::MacroTokens;
+
+/// The call that renders a `Statement` for the database of the `datasource_name`
+fn render_for_datasource(datasource_name: TokenStream) -> TokenStream {
+    quote! { .render(#datasource_name)? }
+}
+
+/// The call that generates the SQL of a `Statement` for the `database_type`
+/// argument of the operations that inspect the SQL without executing it
+fn render_for_database_type() -> TokenStream {
+    quote! { .to_sql(canyon_sql::query::dialect::dialect(database_type)) }
+}
+
+/// The `Err` returned by the `operation` of an entity that does not declare the
+/// `annotation` the operation relies on, like the `#[primary_key]` of a single row
+fn unsupported_operation(operation: &str, annotation: &str) -> TokenStream {
+    let mut message = format!(
+        "You can't use the '{operation}' operation on a \
+        CanyonEntity that does not have a #[{annotation}] annotation."
+    );
+    if annotation == "primary_key" {
+        message
+            .push_str(" If you need to perform an specific search, use the Querybuilder instead.");
+    }
+    quote! {
+        Err(std::io::Error::new(std::io::ErrorKind::Unsupported, #message).into())
+    }
+}
+
+/// The instances whose hooks are invoked around an operation
+enum Hooked {
+    /// A single instance, like `self`, as an expression that borrows it
+    One(TokenStream),
+    /// Every instance of a `&mut [&mut T]` slice, launched by the same operation
+    Each(TokenStream),
+}
+
+impl Hooked {
+    /// Iterates the instances mutably, for the `before_<hook>` and the preparation
+    fn iter_mut(&self) -> TokenStream {
+        match self {
+            Self::One(entity) => quote! { [#entity] },
+            Self::Each(instances) => {
+                quote! { #instances.iter_mut().map(|instance| &mut **instance) }
+            }
+        }
+    }
+
+    /// Iterates the instances, for the `after_<hook>`
+    fn iter(&self) -> TokenStream {
+        match self {
+            Self::One(entity) => quote! { [&*#entity] },
+            Self::Each(instances) => quote! { #instances.iter().map(|instance| &**instance) },
+        }
+    }
+}
+
+/// Wraps the `operation` over the `hooked` instances with the `before_<hook>` and
+/// `after_<hook>` methods of its `CanyonHooks`, if the type implements them.
+///
+/// Every instance, available as `entity`, runs its `before_<hook>` followed by the
+/// `prepare` steps, like its validation. The operation is aborted if any of them
+/// fails, and the `after_<hook>` is only invoked if the operation succeeds
+fn with_hooks(
+    ty: &Ident,
+    hook: &str,
+    hooked: Hooked,
+    prepare: TokenStream,
+    operation: TokenStream,
+) -> TokenStream {
+    let before = format_ident!("before_{}", hook);
+    let after = format_ident!("after_{}", hook);
+    let (iter_mut, iter) = (hooked.iter_mut(), hooked.iter());
+
+    quote! {
+        use canyon_sql::crud::hooks::{ImplementedHooks as _, MissingHooks as _};
+
+        for entity in #iter_mut {
+            (&canyon_sql::crud::hooks::HooksProbe::<#ty>::new()).#before(entity)?;
+            #prepare
+        }
+        let result: Result<(), Box<dyn std::error::Error + Sync + std::marker::Send>> = {
+            #operation
+        };
+        if result.is_ok() {
+            for entity in #iter {
+                (&canyon_sql::crud::hooks::HooksProbe::<#ty>::new()).#after(entity);
+            }
+        }
+        result
+    }
+}
+
+/// Assigns the current time of the client to the `#[created_at]` and `#[updated_at]`
+/// fields of the `entity` that takes it from the client, before it's inserted
+fn fill_timestamps(macro_data: &MacroTokens, entity: TokenStream) -> TokenStream {
+    let fields = macro_data
+        .get_timestamp_annotations()
+        .into_iter()
+        .filter_map(|(field, _, annotation)| match annotation {
+            EntityFieldAnnotation::CreatedAt(TimestampSource::Client)
+            | EntityFieldAnnotation::UpdatedAt(TimestampSource::Client) => Some(field),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    if fields.is_empty() {
+        return quote! {};
+    }
+    quote! {
+        let timestamp = canyon_sql::crud::timestamps::now();
+        #(#entity.#fields = timestamp.into();)*
+    }
+}
+
+/// The fields of the entity that are sent to the database by the inserts, without
+/// the ones generated by the database, along with the position of the primary key
+/// between them, that it's removed from the values of the single inserts
+fn insert_fields(macro_data: &MacroTokens) -> (Vec<Ident>, Option<usize>) {
+    let database_timestamps = macro_data.get_database_timestamp_fields();
+    let fields = macro_data
+        .get_struct_fields()
+        .into_iter()
+        .filter(|field| !database_timestamps.contains(&field))
+        .collect::<Vec<_>>();
+
+    let pk_index = macro_data.get_pk_index().and_then(|_| {
+        let pk = macro_data.get_primary_key_annotation()?;
+        fields.iter().position(|field| *field == pk)
+    });
+
+    (fields, pk_index)
+}
+
+/// The tokens of the `SoftDelete` of the `#[soft_delete]` field
+fn soft_delete_tokens(soft_delete: SoftDelete) -> TokenStream {
+    match soft_delete {
+        SoftDelete::Timestamp => quote! { canyon_sql::crud::soft_delete::SoftDelete::Timestamp },
+        SoftDelete::Flag => quote! { canyon_sql::crud::soft_delete::SoftDelete::Flag },
+    }
+}
+
+/// The predicate that leaves out the soft deleted rows from the reads of the
+/// entity, or `None` if it has no `#[soft_delete]` field
+fn alive_rows(macro_data: &MacroTokens) -> Option<TokenStream> {
+    macro_data
+        .get_soft_delete_field()
+        .map(|(field, soft_delete)| {
+            let column = format!("\"{field}\"");
+            let soft_delete = soft_delete_tokens(soft_delete);
+            quote! { #soft_delete.alive(#column) }
+        })
+}
@@ -0,0 +1,185 @@
+use canyon_observer::manager::field_annotation::EntityFieldAnnotation;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::Type;
+
+use crate::utils::macro_tokens::MacroTokens;
+
+/// Generates the TokenStream for the loaders of the relation fields, the ones annotated
+/// with `#[has_many]` and `#[many_to_many]`, and the helpers for add and remove the
+/// associations in the join table of the `#[many_to_many]` ones
+pub fn generate_relations_tokens(macro_data: &MacroTokens<'_>) -> Vec<(TokenStream, TokenStream)> {
+    let mut relation_quotes: Vec<(TokenStream, TokenStream)> = Vec::new();
+
+    for (field_ident, annotation) in macro_data.get_relation_annotations().iter() {
+        let method_ident = |prefix: &str, suffix: &str| {
+            proc_macro2::Ident::new(
+                &format!("{prefix}_{field_ident}{suffix}"),
+                proc_macro2::Span::call_site(),
+            )
+        };
+        let load_ident = method_ident("load", "");
+        let load_ident_ds = method_ident("load", "_datasource");
+
+        match annotation {
+            EntityFieldAnnotation::HasMany(entity) => {
+                let related_ty = syn::parse_str::<Type>(entity)
+                    .expect("The related entity was already parsed as a type");
+
+                let quoted_method_signature: TokenStream = quote! {
+                    async fn #load_ident(&mut self) ->
+                        Result<(), Box<(dyn std::error::Error + Send + Sync + 'static)>>
+                };
+                let quoted_datasource_method_signature: TokenStream = quote! {
+                    async fn #load_ident_ds<'a>(&mut self, datasource_name: &'a str) ->
+                        Result<(), Box<(dyn std::error::Error + Send + Sync + 'static)>>
+                };
+
+                relation_quotes.push((
+                    quote! { #quoted_method_signature; },
+                    quote! {
+                        /// Loads into the relation field the child entities that are
+                        /// pointing to this one through their foreign key
+                        #quoted_method_signature {
+                            self.#load_ident_ds("").await
+                        }
+                    },
+                ));
+                relation_quotes.push((
+                    quote! { #quoted_datasource_method_signature; },
+                    quote! {
+                        /// Loads into the relation field the child entities that are
+                        /// pointing to this one through their foreign key, with the
+                        /// specified datasource
+                        #quoted_datasource_method_signature {
+                            let related = canyon_sql::crud::load_has_many::<Self, #related_ty>(
+                                self, datasource_name
+                            ).await?;
+                            self.#field_ident = related;
+                            Ok(())
+                        }
+                    },
+                ));
+            }
+            EntityFieldAnnotation::ManyToMany(entity, through, source_column, target_column) => {
+                let related_ty = syn::parse_str::<Type>(entity)
+                    .expect("The related entity was already parsed as a type");
+                let source_column = match source_column {
+                    Some(column) => quote! { Some(#column) },
+                    None => quote! { None },
+                };
+                let target_column = match target_column {
+                    Some(column) => quote! { Some(#column) },
+                    None => quote! { None },
+                };
+                let relation = quote! {
+                    canyon_sql::crud::ManyToMany::new(#through, #source_column, #target_column)
+                };
+
+                let add_ident = method_ident("add", "");
+                let add_ident_ds = method_ident("add", "_datasource");
+                let remove_ident = method_ident("remove", "");
+                let remove_ident_ds = method_ident("remove", "_datasource");
+
+                let load_signature: TokenStream = quote! {
+                    async fn #load_ident(&mut self) ->
+                        Result<(), Box<(dyn std::error::Error + Send + Sync + 'static)>>
+                };
+                let load_datasource_signature: TokenStream = quote! {
+                    async fn #load_ident_ds<'a>(&mut self, datasource_name: &'a str) ->
+                        Result<(), Box<(dyn std::error::Error + Send + Sync + 'static)>>
+                };
+                let add_signature: TokenStream = quote! {
+                    async fn #add_ident<'a>(&self, related: &'a [&'a #related_ty]) ->
+                        Result<(), Box<(dyn std::error::Error + Send + Sync + 'static)>>
+                };
+                let add_datasource_signature: TokenStream = quote! {
+                    async fn #add_ident_ds<'a>(
+                        &self, related: &'a [&'a #related_ty], datasource_name: &'a str
+                    ) -> Result<(), Box<(dyn std::error::Error + Send + Sync + 'static)>>
+                };
+                let remove_signature: TokenStream = quote! {
+                    async fn #remove_ident<'a>(&self, related: &'a [&'a #related_ty]) ->
+                        Result<(), Box<(dyn std::error::Error + Send + Sync + 'static)>>
+                };
+                let remove_datasource_signature: TokenStream = quote! {
+                    async fn #remove_ident_ds<'a>(
+                        &self, related: &'a [&'a #related_ty], datasource_name: &'a str
+                    ) -> Result<(), Box<(dyn std::error::Error + Send + Sync + 'static)>>
+                };
+
+                relation_quotes.push((
+                    quote! { #load_signature; },
+                    quote! {
+                        /// Loads into the relation field the entities linked to this one
+                        /// in the join table
+                        #load_signature {
+                            self.#load_ident_ds("").await
+                        }
+                    },
+                ));
+                relation_quotes.push((
+                    quote! { #load_datasource_signature; },
+                    quote! {
+                        /// Loads into the relation field the entities linked to this one
+                        /// in the join table, with the specified datasource
+                        #load_datasource_signature {
+                            let related = #relation
+                                .load::<Self, #related_ty>(self, datasource_name)
+                                .await?;
+                            self.#field_ident = related;
+                            Ok(())
+                        }
+                    },
+                ));
+                relation_quotes.push((
+                    quote! { #add_signature; },
+                    quote! {
+                        /// Links the `related` entities with this one in the join table
+                        #add_signature {
+                            self.#add_ident_ds(related, "").await
+                        }
+                    },
+                ));
+                relation_quotes.push((
+                    quote! { #add_datasource_signature; },
+                    quote! {
+                        /// Links the `related` entities with this one in the join table,
+                        /// with the specified datasource
+                        #add_datasource_signature {
+                            #relation
+                                .add::<Self, #related_ty>(self, related, datasource_name)
+                                .await
+                                .map(|_| ())
+                        }
+                    },
+                ));
+                relation_quotes.push((
+                    quote! { #remove_signature; },
+                    quote! {
+                        /// Unlinks the `related` entities from this one in the join table
+                        #remove_signature {
+                            self.#remove_ident_ds(related, "").await
+                        }
+                    },
+                ));
+                relation_quotes.push((
+                    quote! { #remove_datasource_signature; },
+                    quote! {
+                        /// Unlinks the `related` entities from this one in the join table,
+                        /// with the specified datasource
+                        #remove_datasource_signature {
+                            #relation
+                                .remove::<Self, #related_ty>(self, related, datasource_name)
+                                .await
+                                .map(|_| ())
+                        }
+                    },
+                ));
+            }
+            _ => {}
+        }
+    }
+
+    relation_quotes
+}
@@ -3,17 +3,77 @@ use canyon_observer::manager::field_annotation::EntityFieldAnnotation;
 use proc_macro2::TokenStream;
 use quote::quote;
 
+use super::{
+    alive_rows, render_for_database_type, render_for_datasource, soft_delete_tokens,
+    unsupported_operation,
+};
 use crate::utils::helpers::*;
 use crate::utils::macro_tokens::MacroTokens;
 
+/// Generates the expression that renders, with the `render` call, a `SELECT`
+/// over the `table` filtered by every column of the `filters`, being their
+/// values the parameters of the query in the same order, and restricted by
+/// the `scope` predicate, if any
+fn select_statement_tokens(
+    table: &str,
+    projection: Option<&str>,
+    filters: &[String],
+    scope: Option<&TokenStream>,
+    render: TokenStream,
+) -> TokenStream {
+    let projection = projection.map(|projection| quote! { .projection(&[#projection]) });
+    let filters = (!filters.is_empty()).then(|| {
+        let conditions = filters.iter().enumerate().map(|(idx, column)| {
+            let position = idx + 1;
+            quote! { (#column, #position) }
+        });
+        quote! {
+            .clause(canyon_sql::query::statement::Clause::Where(
+                canyon_sql::query::statement::Predicate::all_equal(&[#(#conditions),*])
+            ))
+        }
+    });
+
+    let scope = scope.map(|scope| quote! { .scope(#scope) });
+
+    quote! {
+        canyon_sql::query::statement::Statement::from(
+            canyon_sql::query::statement::Select::new(#table)
+                #projection
+                #filters
+                #scope
+        )#render
+    }
+}
+
 /// Generates the TokenStream for build the __find_all() CRUD
 /// associated function
 pub fn generate_find_all_unchecked_tokens(
     macro_data: &MacroTokens<'_>,
-    table_schema_data: &String,
+    table_schema_data: &str,
 ) -> TokenStream {
     let ty = macro_data.ty;
-    let stmt = format!("SELECT * FROM {table_schema_data}");
+    let alive = alive_rows(macro_data);
+    let stmt = select_statement_tokens(
+        table_schema_data,
+        None,
+        &[],
+        alive.as_ref(),
+        quote! {
+            .render("")
+            .unwrap_or_else(|error| panic!("Failed to render the query for the default datasource: {error}"))
+        },
+    );
+    let stmt_ds = select_statement_tokens(
+        table_schema_data,
+        None,
+        &[],
+        alive.as_ref(),
+        quote! {
+            .render(datasource_name)
+            .unwrap_or_else(|error| panic!("Failed to render the query for the datasource `{datasource_name}`: {error}"))
+        },
+    );
 
     quote! {
         /// Performns a `SELECT * FROM table_name`, where `table_name` it's
@@ -41,7 +101,7 @@ pub fn generate_find_all_unchecked_tokens(
         /// passed as parameter.
         async fn find_all_unchecked_datasource<'a>(datasource_name: &'a str) -> Vec<#ty> {
             <#ty as canyon_sql::crud::Transaction<#ty>>::query(
-                #stmt,
+                #stmt_ds,
                 &[],
                 datasource_name
             ).await
@@ -56,12 +116,41 @@ pub fn generate_find_all_unchecked_tokens(
 /// associated function
 pub fn generate_find_all_tokens(
     macro_data: &MacroTokens<'_>,
-    table_schema_data: &String,
+    table_schema_data: &str,
 ) -> TokenStream {
     let ty = macro_data.ty;
-    let stmt = format!("SELECT * FROM {table_schema_data}");
+    let alive = alive_rows(macro_data);
+    let stmt = select_statement_tokens(
+        table_schema_data,
+        None,
+        &[],
+        alive.as_ref(),
+        render_for_datasource(quote! { "" }),
+    );
+    let stmt_ds = select_statement_tokens(
+        table_schema_data,
+        None,
+        &[],
+        alive.as_ref(),
+        render_for_datasource(quote! { datasource_name }),
+    );
+    let stmt_sql = select_statement_tokens(
+        table_schema_data,
+        None,
+        &[],
+        alive.as_ref(),
+        render_for_database_type(),
+    );
 
     quote! {
+        /// Returns the SQL of the `find_all` operation for the `database_type`,
+        /// along with its parameters, without querying the database
+        fn find_all_sql<'a>(database_type: canyon_sql::crud::DatabaseType)
+            -> (String, Vec<&'a dyn canyon_sql::crud::bounds::QueryParameter<'a>>)
+        {
+            (#stmt_sql, vec![])
+        }
+
         /// Performns a `SELECT * FROM table_name`, where `table_name` it's
         /// the name of your entity but converted to the corresponding
         /// database convention. P.ej. PostgreSQL prefers table names declared
@@ -98,7 +187,7 @@ pub fn generate_find_all_tokens(
             Result<Vec<#ty>, Box<(dyn std::error::Error + Send + Sync + 'static)>>
         {
             let result = <#ty as canyon_sql::crud::Transaction<#ty>>::query(
-                #stmt,
+                #stmt_ds,
                 &[],
                 datasource_name
             ).await;
@@ -119,6 +208,15 @@ pub fn generate_find_all_query_tokens(
 ) -> TokenStream {
     let ty = macro_data.ty;
 
+    // The entities with a `#[soft_delete]` field only retrieves the alive rows by default
+    let soft_delete = macro_data
+        .get_soft_delete_field()
+        .map(|(field, soft_delete)| {
+            let column = field.to_string();
+            let soft_delete = soft_delete_tokens(soft_delete);
+            quote! { .with_soft_delete(#column, #soft_delete) }
+        });
+
     quote! {
         /// Generates a [`canyon_sql::query::SelectQueryBuilder`]
         /// that allows you to customize the query by adding parameters and constrains dynamically.
@@ -128,7 +226,7 @@ pub fn generate_find_all_query_tokens(
         /// unless concrete values are set on the available parameters of the
         /// `canyon_macro(table_name = "table_name", schema = "schema")`
         fn select_query<'a>() -> canyon_sql::query::SelectQueryBuilder<'a, #ty> {
-            canyon_sql::query::SelectQueryBuilder::new(#table_schema_data, "")
+            canyon_sql::query::SelectQueryBuilder::new(#table_schema_data, "")#soft_delete
         }
 
         /// Generates a [`canyon_sql::query::SelectQueryBuilder`]
@@ -143,20 +241,38 @@ pub fn generate_find_all_query_tokens(
         /// described in the configuration file, and selected with the [`&str`]
         /// passed as parameter.
         fn select_query_datasource<'a>(datasource_name: &'a str) -> canyon_sql::query::SelectQueryBuilder<'a, #ty> {
-            canyon_sql::query::SelectQueryBuilder::new(#table_schema_data, datasource_name)
+            canyon_sql::query::SelectQueryBuilder::new(#table_schema_data, datasource_name)#soft_delete
         }
     }
 }
 
 /// Performs a COUNT(*) query over some table, returning a [`Result`] wrapping
 /// a possible success or error coming from the database
-pub fn generate_count_tokens(
-    macro_data: &MacroTokens<'_>,
-    table_schema_data: &String,
-) -> TokenStream {
+pub fn generate_count_tokens(macro_data: &MacroTokens<'_>, table_schema_data: &str) -> TokenStream {
     let ty = macro_data.ty;
+    let alive = alive_rows(macro_data);
     let ty_str = &ty.to_string();
-    let stmt = format!("SELECT COUNT (*) FROM {table_schema_data}");
+    let stmt = select_statement_tokens(
+        table_schema_data,
+        Some("COUNT(*)"),
+        &[],
+        alive.as_ref(),
+        render_for_datasource(quote! { "" }),
+    );
+    let stmt_ds = select_statement_tokens(
+        table_schema_data,
+        Some("COUNT(*)"),
+        &[],
+        alive.as_ref(),
+        render_for_datasource(quote! { datasource_name }),
+    );
+    let stmt_sql = select_statement_tokens(
+        table_schema_data,
+        Some("COUNT(*)"),
+        &[],
+        alive.as_ref(),
+        render_for_database_type(),
+    );
 
     let result_handling = quote! {
         if let Err(error) = count {
@@ -186,6 +302,14 @@ pub fn generate_count_tokens(
     };
 
     quote! {
+        /// Returns the SQL of the `count` operation for the `database_type`,
+        /// along with its parameters, without querying the database
+        fn count_sql<'a>(database_type: canyon_sql::crud::DatabaseType)
+            -> (String, Vec<&'a dyn canyon_sql::crud::bounds::QueryParameter<'a>>)
+        {
+            (#stmt_sql, vec![])
+        }
+
         /// Performs a COUNT(*) query over some table, returning a [`Result`] rather than panicking,
         /// wrapping a possible success or error coming from the database
         async fn count() -> Result<i64, Box<(dyn std::error::Error + Send + Sync + 'static)>> {
@@ -202,7 +326,7 @@ pub fn generate_count_tokens(
         /// wrapping a possible success or error coming from the database with the specified datasource
         async fn count_datasource<'a>(datasource_name: &'a str) -> Result<i64, Box<(dyn std::error::Error + Send + Sync + 'static)>> {
             let count = <#ty as canyon_sql::crud::Transaction<#ty>>::query(
-                #stmt,
+                #stmt_ds,
                 &[],
                 datasource_name
             ).await;
@@ -212,47 +336,227 @@ pub fn generate_count_tokens(
     }
 }
 
+/// Generates the TokenStream for the `PrimaryKey` associated type, that's the type of the
+/// `#[primary_key]` field, a tuple with the types of every `#[primary_key]` field if the
+/// primary key it's a composite one, or the unit type if there's no primary key,
+/// and for the `primary_key()` method, that retrieves its value from an instance
+pub fn generate_primary_key_tokens(macro_data: &MacroTokens<'_>) -> TokenStream {
+    let pk_fields = macro_data.get_primary_key_annotations();
+    let pk_idents = pk_fields.iter().map(|(ident, _)| ident);
+    let pk_types = pk_fields.iter().map(|(_, ty)| ty);
+
+    let (pk_type, pk_value) = if pk_fields.is_empty() {
+        (quote! { () }, quote! {})
+    } else if pk_fields.len() == 1 {
+        (
+            quote! { #(#pk_types)* },
+            quote! { #(self.#pk_idents.clone())* },
+        )
+    } else {
+        (
+            quote! { (#(#pk_types),*) },
+            quote! { (#(self.#pk_idents.clone()),*) },
+        )
+    };
+
+    quote! {
+        type PrimaryKey = #pk_type;
+
+        /// Retrieves the value of the primary key of the current instance, being a tuple
+        /// with the values of every `#[primary_key]` field if it's a composite one
+        fn primary_key(&self) -> Self::PrimaryKey {
+            #pk_value
+        }
+    }
+}
+
+/// Generates the TokenStream for the implementation of the `TableMetadata` trait,
+/// with the name of the table, the columns and the foreign keys of the entity
+pub fn generate_table_metadata_tokens(
+    macro_data: &MacroTokens<'_>,
+    table_schema_data: &String,
+) -> TokenStream {
+    let ty = macro_data.ty;
+    let columns = macro_data
+        .get_struct_fields()
+        .iter()
+        .map(|ident| ident.to_string())
+        .collect::<Vec<String>>();
+
+    let foreign_keys =
+        macro_data
+            .get_fk_annotations()
+            .into_iter()
+            .filter_map(|(ident, annotation)| match annotation {
+                EntityFieldAnnotation::ForeignKey(table, column) => {
+                    let ident = ident.to_string();
+                    Some(quote! { (#ident, #table, #column) })
+                }
+                _ => None,
+            });
+
+    let soft_delete = match macro_data.get_soft_delete_field() {
+        Some((field, soft_delete)) => {
+            let column = field.to_string();
+            let soft_delete = soft_delete_tokens(soft_delete);
+            quote! { Some((#column, #soft_delete)) }
+        }
+        None => quote! { None },
+    };
+    let version = match macro_data.get_version_field() {
+        Some(field) => {
+            let column = field.to_string();
+            quote! { Some(#column) }
+        }
+        None => quote! { None },
+    };
+
+    let timestamps = macro_data
+        .get_timestamp_annotations()
+        .into_iter()
+        .map(|(ident, _, _)| ident.to_string());
+
+    let primary_key = macro_data
+        .get_primary_key_annotations()
+        .into_iter()
+        .map(|(ident, _)| ident.to_string());
+
+    let fields_with_types = macro_data._fields_with_types();
+    let column_values = fields_with_types.iter().map(|(ident, _)| {
+        let column = ident.to_string();
+        quote! {
+            #column => Some(&self.#ident as &dyn canyon_sql::crud::bounds::QueryParameter<'_>)
+        }
+    });
+    let column_keys = fields_with_types.iter().map(|(ident, ty)| {
+        let column = ident.to_string();
+        // Nullable columns are compared by their inner value, so they can be
+        // matched against the non nullable side of a relation
+        if quote! { #ty }.to_string().starts_with("Option") {
+            quote! { #column => self.#ident.as_ref().map(|value| format!("{:?}", value)) }
+        } else {
+            quote! { #column => Some(format!("{:?}", self.#ident)) }
+        }
+    });
+
+    quote! {
+        impl canyon_sql::crud::bounds::TableMetadata for #ty {
+            const TABLE_NAME: &'static str = #table_schema_data;
+            const COLUMNS: &'static [&'static str] = &[#(#columns),*];
+            const PRIMARY_KEY: &'static [&'static str] = &[#(#primary_key),*];
+            const FOREIGN_KEYS: &'static [(&'static str, &'static str, &'static str)] =
+                &[#(#foreign_keys),*];
+            const TIMESTAMPS: &'static [&'static str] = &[#(#timestamps),*];
+            const SOFT_DELETE: Option<(&'static str, canyon_sql::crud::soft_delete::SoftDelete)> =
+                #soft_delete;
+            const VERSION: Option<&'static str> = #version;
+
+            fn column_value(&self, column: &str)
+                -> Option<&dyn canyon_sql::crud::bounds::QueryParameter<'_>>
+            {
+                match column {
+                    #(#column_values),*,
+                    _ => None
+                }
+            }
+
+            fn column_key(&self, column: &str) -> Option<String> {
+                match column {
+                    #(#column_keys),*,
+                    _ => None
+                }
+            }
+        }
+    }
+}
+
 /// Generates the TokenStream for build the __find_by_pk() CRUD operation
 pub fn generate_find_by_pk_tokens(
     macro_data: &MacroTokens<'_>,
-    table_schema_data: &String,
+    table_schema_data: &str,
 ) -> TokenStream {
     let ty = macro_data.ty;
-    let pk = macro_data.get_primary_key_annotation().unwrap_or_default();
-    let stmt = format!("SELECT * FROM {table_schema_data} WHERE {pk} = $1");
+    let alive = alive_rows(macro_data);
+    let pk_fields = macro_data.get_primary_key_annotations();
+    let pk_len = pk_fields.len();
+
+    // A composite primary key filters by every column that composes it
+    let pk_columns = pk_fields
+        .iter()
+        .map(|(pk_field, _)| format!("\"{pk_field}\""))
+        .collect::<Vec<String>>();
+    let stmt = select_statement_tokens(
+        table_schema_data,
+        None,
+        &pk_columns,
+        alive.as_ref(),
+        render_for_datasource(quote! { "" }),
+    );
+    let stmt_ds = select_statement_tokens(
+        table_schema_data,
+        None,
+        &pk_columns,
+        alive.as_ref(),
+        render_for_datasource(quote! { datasource_name }),
+    );
+    let stmt_sql = select_statement_tokens(
+        table_schema_data,
+        None,
+        &pk_columns,
+        alive.as_ref(),
+        render_for_database_type(),
+    );
 
     // Disabled if there's no `primary_key` annotation
-    if pk.is_empty() {
+    if pk_fields.is_empty() {
+        let unsupported = |operation: &str| unsupported_operation(operation, "primary_key");
+        let find_by_pk_sql = unsupported("find_by_pk_sql");
+        let find_by_pk = unsupported("find_by_pk");
+        let find_by_pk_ds = unsupported("find_by_pk_datasource");
+
         return quote! {
-            async fn find_by_pk<'a>(value: &'a dyn canyon_sql::crud::bounds::QueryParameter<'a>)
-                -> Result<Option<#ty>, Box<(dyn std::error::Error + Send + Sync + 'static)>>
-            {
-                Err(
-                    std::io::Error::new(
-                        std::io::ErrorKind::Unsupported,
-                        "You can't use the 'find_by_pk' associated function on a \
-                        CanyonEntity that does not have a #[primary_key] annotation. \
-                        If you need to perform an specific search, use the Querybuilder instead."
-                    ).into_inner().unwrap()
-                )
+            fn find_by_pk_sql<'a>(
+                value: impl Into<canyon_sql::crud::bounds::PrimaryKeyValue<'a>>,
+                database_type: canyon_sql::crud::DatabaseType
+            ) -> Result<
+                (String, canyon_sql::crud::bounds::PrimaryKeyValue<'a>),
+                Box<(dyn std::error::Error + Send + Sync + 'static)>
+            > {
+                #find_by_pk_sql
+            }
+
+            async fn find_by_pk<'a>(
+                value: impl Into<canyon_sql::crud::bounds::PrimaryKeyValue<'a>> + Send + 'a
+            ) -> Result<Option<#ty>, Box<(dyn std::error::Error + Send + Sync + 'static)>> {
+                #find_by_pk
             }
 
             async fn find_by_pk_datasource<'a>(
-                value: &'a dyn canyon_sql::crud::bounds::QueryParameter<'a>,
+                value: impl Into<canyon_sql::crud::bounds::PrimaryKeyValue<'a>> + Send + 'a,
                 datasource_name: &'a str
             ) -> Result<Option<#ty>, Box<(dyn std::error::Error + Send + Sync + 'static)>> {
-                Err(
-                    std::io::Error::new(
-                        std::io::ErrorKind::Unsupported,
-                        "You can't use the 'find_by_pk_datasource' associated function on a \
-                        CanyonEntity that does not have a #[primary_key] annotation. \
-                        If you need to perform an specific search, use the Querybuilder instead."
-                    ).into_inner().unwrap()
-                )
+                #find_by_pk_ds
             }
         };
     }
 
+    // The values of every `#[primary_key]` field, in their declaration order
+    let pk_values = quote! {
+        let key: canyon_sql::crud::bounds::PrimaryKeyValue<'a> = value.into();
+        let values = key.values();
+        if values.len() != #pk_len {
+            return Err(
+                std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    format!(
+                        "The primary key of {} is composed by {} field(s), but {} value(s) were provided",
+                        stringify!(#ty), #pk_len, values.len()
+                    )
+                ).into()
+            );
+        }
+    };
+
     let result_handling = quote! {
         if let Err(error) = result {
             Err(error)
@@ -271,24 +575,42 @@ pub fn generate_find_by_pk_tokens(
     };
 
     quote! {
+        /// Returns the SQL of the `find_by_pk` operation for the `database_type`,
+        /// along with its parameters, without querying the database
+        fn find_by_pk_sql<'a>(
+            value: impl Into<canyon_sql::crud::bounds::PrimaryKeyValue<'a>>,
+            database_type: canyon_sql::crud::DatabaseType
+        ) -> Result<
+            (String, canyon_sql::crud::bounds::PrimaryKeyValue<'a>),
+            Box<(dyn std::error::Error + Send + Sync + 'static)>
+        > {
+            #pk_values
+
+            Ok((#stmt_sql, key))
+        }
+
         /// Finds an element on the queried table that matches the
         /// value of the field annotated with the `primary_key` attribute,
         /// filtering by the column that it's declared as the primary
         /// key on the database.
         ///
         /// This operation it's only available if the [`CanyonEntity`] contains
-        /// some field declared as primary key.
+        /// some field declared as primary key. The entities with a composite
+        /// primary key are searched with a tuple that holds the values of every
+        /// `#[primary_key]` field, following their declaration order.
         ///
         /// Also, returns a [`Result<Option<T>, Error>`], wrapping a possible failure
         /// querying the database, or, if no errors happens, a success containing
         /// and Option<T> with the data found wrapped in the Some(T) variant,
         /// or None if the value isn't found on the table.
-        async fn find_by_pk<'a>(value: &'a dyn canyon_sql::crud::bounds::QueryParameter<'a>) ->
-            Result<Option<#ty>, Box<(dyn std::error::Error + Send + Sync + 'static)>>
-        {
+        async fn find_by_pk<'a>(
+            value: impl Into<canyon_sql::crud::bounds::PrimaryKeyValue<'a>> + Send + 'a
+        ) -> Result<Option<#ty>, Box<(dyn std::error::Error + Send + Sync + 'static)>> {
+            #pk_values
+
             let result = <#ty as canyon_sql::crud::Transaction<#ty>>::query(
                 #stmt,
-                vec![value],
+                values,
                 ""
             ).await;
 
@@ -305,20 +627,23 @@ pub fn generate_find_by_pk_tokens(
         /// passed as parameter.
         ///
         /// This operation it's only available if the [`CanyonEntity`] contains
-        /// some field declared as primary key.
+        /// some field declared as primary key. The entities with a composite
+        /// primary key are searched with a tuple that holds the values of every
+        /// `#[primary_key]` field, following their declaration order.
         ///
         /// Also, returns a [`Result<Option<T>, Error>`], wrapping a possible failure
         /// querying the database, or, if no errors happens, a success containing
         /// and Option<T> with the data found wrapped in the Some(T) variant,
         /// or None if the value isn't found on the table.
         async fn find_by_pk_datasource<'a>(
-            value: &'a dyn canyon_sql::crud::bounds::QueryParameter<'a>,
+            value: impl Into<canyon_sql::crud::bounds::PrimaryKeyValue<'a>> + Send + 'a,
             datasource_name: &'a str
         ) -> Result<Option<#ty>, Box<(dyn std::error::Error + Send + Sync + 'static)>> {
+            #pk_values
 
             let result = <#ty as canyon_sql::crud::Transaction<#ty>>::query(
-                #stmt,
-                vec![value],
+                #stmt_ds,
+                values,
                 datasource_name
             ).await;
 
@@ -361,10 +686,20 @@ pub fn generate_find_by_foreign_key_tokens(
                     Result<Option<#fk_ty>, Box<(dyn std::error::Error + Send + Sync + 'static)>>
             };
 
-            let stmt = format!(
-                "SELECT * FROM {} WHERE {} = $1",
+            let filters = [format!("\"{column}\"")];
+            let stmt = select_statement_tokens(
+                table,
+                None,
+                &filters,
+                None,
+                render_for_datasource(quote! { "" }),
+            );
+            let stmt_ds = select_statement_tokens(
                 table,
-                format!("\"{column}\"").as_str(),
+                None,
+                &filters,
+                None,
+                render_for_datasource(quote! { datasource_name }),
             );
             let result_handler = quote! {
                 if let Err(error) = result {
@@ -404,7 +739,7 @@ pub fn generate_find_by_foreign_key_tokens(
                     /// Searches the parent entity (if exists) for this type with the specified datasource
                     #quoted_datasource_method_signature {
                         let result = <#fk_ty as canyon_sql::crud::Transaction<#fk_ty>>::query(
-                            #stmt,
+                            #stmt_ds,
                             &[&self.#field_ident as &dyn canyon_sql::crud::bounds::QueryParameter<'_>],
                             datasource_name
                         ).await;
@@ -425,7 +760,7 @@ pub fn generate_find_by_foreign_key_tokens(
 /// derive macro on the parent side of the relation
 pub fn generate_find_by_reverse_foreign_key_tokens(
     macro_data: &MacroTokens<'_>,
-    table_schema_data: &String,
+    table_schema_data: &str,
 ) -> Vec<(TokenStream, TokenStream)> {
     let mut rev_fk_quotes: Vec<(TokenStream, TokenStream)> = Vec::new();
     let ty = macro_data.ty;
@@ -459,7 +794,21 @@ pub fn generate_find_by_reverse_foreign_key_tokens(
                     Ok(result.ok().unwrap().get_entities::<#ty>())
                 }
             };
-            let f_ident = field_ident.to_string();
+            let filters = [format!("\"{field_ident}\"")];
+            let stmt = select_statement_tokens(
+                table_schema_data,
+                None,
+                &filters,
+                None,
+                render_for_datasource(quote! { "" }),
+            );
+            let stmt_ds = select_statement_tokens(
+                table_schema_data,
+                None,
+                &filters,
+                None,
+                render_for_datasource(quote! { datasource_name }),
+            );
 
             rev_fk_quotes.push((
                 quote! { #quoted_method_signature; },
@@ -473,14 +822,8 @@ pub fn generate_find_by_reverse_foreign_key_tokens(
                             "Column: {:?} not found in type: {:?}", #column, #table
                             ).as_str());
 
-                        let stmt = format!(
-                            "SELECT * FROM {} WHERE {} = $1",
-                            #table_schema_data,
-                            format!("\"{}\"", #f_ident).as_str()
-                        );
-
                         let result = <#ty as canyon_sql::crud::Transaction<#ty>>::query(
-                            stmt,
+                            #stmt,
                             &[lookage_value],
                             ""
                         ).await;
@@ -503,14 +846,8 @@ pub fn generate_find_by_reverse_foreign_key_tokens(
                                 "Column: {:?} not found in type: {:?}", #column, #table
                             ).as_str());
 
-                        let stmt = format!(
-                            "SELECT * FROM {} WHERE {} = $1",
-                            #table_schema_data,
-                            format!("\"{}\"", #f_ident).as_str()
-                        );
-
                         let result = <#ty as canyon_sql::crud::Transaction<#ty>>::query(
-                            stmt,
+                            #stmt_ds,
                             &[lookage_value],
                             datasource_name
                         ).await;
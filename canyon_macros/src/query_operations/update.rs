@@ -1,76 +1,306 @@
+use canyon_crud::timestamps::TimestampSource;
+use canyon_observer::manager::field_annotation::EntityFieldAnnotation;
 use proc_macro2::TokenStream;
 use quote::quote;
 
+use super::{
+    render_for_database_type, render_for_datasource, unsupported_operation, with_hooks, Hooked,
+};
 use crate::utils::macro_tokens::MacroTokens;
 
 /// Generates the TokenStream for the __update() CRUD operation
 pub fn generate_update_tokens(macro_data: &MacroTokens, table_schema_data: &String) -> TokenStream {
     let ty = macro_data.ty;
 
-    let update_columns = macro_data.get_column_names_pk_parsed();
+    // The `#[created_at]` fields are never updated, and the `#[updated_at]` ones are
+    // assigned to the current time of their source instead of their value
+    let timestamps = macro_data.get_timestamp_annotations();
 
-    // Retrieves the fields of the Struct
-    let fields = macro_data.get_struct_fields();
+    // Retrieves the fields of the Struct that are bound as parameters
+    let all_fields = macro_data.get_struct_fields();
+    let pk_fields = macro_data
+        .get_pk_indexes()
+        .into_iter()
+        .map(|idx| all_fields[idx].clone())
+        .collect::<Vec<_>>();
+    let fields = all_fields
+        .into_iter()
+        .filter(|field| {
+            !timestamps
+                .iter()
+                .any(|(timestamp, _, _)| *timestamp == field)
+        })
+        .collect::<Vec<_>>();
+    let pk_indexes = fields
+        .iter()
+        .enumerate()
+        .filter(|(_, field)| pk_fields.contains(field))
+        .map(|(idx, _)| idx)
+        .collect::<Vec<usize>>();
 
-    let mut vec_columns_values: Vec<String> = Vec::new();
-    for (i, column_name) in update_columns.iter().enumerate() {
-        let column_equal_value = format!("{} = ${}", column_name.to_owned(), i + 2);
-        vec_columns_values.push(column_equal_value)
-    }
+    // The `#[version]` field it's not assigned, but matched against the row and
+    // moved forward by one
+    let version = macro_data.get_version_field();
+    let version_index =
+        version.and_then(|version| fields.iter().position(|field| field == version));
+    let increment = version.map(|version| {
+        let column = format!("\"{version}\"");
+        quote! {
+            .computed(#column, canyon_sql::query::statement::Computed::Increment(String::from(#column)))
+        }
+    });
 
-    let str_columns_values = vec_columns_values.join(", ");
+    // The placeholders follows the declaration order of the fields, because
+    // every field is passed as a query parameter
+    let mut assignments = fields
+        .iter()
+        .enumerate()
+        .filter(|(idx, _)| !pk_indexes.contains(idx) && Some(*idx) != version_index)
+        .map(|(idx, column_name)| {
+            let column = format!("\"{column_name}\"");
+            let position = idx + 1;
+            quote! { (#column, #position) }
+        })
+        .collect::<Vec<TokenStream>>();
 
-    let update_values = fields.iter().map(|ident| {
-        quote! { &self.#ident }
-    });
-    let update_values_cloned = update_values.clone();
+    // A composite primary key matches the row by every column that composes it,
+    // and the versioned entities by its version too
+    let pk_conditions = pk_indexes
+        .iter()
+        .chain(version_index.iter())
+        .map(|idx| {
+            let column = format!("\"{}\"", fields[*idx]);
+            let position = idx + 1;
+            quote! { (#column, #position) }
+        })
+        .collect::<Vec<TokenStream>>();
 
-    if let Some(primary_key) = macro_data.get_primary_key_annotation() {
-        let pk_index = macro_data
-            .get_pk_index()
-            .expect("Update method failed to retrieve the index of the primary key");
+    // The time of the client is assigned to the entity and bound after the fields,
+    // and the one of the database is computed by the statement
+    let mut updated_at = Vec::new();
+    let mut client_times = Vec::new();
+    for (field, _, annotation) in &timestamps {
+        let column = format!("\"{field}\"");
+        match annotation {
+            EntityFieldAnnotation::UpdatedAt(TimestampSource::Client) => {
+                client_times.push(*field);
+                let position = fields.len() + client_times.len();
+                assignments.push(quote! { (#column, #position) });
+            }
+            EntityFieldAnnotation::UpdatedAt(TimestampSource::Database) => {
+                updated_at.push(quote! {
+                    .computed(#column, canyon_sql::query::statement::Computed::CurrentTimestamp)
+                });
+            }
+            _ => {}
+        }
+    }
 
+    let update_values = fields
+        .iter()
+        .chain(client_times.iter().copied())
+        .map(|ident| quote! { &self.#ident });
+    // The inspected statement binds the time of the client when it's sent
+    let update_values_sql = fields.iter().map(|ident| quote! { &self.#ident }).chain(
+        client_times
+            .iter()
+            .map(|_| quote! { &canyon_sql::crud::timestamps::ClientTime }),
+    );
+    let fill_updated_at = (!client_times.is_empty()).then(|| {
         quote! {
-            /// Updates a database record that matches
-            /// the current instance of a T type, returning a result
-            /// indicating a possible failure querying the database.
-            async fn update(&self) -> Result<(), Box<dyn std::error::Error + Sync + std::marker::Send>> {
-                let stmt = format!(
-                    "UPDATE {} SET {} WHERE {} = ${:?}",
-                    #table_schema_data, #str_columns_values, #primary_key, #pk_index + 1
-                );
-                let update_values: &[&dyn canyon_sql::crud::bounds::QueryParameter<'_>] = &[#(#update_values),*];
+            let timestamp = canyon_sql::crud::timestamps::now();
+            #(entity.#client_times = timestamp.into();)*
+        }
+    });
+
+    let update_stmt = |render: TokenStream| {
+        quote! {
+            canyon_sql::query::statement::Statement::from(
+                canyon_sql::query::statement::Update::new(
+                    #table_schema_data, &[#(#assignments),*]
+                )#(#updated_at)*#increment.clause(canyon_sql::query::statement::Clause::Where(
+                    canyon_sql::query::statement::Predicate::all_equal(&[#(#pk_conditions),*])
+                ))
+            )#render
+        }
+    };
+
+    if !pk_indexes.is_empty() {
+        let stmt = update_stmt(render_for_datasource(quote! { datasource_name }));
+        let stmt_sql = update_stmt(render_for_database_type());
+
+        // When every field is part of the primary key, there's nothing to update
+        let nothing_to_update =
+            assignments.is_empty() && updated_at.is_empty() && version.is_none();
+        let update_transaction = if nothing_to_update {
+            quote! { Ok(()) }
+        } else if let Some(version) = version {
+            // The row was modified by someone else if the version no longer matches,
+            // and the entity follows the version of the row otherwise
+            quote! {
+                let stmt = #stmt;
+
+                let affected_rows = <#ty as canyon_sql::crud::Transaction<#ty>>::execute(
+                    stmt, update_values, datasource_name
+                ).await?;
+
+                canyon_sql::crud::locking::VersionConflict::check::<#ty>(
+                    i64::from(self.#version), affected_rows
+                )?;
+                self.#version += 1;
+                Ok(())
+            }
+        } else {
+            quote! {
+                let stmt = #stmt;
 
                 let result = <#ty as canyon_sql::crud::Transaction<#ty>>::query(
-                    stmt, update_values, ""
+                    stmt, update_values, datasource_name
                 ).await;
 
                 if let Err(e) = result {
                     Err(e)
                 } else { Ok(()) }
             }
+        };
+
+        // Without anything to update, there's no statement to inspect
+        let update_sql = if nothing_to_update {
+            quote! {
+                Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    format!(
+                        "The update of {} has no column to assign, since every field is part of its primary key",
+                        stringify!(#ty)
+                    )
+                ).into())
+            }
+        } else {
+            quote! {
+                let update_values: Vec<&dyn canyon_sql::crud::bounds::QueryParameter<'_>> = vec![#(#update_values_sql),*];
+                Ok((#stmt_sql, update_values))
+            }
+        };
+
+        let hooked_update = with_hooks(
+            ty,
+            "update",
+            Hooked::One(quote! { &mut *self }),
+            quote! {
+                canyon_sql::crud::validation::Validate::validate(&*entity)?;
+                #fill_updated_at
+            },
+            quote! {
+                let update_values: &[&dyn canyon_sql::crud::bounds::QueryParameter<'_>] = &[#(#update_values),*];
+                #update_transaction
+            },
+        );
+
+        // Only the changed columns are written, guarded by the version of the snapshot
+        let changes_result = match version {
+            Some(version) => quote! {
+                canyon_sql::crud::locking::VersionConflict::check::<#ty>(
+                    i64::from(snapshot.#version), affected_rows
+                )?;
+                self.#version = snapshot.#version + 1;
+                Ok(())
+            },
+            None => quote! { Ok(()) },
+        };
+        let hooked_update_changes = with_hooks(
+            ty,
+            "update",
+            Hooked::One(quote! { &mut *self }),
+            quote! { canyon_sql::crud::validation::Validate::validate(&*entity)?; },
+            quote! {
+                let mut update = <#ty as canyon_sql::crud::CrudOperations<#ty>>::update_query_datasource(
+                    datasource_name
+                );
+                update.changes(snapshot, &*self)?;
+                let affected_rows = update.execute().await?;
+                #changes_result
+            },
+        );
+
+        quote! {
+            /// Returns the SQL of the `update` operation for the `database_type`,
+            /// along with its parameters, without querying the database
+            fn update_sql(&self, database_type: canyon_sql::crud::DatabaseType)
+                -> Result<
+                    (String, Vec<&dyn canyon_sql::crud::bounds::QueryParameter<'_>>),
+                    Box<dyn std::error::Error + Sync + std::marker::Send>
+                >
+            {
+                #update_sql
+            }
+
+            /// Updates a database record that matches
+            /// the current instance of a T type, returning a result
+            /// indicating a possible failure querying the database.
+            async fn update(&mut self) -> Result<(), Box<dyn std::error::Error + Sync + std::marker::Send>> {
+                let datasource_name = "";
+                #hooked_update
+            }
 
 
             /// Updates a database record that matches
             /// the current instance of a T type, returning a result
             /// indicating a possible failure querying the database with the
             /// specified datasource
-            async fn update_datasource<'a>(&self, datasource_name: &'a str)
+            async fn update_datasource<'a>(&mut self, datasource_name: &'a str)
                 -> Result<(), Box<dyn std::error::Error + Sync + std::marker::Send>>
             {
-                let stmt = format!(
-                    "UPDATE {} SET {} WHERE {} = ${:?}",
-                    #table_schema_data, #str_columns_values, #primary_key, #pk_index + 1
-                );
-                let update_values: &[&dyn canyon_sql::crud::bounds::QueryParameter<'_>] = &[#(#update_values_cloned),*];
+                #hooked_update
+            }
 
-                let result = <#ty as canyon_sql::crud::Transaction<#ty>>::query(
-                    stmt, update_values, datasource_name
-                ).await;
+            /// Updates the columns of the database record that matches the current
+            /// instance of a T type whose values changed since the `snapshot`,
+            /// returning a result indicating a possible failure querying the database
+            async fn update_changes(&mut self, snapshot: &#ty)
+                -> Result<(), Box<dyn std::error::Error + Sync + std::marker::Send>>
+            {
+                self.update_changes_datasource(snapshot, "").await
+            }
 
-                if let Err(e) = result {
-                    Err(e)
-                } else { Ok(()) }
+            /// Updates the columns of the database record that matches the current
+            /// instance of a T type whose values changed since the `snapshot`,
+            /// returning a result indicating a possible failure querying the database
+            /// with the specified datasource
+            async fn update_changes_datasource<'a>(&mut self, snapshot: &#ty, datasource_name: &'a str)
+                -> Result<(), Box<dyn std::error::Error + Sync + std::marker::Send>>
+            {
+                if canyon_sql::crud::tracking::changed_columns(snapshot, self).is_empty() {
+                    return Ok(());
+                }
+                #hooked_update_changes
+            }
+
+            /// Generates a [`canyon_sql::query::UpdateQueryBuilder`] that assigns the
+            /// columns whose values changed between `old` and `new` to the ones of `new`,
+            /// filtered by its primary key
+            fn diff<'a>(old: &'a #ty, new: &'a #ty)
+                -> Result<
+                    canyon_sql::query::UpdateQueryBuilder<'a, #ty>,
+                    Box<dyn std::error::Error + Sync + std::marker::Send>
+                >
+            {
+                Self::diff_datasource(old, new, "")
+            }
+
+            /// Generates a [`canyon_sql::query::UpdateQueryBuilder`] that assigns the
+            /// columns whose values changed between `old` and `new` to the ones of `new`,
+            /// filtered by its primary key, against the specified datasource
+            fn diff_datasource<'a>(old: &'a #ty, new: &'a #ty, datasource_name: &'a str)
+                -> Result<
+                    canyon_sql::query::UpdateQueryBuilder<'a, #ty>,
+                    Box<dyn std::error::Error + Sync + std::marker::Send>
+                >
+            {
+                let mut update = <#ty as canyon_sql::crud::CrudOperations<#ty>>::update_query_datasource(
+                    datasource_name
+                );
+                update.changes(old, new)?;
+                Ok(update)
             }
         }
     } else {
@@ -78,31 +308,65 @@ pub fn generate_update_tokens(macro_data: &MacroTokens, table_schema_data: &Stri
         // Use instead the update associated function of the querybuilder
 
         // TODO Returning an error should be a provisional way of doing this
+        let unsupported_update_sql = unsupported_operation("update_sql", "primary_key");
+        let unsupported_update = unsupported_operation("update", "primary_key");
+        let unsupported_update_datasource =
+            unsupported_operation("update_datasource", "primary_key");
+        let unsupported_update_changes = unsupported_operation("update_changes", "primary_key");
+        let unsupported_update_changes_datasource =
+            unsupported_operation("update_changes_datasource", "primary_key");
+        let unsupported_diff = unsupported_operation("diff", "primary_key");
+        let unsupported_diff_datasource = unsupported_operation("diff_datasource", "primary_key");
         quote! {
-            async fn update(&self)
+            fn update_sql(&self, database_type: canyon_sql::crud::DatabaseType)
+                -> Result<
+                    (String, Vec<&dyn canyon_sql::crud::bounds::QueryParameter<'_>>),
+                    Box<dyn std::error::Error + Sync + std::marker::Send>
+                >
+            {
+                #unsupported_update_sql
+            }
+
+            async fn update(&mut self)
+                -> Result<(), Box<dyn std::error::Error + Sync + std::marker::Send>>
+            {
+                #unsupported_update
+            }
+
+            async fn update_datasource<'a>(&mut self, datasource_name: &'a str)
                 -> Result<(), Box<dyn std::error::Error + Sync + std::marker::Send>>
             {
-                Err(
-                    std::io::Error::new(
-                        std::io::ErrorKind::Unsupported,
-                        "You can't use the 'update' method on a \
-                        CanyonEntity that does not have a #[primary_key] annotation. \
-                        If you need to perform an specific search, use the Querybuilder instead."
-                    ).into_inner().unwrap()
-                )
+                #unsupported_update_datasource
             }
 
-            async fn update_datasource<'a>(&self, datasource_name: &'a str)
+            async fn update_changes(&mut self, snapshot: &#ty)
                 -> Result<(), Box<dyn std::error::Error + Sync + std::marker::Send>>
             {
-                Err(
-                    std::io::Error::new(
-                        std::io::ErrorKind::Unsupported,
-                        "You can't use the 'update_datasource' method on a \
-                        CanyonEntity that does not have a #[primary_key] annotation. \
-                        If you need to perform an specific search, use the Querybuilder instead."
-                    ).into_inner().unwrap()
-                )
+                #unsupported_update_changes
+            }
+
+            async fn update_changes_datasource<'a>(&mut self, snapshot: &#ty, datasource_name: &'a str)
+                -> Result<(), Box<dyn std::error::Error + Sync + std::marker::Send>>
+            {
+                #unsupported_update_changes_datasource
+            }
+
+            fn diff<'a>(old: &'a #ty, new: &'a #ty)
+                -> Result<
+                    canyon_sql::query::UpdateQueryBuilder<'a, #ty>,
+                    Box<dyn std::error::Error + Sync + std::marker::Send>
+                >
+            {
+                #unsupported_diff
+            }
+
+            fn diff_datasource<'a>(old: &'a #ty, new: &'a #ty, datasource_name: &'a str)
+                -> Result<
+                    canyon_sql::query::UpdateQueryBuilder<'a, #ty>,
+                    Box<dyn std::error::Error + Sync + std::marker::Send>
+                >
+            {
+                #unsupported_diff_datasource
             }
         }
     }
@@ -116,6 +380,20 @@ pub fn generate_update_query_tokens(
 ) -> TokenStream {
     let ty = macro_data.ty;
 
+    // The `#[updated_at]` fields, assigned by every `set` of the query builder
+    let updated_at = macro_data
+        .get_timestamp_annotations()
+        .into_iter()
+        .filter_map(|(field, _, annotation)| match annotation {
+            EntityFieldAnnotation::UpdatedAt(source) => {
+                let column = field.to_string();
+                let source = timestamp_source(source);
+                Some(quote! { .with_updated_at(#column, #source) })
+            }
+            _ => None,
+        })
+        .collect::<Vec<TokenStream>>();
+
     quote! {
         /// Generates a [`canyon_sql::query::UpdateQueryBuilder`]
         /// that allows you to customize the query by adding parameters and constrains dynamically.
@@ -125,7 +403,7 @@ pub fn generate_update_query_tokens(
         /// unless concrete values are set on the available parameters of the
         /// `canyon_macro(table_name = "table_name", schema = "schema")`
         fn update_query<'a>() -> canyon_sql::query::UpdateQueryBuilder<'a, #ty> {
-            canyon_sql::query::UpdateQueryBuilder::new(#table_schema_data, "")
+            canyon_sql::query::UpdateQueryBuilder::new(#table_schema_data, "")#(#updated_at)*
         }
 
         /// Generates a [`canyon_sql::query::UpdateQueryBuilder`]
@@ -140,7 +418,17 @@ pub fn generate_update_query_tokens(
         /// described in the configuration file, and selected with the [`&str`]
         /// passed as parameter.
         fn update_query_datasource<'a>(datasource_name: &'a str) -> canyon_sql::query::UpdateQueryBuilder<'a, #ty> {
-            canyon_sql::query::UpdateQueryBuilder::new(#table_schema_data, datasource_name)
+            canyon_sql::query::UpdateQueryBuilder::new(#table_schema_data, datasource_name)#(#updated_at)*
+        }
+    }
+}
+
+/// The tokens of the `TimestampSource` of a `#[created_at]` or `#[updated_at]` annotation
+fn timestamp_source(source: TimestampSource) -> TokenStream {
+    match source {
+        TimestampSource::Client => quote! { canyon_sql::crud::timestamps::TimestampSource::Client },
+        TimestampSource::Database => {
+            quote! { canyon_sql::crud::timestamps::TimestampSource::Database }
         }
     }
 }
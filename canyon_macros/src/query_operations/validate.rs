@@ -0,0 +1,90 @@
+use canyon_observer::manager::field_annotation::ValidationRule;
+use proc_macro2::TokenStream;
+use quote::quote;
+
+use crate::utils::macro_tokens::MacroTokens;
+
+/// Generates the TokenStream for the implementation of the `Validate` trait, that
+/// checks the rules of the `#[validate]` annotations of the fields, invoked by the
+/// `insert`, `insert_returning_all`, `multi_insert` and `update` operations
+pub fn generate_validate_tokens(macro_data: &MacroTokens) -> TokenStream {
+    let ty = macro_data.ty;
+    let validate_annotations = macro_data.get_validate_annotations();
+
+    if validate_annotations.is_empty() {
+        return quote! {
+            impl canyon_sql::crud::validation::Validate for #ty {
+                fn validate(&self) -> Result<(), canyon_sql::crud::validation::ValidationErrors> {
+                    Ok(())
+                }
+            }
+        };
+    }
+
+    let checks = validate_annotations.iter().flat_map(|(field, rules)| {
+        let name = field.to_string();
+        rules.iter().map(move |rule| match rule {
+            ValidationRule::Length(min, max) => {
+                let (min, max) = (optional(min.map(|min| quote! { #min })), optional(max.map(|max| quote! { #max })));
+                quote! {
+                    errors.check(#name, "length", canyon_sql::crud::validation::length(&self.#field, #min, #max));
+                }
+            }
+            ValidationRule::Range(min, max) => {
+                let (min, max) = (optional(min.map(float)), optional(max.map(float)));
+                quote! {
+                    errors.check(#name, "range", canyon_sql::crud::validation::range(&self.#field, #min, #max));
+                }
+            }
+            ValidationRule::Regex(pattern) => quote! {
+                {
+                    static PATTERN: std::sync::OnceLock<canyon_sql::crud::validation::Regex> =
+                        std::sync::OnceLock::new();
+                    let pattern = PATTERN.get_or_init(|| {
+                        canyon_sql::crud::validation::Regex::new(#pattern)
+                            .expect("The pattern is checked by the `canyon_entity` macro")
+                    });
+                    errors.check(#name, "regex", canyon_sql::crud::validation::regex(&self.#field, pattern));
+                }
+            },
+            ValidationRule::Email => quote! {
+                errors.check(#name, "email", canyon_sql::crud::validation::email(&self.#field));
+            },
+            ValidationRule::Custom(function) => {
+                let function: syn::Path = syn::parse_str(function)
+                    .expect("The path is checked by the `canyon_entity` macro");
+                quote! {
+                    errors.check(#name, "custom", #function(&self.#field));
+                }
+            }
+        })
+    });
+
+    quote! {
+        impl canyon_sql::crud::validation::Validate for #ty {
+            fn validate(&self) -> Result<(), canyon_sql::crud::validation::ValidationErrors> {
+                let mut errors = canyon_sql::crud::validation::ValidationErrors::default();
+                #(#checks)*
+                errors.into_result()
+            }
+        }
+    }
+}
+
+/// The tokens of an `Option` with the `value`, if any
+fn optional(value: Option<TokenStream>) -> TokenStream {
+    match value {
+        Some(value) => quote! { Some(#value) },
+        None => quote! { None },
+    }
+}
+
+/// The tokens of an `f64` literal, negated outside of the literal if it's negative
+fn float(value: f64) -> TokenStream {
+    let absolute = value.abs();
+    if value.is_sign_negative() {
+        quote! { -#absolute }
+    } else {
+        quote! { #absolute }
+    }
+}
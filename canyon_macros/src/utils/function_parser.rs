@@ -7,6 +7,7 @@ use syn::{
 #[derive(Clone)]
 pub struct FunctionParser {
     pub attrs: Vec<Attribute>,
+    #[allow(dead_code)]
     pub vis: Visibility,
     pub sig: Signature,
     pub block: Box<Block>,
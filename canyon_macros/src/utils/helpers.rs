@@ -63,8 +63,8 @@ pub fn table_schema_parser(macro_data: &MacroTokens<'_>) -> Result<String, Token
             }
 
             let mut final_table_name = String::new();
-            if schema.is_some() {
-                final_table_name.push_str(format!("{}.", schema.unwrap()).as_str())
+            if let Some(schema) = schema {
+                final_table_name.push_str(format!("{schema}.").as_str())
             }
 
             if let Some(t_name) = table_name {
@@ -143,7 +143,7 @@ pub fn database_table_name_to_struct_ident(name: &str) -> Ident {
             first_iteration = false;
         } else {
             match char {
-                n if n == '_' => {
+                '_' => {
                     previous_was_underscore = true;
                 }
                 char if char.is_ascii_lowercase() => {
@@ -1,30 +1,44 @@
 use std::convert::TryFrom;
 
-use canyon_observer::manager::field_annotation::EntityFieldAnnotation;
+use canyon_crud::{soft_delete::SoftDelete, timestamps::TimestampSource};
+use canyon_observer::manager::field_annotation::{EntityFieldAnnotation, ValidationRule};
 use proc_macro2::Ident;
-use syn::{Attribute, DeriveInput, Fields, Generics, Type, Visibility};
+use syn::{Attribute, DeriveInput, Field, Generics, Type, Visibility};
 
 /// Provides a convenient way of store the data for the TokenStream
 /// received on a macro
 pub struct MacroTokens<'a> {
+    #[allow(dead_code)]
     pub vis: &'a Visibility,
     pub ty: &'a Ident,
+    #[allow(dead_code)]
     pub generics: &'a Generics,
     pub attrs: &'a Vec<Attribute>,
-    pub fields: &'a Fields,
+    /// The fields mapped against a column of the table
+    pub fields: Vec<&'a Field>,
+    /// The fields annotated with `#[has_many]` or `#[many_to_many]`
+    pub relation_fields: Vec<&'a Field>,
 }
 
 impl<'a> MacroTokens<'a> {
     pub fn new(ast: &'a DeriveInput) -> Self {
+        let (relation_fields, fields) = match &ast.data {
+            syn::Data::Struct(ref s) => s.fields.iter().partition(|field| {
+                field
+                    .attrs
+                    .iter()
+                    .any(EntityFieldAnnotation::is_relation_attribute)
+            }),
+            _ => panic!("This derive macro can only be automatically derived for structs"),
+        };
+
         Self {
             vis: &ast.vis,
             ty: &ast.ident,
             generics: &ast.generics,
             attrs: &ast.attrs,
-            fields: match &ast.data {
-                syn::Data::Struct(ref s) => &s.fields,
-                _ => panic!("This derive macro can only be automatically derived for structs"),
-            },
+            fields,
+            relation_fields,
         }
     }
 
@@ -77,45 +91,33 @@ impl<'a> MacroTokens<'a> {
     /// (autoincremental = true) or its without the autoincremental attribute, which leads
     /// to the same behaviour.
     ///
-    /// Returns every field if there's no PK, or if it's present but autoincremental = false
+    /// Returns every field if there's no PK, or if it's present but autoincremental = false,
+    /// or if the PK is a composite one, which is never autogenerated by the database
     pub fn get_column_names_pk_parsed(&self) -> Vec<String> {
+        let has_composite_pk = self.has_composite_primary_key();
         self.fields
             .iter()
             .filter(|field| {
-                if !field.attrs.is_empty() {
-                    field.attrs.iter().any(|attr| {
-                        let a = attr.path.segments[0].clone().ident;
-                        let b = attr.tokens.to_string();
-                        !(a == "primary_key" || b.contains("false"))
-                    })
-                } else {
-                    true
-                }
+                // Other attributes, like `#[foreign_key]` or `#[validate]`,
+                // doesn't change if the column it's generated by the database
+                has_composite_pk
+                    || !field
+                        .attrs
+                        .iter()
+                        .any(|attr| attr.path.segments[0].ident == "primary_key")
             })
             .map(|c| format!("\"{}\"", c.ident.as_ref().unwrap()))
             .collect::<Vec<String>>()
     }
 
-    /// Retrieves the fields of the Struct as continuous String, comma separated
-    pub fn get_struct_fields_as_strings(&self) -> String {
-        let column_names: String = self
-            .get_struct_fields()
-            .iter()
-            .map(|ident| ident.to_owned().to_string())
-            .collect::<Vec<String>>()
-            .iter()
-            .map(|column| column.to_owned() + ", ")
-            .collect::<String>();
-
-        let mut column_names_as_chars = column_names.chars();
-        column_names_as_chars.next_back();
-        column_names_as_chars.next_back();
-
-        column_names_as_chars.as_str().to_owned()
-    }
-
+    /// Returns the position of the field annotated with `#[primary_key]`
+    /// (if exists) in the declaration order of the struct fields.
     ///
+    /// Returns `None` if the primary key is a composite one. Use [`Self::get_pk_indexes`] instead
     pub fn get_pk_index(&self) -> Option<usize> {
+        if self.has_composite_primary_key() {
+            return None;
+        }
         let mut pk_index = None;
         for (idx, field) in self.fields.iter().enumerate() {
             for attr in &field.attrs {
@@ -127,9 +129,31 @@ impl<'a> MacroTokens<'a> {
         pk_index
     }
 
+    /// Returns the positions of all the fields annotated with `#[primary_key]`
+    /// in the declaration order of the struct fields
+    pub fn get_pk_indexes(&self) -> Vec<usize> {
+        self.fields
+            .iter()
+            .enumerate()
+            .filter(|(_, field)| {
+                field
+                    .attrs
+                    .iter()
+                    .any(|attr| attr.path.segments[0].clone().ident == "primary_key")
+            })
+            .map(|(idx, _)| idx)
+            .collect::<Vec<usize>>()
+    }
+
     /// Utility for find the primary key attribute (if exists) and the
-    /// column name (field) which belongs
+    /// column name (field) which belongs.
+    ///
+    /// Returns `None` if the primary key is a composite one. Use
+    /// [`Self::get_primary_key_annotations`] instead
     pub fn get_primary_key_annotation(&self) -> Option<String> {
+        if self.has_composite_primary_key() {
+            return None;
+        }
         let f = self.fields.iter().find(|field| {
             field
                 .attrs
@@ -143,6 +167,22 @@ impl<'a> MacroTokens<'a> {
         f.map(|v| v.ident.clone().unwrap().to_string())
     }
 
+    /// Retrieves the name and the type of every field annotated with `#[primary_key]`,
+    /// in the declaration order of the struct fields
+    pub fn get_primary_key_annotations(&self) -> Vec<(Ident, Type)> {
+        let fields_with_types = self._fields_with_types();
+        self.get_pk_indexes()
+            .into_iter()
+            .map(|idx| fields_with_types[idx].clone())
+            .collect::<Vec<_>>()
+    }
+
+    /// Boolean that returns true if the type contains more than one `#[primary_key]`
+    /// annotation, being its primary key a composite one. False otherwise.
+    pub fn has_composite_primary_key(&self) -> bool {
+        self.get_pk_indexes().len() > 1
+    }
+
     /// Utility for find the `foreign_key` attributes (if exists)
     pub fn get_fk_annotations(&self) -> Vec<(&Ident, EntityFieldAnnotation)> {
         let mut foreign_key_annotations = Vec::new();
@@ -163,45 +203,121 @@ impl<'a> MacroTokens<'a> {
         foreign_key_annotations
     }
 
-    /// Boolean that returns true if the type contains a `#[primary_key]`
-    /// annotation. False otherwise.
-    pub fn type_has_primary_key(&self) -> bool {
-        self.fields.iter().any(|field| {
+    /// Utility for find the `has_many` and `many_to_many` attributes of the relation fields
+    pub fn get_relation_annotations(&self) -> Vec<(&Ident, EntityFieldAnnotation)> {
+        let mut relation_annotations = Vec::new();
+
+        self.relation_fields.iter().for_each(|field| {
             field
                 .attrs
                 .iter()
-                .map(|attr| attr.path.segments[0].clone().ident)
-                .map(|ident| ident.to_string())
-                .find(|a| a == "primary_key")
-                == Some("primary_key".to_string())
-        })
+                .filter(|attr| EntityFieldAnnotation::is_relation_attribute(attr))
+                .for_each(|attr| {
+                    if let Ok(annotation) = EntityFieldAnnotation::try_from(&attr) {
+                        relation_annotations.push((field.ident.as_ref().unwrap(), annotation))
+                    }
+                });
+        });
+
+        relation_annotations
     }
 
-    /// Returns an String ready to be inserted on the VALUES Sql clause
-    /// representing generic query parameters ($x).
-    ///
-    /// Already returns the correct number of placeholders, skipping one
-    /// entry in the type contains a `#[primary_key]`
-    pub fn placeholders_generator(&self) -> String {
-        let mut placeholders = String::new();
-        if self.type_has_primary_key() {
-            for num in 1..self.fields.len() {
-                if num < self.fields.len() - 1 {
-                    placeholders.push_str(&("$".to_owned() + &(num).to_string() + ", "));
-                } else {
-                    placeholders.push_str(&("$".to_owned() + &(num).to_string()));
-                }
-            }
-        } else {
-            for num in 1..self.fields.len() + 1 {
-                if num < self.fields.len() {
-                    placeholders.push_str(&("$".to_owned() + &(num).to_string() + ", "));
-                } else {
-                    placeholders.push_str(&("$".to_owned() + &(num).to_string()));
-                }
-            }
-        }
+    /// Utility for find the rules of the `validate` attributes of every field,
+    /// in the order that they're declared. The malformed ones are already reported
+    /// by the `canyon_entity` macro
+    pub fn get_validate_annotations(&self) -> Vec<(&Ident, Vec<ValidationRule>)> {
+        let mut validate_annotations = Vec::new();
+
+        self.fields.iter().for_each(|field| {
+            field
+                .attrs
+                .iter()
+                .filter(|attr| attr.path.segments[0].ident == "validate")
+                .for_each(|attr| {
+                    if let Ok(EntityFieldAnnotation::Validate(rules)) =
+                        EntityFieldAnnotation::try_from(&attr)
+                    {
+                        validate_annotations.push((field.ident.as_ref().unwrap(), rules))
+                    }
+                });
+        });
+
+        validate_annotations
+    }
+
+    /// Utility for find the `created_at` and `updated_at` attributes, along with
+    /// the name and the type of the annotated fields
+    pub fn get_timestamp_annotations(&self) -> Vec<(&Ident, &Type, EntityFieldAnnotation)> {
+        let mut timestamp_annotations = Vec::new();
+
+        self.fields.iter().for_each(|field| {
+            field
+                .attrs
+                .iter()
+                .filter(|attr| {
+                    let ident = &attr.path.segments[0].ident;
+                    ident == "created_at" || ident == "updated_at"
+                })
+                .for_each(|attr| {
+                    if let Ok(annotation) = EntityFieldAnnotation::try_from(&attr) {
+                        timestamp_annotations.push((
+                            field.ident.as_ref().unwrap(),
+                            &field.ty,
+                            annotation,
+                        ))
+                    }
+                });
+        });
 
-        placeholders
+        timestamp_annotations
+    }
+
+    /// The fields whose value is the current time of the database, that are left
+    /// out of the inserts so the columns takes their default value
+    pub fn get_database_timestamp_fields(&self) -> Vec<&Ident> {
+        self.get_timestamp_annotations()
+            .into_iter()
+            .filter(|(_, _, annotation)| {
+                matches!(
+                    annotation,
+                    EntityFieldAnnotation::CreatedAt(TimestampSource::Database)
+                        | EntityFieldAnnotation::UpdatedAt(TimestampSource::Database)
+                )
+            })
+            .map(|(field, _, _)| field)
+            .collect()
+    }
+
+    /// Utility for find the field annotated with `#[soft_delete]` (if exists), along
+    /// with how it tells the deleted rows, by a `bool` or by a nullable time
+    pub fn get_soft_delete_field(&self) -> Option<(&Ident, SoftDelete)> {
+        self.fields
+            .iter()
+            .find(|field| {
+                field
+                    .attrs
+                    .iter()
+                    .any(|attr| attr.path.segments[0].ident == "soft_delete")
+            })
+            .map(|field| {
+                let soft_delete = match &field.ty {
+                    Type::Path(ty) if ty.path.is_ident("bool") => SoftDelete::Flag,
+                    _ => SoftDelete::Timestamp,
+                };
+                (field.ident.as_ref().unwrap(), soft_delete)
+            })
+    }
+
+    /// Utility for find the field annotated with `#[version]` (if exists)
+    pub fn get_version_field(&self) -> Option<&Ident> {
+        self.fields
+            .iter()
+            .find(|field| {
+                field
+                    .attrs
+                    .iter()
+                    .any(|attr| attr.path.segments[0].ident == "version")
+            })
+            .map(|field| field.ident.as_ref().unwrap())
     }
 }
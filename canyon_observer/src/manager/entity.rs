@@ -26,13 +26,18 @@ unsafe impl Send for CanyonEntity {}
 unsafe impl Sync for CanyonEntity {}
 
 impl CanyonEntity {
+    /// The fields of the entity that are mapped against a column of the table,
+    /// skipping the relation fields
+    pub fn column_fields(&self) -> impl Iterator<Item = &EntityField> {
+        self.fields.iter().filter(|field| !field.is_relation())
+    }
+
     /// Generates as many variants for the enum as fields has the type
     /// which this enum is related to, and that type it's the entity
     /// stored in [`CanyonEntity`]
     /// of the corresponding field
     pub fn get_fields_as_enum_variants(&self) -> Vec<TokenStream> {
-        self.fields
-            .iter()
+        self.column_fields()
             .map(|f| {
                 let field_name = &f.name;
                 quote! { #field_name }
@@ -46,8 +51,7 @@ impl CanyonEntity {
     /// Makes a variant `#field_name(#ty)` where `#ty` it's a trait object
     /// of type [`canyon_crud::bounds::QueryParameter`]
     pub fn get_fields_as_enum_variants_with_value(&self) -> Vec<TokenStream> {
-        self.fields
-            .iter()
+        self.column_fields()
             .map(|f| {
                 let field_name = &f.name;
                 quote! { #field_name(&'a dyn canyon_sql::crud::bounds::QueryParameter<'a>) }
@@ -56,8 +60,7 @@ impl CanyonEntity {
     }
 
     pub fn create_match_arm_for_get_variant_as_str(&self, enum_name: &Ident) -> Vec<TokenStream> {
-        self.fields
-            .iter()
+        self.column_fields()
             .map(|f| {
                 let field_name = &f.name;
                 let field_name_as_str = f.name.to_string();
@@ -76,8 +79,7 @@ impl CanyonEntity {
         &self,
         enum_name: &Ident,
     ) -> Vec<TokenStream> {
-        self.fields
-            .iter()
+        self.column_fields()
             .map(|f| {
                 let field_name = &f.name;
                 let field_name_as_string = f.name.to_string();
@@ -96,8 +98,7 @@ impl CanyonEntity {
         &self,
         enum_name: &Ident,
     ) -> Vec<TokenStream> {
-        self.fields
-            .iter()
+        self.column_fields()
             .map(|f| {
                 let field_name = &f.name;
                 let field_name_as_string = f.name.to_string();
@@ -132,6 +133,30 @@ impl Parse for CanyonEntity {
             parsed_fields.push(struct_attribute)
         }
 
+        // Only one column could tell if a row was deleted
+        if let Some(field) = parsed_fields
+            .iter()
+            .filter(|field| field.is_soft_delete())
+            .nth(1)
+        {
+            return Err(syn::Error::new_spanned(
+                &field.name,
+                "Only one field of the entity could be annotated with `soft_delete`",
+            ));
+        }
+
+        // Neither could more than one hold the version of the row
+        if let Some(field) = parsed_fields
+            .iter()
+            .filter(|field| field.is_version())
+            .nth(1)
+        {
+            return Err(syn::Error::new_spanned(
+                &field.name,
+                "Only one field of the entity could be annotated with `version`",
+            ));
+        }
+
         Ok(Self {
             struct_name: _struct.ident,
             user_table_name: None,
@@ -35,6 +35,26 @@ impl EntityField {
         }
     }
 
+    /// Returns true if the field holds the entities of a relation (`#[has_many]`
+    /// or `#[many_to_many]`) instead of the value of a column
+    pub fn is_relation(&self) -> bool {
+        self.attributes.iter().any(|attr| attr.is_relation())
+    }
+
+    /// Returns true if the field tells if the row was soft deleted
+    pub fn is_soft_delete(&self) -> bool {
+        self.attributes
+            .iter()
+            .any(|attr| matches!(attr, EntityFieldAnnotation::SoftDelete))
+    }
+
+    /// Returns true if the field holds the version of the row
+    pub fn is_version(&self) -> bool {
+        self.attributes
+            .iter()
+            .any(|attr| matches!(attr, EntityFieldAnnotation::Version))
+    }
+
     pub fn new(name: &Ident, raw_helper_attributes: &[Attribute], ty: &Type) -> syn::Result<Self> {
         let mut attributes = Vec::new();
         for attr in raw_helper_attributes {
@@ -45,11 +65,46 @@ impl EntityField {
             }
         }
 
-        Ok(Self {
+        let field = Self {
             name: name.clone(),
             field_type: ty.clone(),
             attributes,
-        })
+        };
+
+        // The timestamps are filled with the current time as a `NaiveDateTime`
+        let field_type = field.get_field_type_as_string().replace(' ', "");
+        if field.attributes.iter().any(|attr| attr.is_timestamp())
+            && !field_type.ends_with("NaiveDateTime")
+            && !field_type.ends_with("NaiveDateTime>")
+        {
+            return Err(syn::Error::new_spanned(
+                ty,
+                "The `created_at` and `updated_at` annotations are only supported \
+                on `NaiveDateTime` and `Option<NaiveDateTime>` fields",
+            ));
+        }
+
+        // The deleted rows are told apart by a time or by a flag
+        if field.is_soft_delete()
+            && !field_type.ends_with("Option<NaiveDateTime>")
+            && field_type != "bool"
+        {
+            return Err(syn::Error::new_spanned(
+                ty,
+                "The `soft_delete` annotation is only supported \
+                on `Option<NaiveDateTime>` and `bool` fields",
+            ));
+        }
+
+        // The version is moved forward by one on every write
+        if field.is_version() && !["i16", "i32", "i64"].contains(&field_type.as_str()) {
+            return Err(syn::Error::new_spanned(
+                ty,
+                "The `version` annotation is only supported on `i16`, `i32` and `i64` fields",
+            ));
+        }
+
+        Ok(field)
     }
 }
 
@@ -1,6 +1,10 @@
+use canyon_crud::timestamps::TimestampSource;
 use proc_macro2::Ident;
 use std::{collections::HashMap, convert::TryFrom};
-use syn::{punctuated::Punctuated, Attribute, MetaNameValue, Token};
+use syn::{
+    parenthesized, parse::ParseStream, punctuated::Punctuated, Attribute, Lit, LitStr,
+    MetaNameValue, Token, Type,
+};
 
 /// The available annotations for a field that belongs to any struct
 /// annotaded with `#[canyon_entity]`
@@ -8,6 +12,37 @@ use syn::{punctuated::Punctuated, Attribute, MetaNameValue, Token};
 pub enum EntityFieldAnnotation {
     PrimaryKey(bool),
     ForeignKey(String, String),
+    /// The related entity of a one-to-many relation field
+    HasMany(String),
+    /// The related entity, the join table and the (optional) columns of the join
+    /// table that points to the annotated entity and to the related one
+    ManyToMany(String, String, Option<String>, Option<String>),
+    /// The rules of a `#[validate(...)]` attribute
+    Validate(Vec<ValidationRule>),
+    /// A `#[created_at]` field, filled with the current time of the source by the inserts
+    CreatedAt(TimestampSource),
+    /// A `#[updated_at]` field, filled with the current time of the source by the inserts and the updates
+    UpdatedAt(TimestampSource),
+    /// A `#[soft_delete]` field, that marks the row as deleted instead of removing it
+    SoftDelete,
+    /// A `#[version]` field, that guards the writes of the row against the concurrent ones
+    Version,
+}
+
+/// A rule of the `#[validate(...)]` annotation, checked by the generated
+/// `Validate` implementation of the entity
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValidationRule {
+    /// The min and max number of characters of a text, `length(min = 1, max = 64)`
+    Length(Option<usize>, Option<usize>),
+    /// The min and max (both inclusive) values of a number, `range(min = 0, max = 100)`
+    Range(Option<f64>, Option<f64>),
+    /// The pattern that a text must match, `regex = "^[a-z-]+$"`
+    Regex(String),
+    /// A text that looks like an email address, `email`
+    Email,
+    /// The path of an user defined `fn(&T) -> Result<(), String>`, `custom = "path::to::fn"`
+    Custom(String),
 }
 
 impl EntityFieldAnnotation {
@@ -21,9 +56,66 @@ impl EntityFieldAnnotation {
             Self::ForeignKey(table, column) => {
                 format!("Annotation: ForeignKey, Table: {table}, Column: {column}")
             }
+            Self::HasMany(entity) => format!("Annotation: HasMany, Entity: {entity}"),
+            Self::ManyToMany(entity, through, _, _) => {
+                format!("Annotation: ManyToMany, Entity: {entity}, Through: {through}")
+            }
+            // Only the rules that could be checked by the database. The pattern
+            // goes at the end, since it may contain any character
+            Self::Validate(rules) => {
+                let mut annotation = String::from("Annotation: Validate");
+                let mut pattern = None;
+                for rule in rules {
+                    match rule {
+                        ValidationRule::Length(min, max) => {
+                            if let Some(min) = min {
+                                annotation.push_str(&format!(", MinLength: {min}"));
+                            }
+                            if let Some(max) = max {
+                                annotation.push_str(&format!(", MaxLength: {max}"));
+                            }
+                        }
+                        ValidationRule::Range(min, max) => {
+                            if let Some(min) = min {
+                                annotation.push_str(&format!(", Min: {min}"));
+                            }
+                            if let Some(max) = max {
+                                annotation.push_str(&format!(", Max: {max}"));
+                            }
+                        }
+                        ValidationRule::Regex(regex) => pattern = Some(regex),
+                        ValidationRule::Email | ValidationRule::Custom(_) => {}
+                    }
+                }
+                if let Some(pattern) = pattern {
+                    annotation.push_str(&format!(", Regex: {pattern}"));
+                }
+                annotation
+            }
+            Self::CreatedAt(source) => format!("Annotation: CreatedAt, Source: {source:?}"),
+            Self::UpdatedAt(source) => format!("Annotation: UpdatedAt, Source: {source:?}"),
+            Self::SoftDelete => String::from("Annotation: SoftDelete"),
+            Self::Version => String::from("Annotation: Version"),
         }
     }
 
+    /// Returns true for the annotations of the relation fields, that are
+    /// not mapped against any column of the table
+    pub fn is_relation(&self) -> bool {
+        matches!(self, Self::HasMany(_) | Self::ManyToMany(..))
+    }
+
+    /// Returns true for the `#[created_at]` and `#[updated_at]` annotations
+    pub fn is_timestamp(&self) -> bool {
+        matches!(self, Self::CreatedAt(_) | Self::UpdatedAt(_))
+    }
+
+    /// Returns true if the attribute declares a relation field
+    /// (`#[has_many]` or `#[many_to_many]`)
+    pub fn is_relation_attribute(attribute: &Attribute) -> bool {
+        attribute.path.is_ident("has_many") || attribute.path.is_ident("many_to_many")
+    }
+
     /// Retrieves the user defined data in the #[primary_key] attribute
     fn primary_key_parser(
         ident: &Ident,
@@ -126,6 +218,255 @@ impl EntityFieldAnnotation {
             )),
         }
     }
+
+    /// Retrieves the related entity declared in the `#[has_many(Entity)]` attribute
+    fn has_many_parser(attribute: &Attribute) -> syn::Result<Self> {
+        let entity: Type = attribute.parse_args().map_err(|_| {
+            syn::Error::new_spanned(
+                attribute,
+                "Expected the related entity on the `has_many` annotation, like `#[has_many(Entity)]`",
+            )
+        })?;
+
+        Ok(EntityFieldAnnotation::HasMany(
+            quote::quote!(#entity).to_string().replace(' ', ""),
+        ))
+    }
+
+    /// Retrieves the related entity and the join table arguments declared in the
+    /// `#[many_to_many(Entity, through = "table", source_column = "..", target_column = "..")]`
+    /// attribute, where only the `through` argument is mandatory
+    fn many_to_many_parser(attribute: &Attribute) -> syn::Result<Self> {
+        let (entity, name_values) = attribute.parse_args_with(|input: ParseStream| {
+            let entity: Type = input.parse()?;
+            let mut name_values = Vec::new();
+            while !input.is_empty() {
+                input.parse::<Token![,]>()?;
+                if input.is_empty() {
+                    break;
+                }
+                name_values.push(input.parse::<MetaNameValue>()?);
+            }
+            Ok((entity, name_values))
+        })?;
+
+        let mut data: HashMap<String, String> = HashMap::new();
+        for nv in name_values {
+            let attr_value_ident = nv.path.get_ident().unwrap().to_string();
+            match (attr_value_ident.as_str(), &nv.lit) {
+                ("through" | "source_column" | "target_column", syn::Lit::Str(v)) => {
+                    data.insert(attr_value_ident, v.value());
+                }
+                ("through" | "source_column" | "target_column", _) => {
+                    return Err(syn::Error::new_spanned(
+                        nv.path,
+                        format!("Only string literals are supported for the `{attr_value_ident}` attribute"),
+                    ))
+                }
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        nv.path,
+                        format!("Unknown argument `{attr_value_ident}` on the Many To Many annotation"),
+                    ))
+                }
+            }
+        }
+
+        Ok(EntityFieldAnnotation::ManyToMany(
+            quote::quote!(#entity).to_string().replace(' ', ""),
+            match data.remove("through") {
+                Some(through) => through,
+                None => {
+                    return Err(syn::Error::new_spanned(
+                        attribute,
+                        "Missed `through` argument on the Many To Many annotation".to_string(),
+                    ))
+                }
+            },
+            data.remove("source_column"),
+            data.remove("target_column"),
+        ))
+    }
+
+    /// Retrieves the source of the current time declared in the `#[created_at]` and
+    /// `#[updated_at]` attributes, like `#[updated_at(source = "database")]`, being
+    /// the client the default one for the attributes without arguments
+    fn timestamp_source_parser(
+        ident: &Ident,
+        attribute: &Attribute,
+        attr_args: &Result<Punctuated<MetaNameValue, Token![,]>, syn::Error>,
+    ) -> syn::Result<TimestampSource> {
+        if attribute.tokens.is_empty() {
+            return Ok(TimestampSource::Client);
+        }
+        let name_values = attr_args.as_ref().map_err(Clone::clone)?;
+
+        let mut source = TimestampSource::Client;
+        for nv in name_values {
+            let Some(attr_value_ident) = nv.path.get_ident().map(ToString::to_string) else {
+                return Err(syn::Error::new_spanned(
+                    &nv.path,
+                    format!(
+                        "Expected a single identifier as the argument of the `{ident}` annotation"
+                    ),
+                ));
+            };
+            source = match (attr_value_ident.as_str(), &nv.lit) {
+                ("source", syn::Lit::Str(v)) if v.value() == "client" => TimestampSource::Client,
+                ("source", syn::Lit::Str(v)) if v.value() == "database" => {
+                    TimestampSource::Database
+                }
+                ("source", _) => {
+                    return Err(syn::Error::new_spanned(
+                        &nv.lit,
+                        format!("Expected `\"client\"` or `\"database\"` as the source of the `{ident}` annotation"),
+                    ))
+                }
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        nv.path.clone(),
+                        format!("Unknown argument `{attr_value_ident}` on the `{ident}` annotation"),
+                    ))
+                }
+            };
+        }
+
+        Ok(source)
+    }
+
+    /// Retrieves the comma separated rules declared in the
+    /// `#[validate(length(min = 1, max = 64), regex = "^[a-z-]+$", email, custom = "path::to::fn")]`
+    /// attribute, where every kind of rule could appear only once
+    fn validate_parser(attribute: &Attribute) -> syn::Result<Self> {
+        let rules = attribute.parse_args_with(|input: ParseStream| {
+            let mut rules: Vec<ValidationRule> = Vec::new();
+            while !input.is_empty() {
+                let rule: Ident = input.parse()?;
+                let parsed = match rule.to_string().as_str() {
+                    "length" => {
+                        let (min, max) = Self::validation_bounds(input, &rule)?;
+                        let count = |bound: Option<(Lit, f64)>| match bound {
+                            Some((Lit::Int(lit), value)) if value >= 0.0 => {
+                                lit.base10_parse::<usize>().map(Some)
+                            }
+                            Some((lit, _)) => Err(syn::Error::new_spanned(
+                                lit,
+                                "Only positive integer literals are supported for the `length` bounds",
+                            )),
+                            None => Ok(None),
+                        };
+                        ValidationRule::Length(count(min)?, count(max)?)
+                    }
+                    "range" => {
+                        let (min, max) = Self::validation_bounds(input, &rule)?;
+                        ValidationRule::Range(min.map(|b| b.1), max.map(|b| b.1))
+                    }
+                    "regex" => {
+                        input.parse::<Token![=]>()?;
+                        let pattern: LitStr = input.parse()?;
+                        regex::Regex::new(&pattern.value()).map_err(|error| {
+                            syn::Error::new_spanned(&pattern, format!("Invalid regex: {error}"))
+                        })?;
+                        ValidationRule::Regex(pattern.value())
+                    }
+                    "email" => ValidationRule::Email,
+                    "custom" => {
+                        input.parse::<Token![=]>()?;
+                        let function: LitStr = input.parse()?;
+                        function.parse::<syn::Path>()?;
+                        ValidationRule::Custom(function.value())
+                    }
+                    _ => {
+                        return Err(syn::Error::new_spanned(
+                            &rule,
+                            format!("Unknown validation rule `{rule}`. Expected `length`, `range`, `regex`, `email` or `custom`"),
+                        ))
+                    }
+                };
+
+                if rules
+                    .iter()
+                    .any(|r| std::mem::discriminant(r) == std::mem::discriminant(&parsed))
+                {
+                    return Err(syn::Error::new_spanned(
+                        &rule,
+                        format!("Duplicated validation rule `{rule}`"),
+                    ));
+                }
+                rules.push(parsed);
+
+                if !input.is_empty() {
+                    input.parse::<Token![,]>()?;
+                }
+            }
+            Ok(rules)
+        })?;
+
+        if rules.is_empty() {
+            return Err(syn::Error::new_spanned(
+                attribute,
+                "Expected at least one rule on the `validate` annotation",
+            ));
+        }
+
+        Ok(EntityFieldAnnotation::Validate(rules))
+    }
+
+    /// Retrieves the `min` and `max` bounds of the `length(...)` and `range(...)` rules,
+    /// with the literal and its numeric value, where at least one of them is mandatory
+    #[allow(clippy::type_complexity)]
+    fn validation_bounds(
+        input: ParseStream,
+        rule: &Ident,
+    ) -> syn::Result<(Option<(Lit, f64)>, Option<(Lit, f64)>)> {
+        let content;
+        parenthesized!(content in input);
+
+        let (mut min, mut max) = (None, None);
+        while !content.is_empty() {
+            let bound: Ident = content.parse()?;
+            content.parse::<Token![=]>()?;
+            let negative = content.parse::<Option<Token![-]>>()?.is_some();
+            let lit: Lit = content.parse()?;
+            let value = match &lit {
+                Lit::Int(int) if !negative => int.base10_parse::<f64>()?,
+                Lit::Float(float) if !negative => float.base10_parse::<f64>()?,
+                Lit::Int(int) => -int.base10_parse::<f64>()?,
+                Lit::Float(float) => -float.base10_parse::<f64>()?,
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        lit,
+                        format!("Only numeric literals are supported for the `{rule}` bounds"),
+                    ))
+                }
+            };
+            match bound.to_string().as_str() {
+                "min" => min = Some((lit, value)),
+                "max" => max = Some((lit, value)),
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        &bound,
+                        format!(
+                        "Unknown argument `{bound}` on the `{rule}` rule. Expected `min` or `max`"
+                    ),
+                    ))
+                }
+            }
+
+            if !content.is_empty() {
+                content.parse::<Token![,]>()?;
+            }
+        }
+
+        if min.is_none() && max.is_none() {
+            return Err(syn::Error::new_spanned(
+                rule,
+                format!("Expected a `min` or a `max` bound on the `{rule}` rule"),
+            ));
+        }
+
+        Ok((min, max))
+    }
 }
 
 impl TryFrom<&&Attribute> for EntityFieldAnnotation {
@@ -139,6 +480,29 @@ impl TryFrom<&&Attribute> for EntityFieldAnnotation {
         Ok(match ident.to_string().as_str() {
             "primary_key" => EntityFieldAnnotation::primary_key_parser(&ident, &name_values)?,
             "foreign_key" => EntityFieldAnnotation::foreign_key_parser(&ident, &name_values)?,
+            "has_many" => EntityFieldAnnotation::has_many_parser(attribute)?,
+            "many_to_many" => EntityFieldAnnotation::many_to_many_parser(attribute)?,
+            "validate" => EntityFieldAnnotation::validate_parser(attribute)?,
+            "created_at" => EntityFieldAnnotation::CreatedAt(
+                EntityFieldAnnotation::timestamp_source_parser(&ident, attribute, &name_values)?,
+            ),
+            "updated_at" => EntityFieldAnnotation::UpdatedAt(
+                EntityFieldAnnotation::timestamp_source_parser(&ident, attribute, &name_values)?,
+            ),
+            "soft_delete" if attribute.tokens.is_empty() => EntityFieldAnnotation::SoftDelete,
+            "soft_delete" => {
+                return Err(syn::Error::new_spanned(
+                    &attribute.tokens,
+                    "The `soft_delete` annotation doesn't take any argument",
+                ))
+            }
+            "version" if attribute.tokens.is_empty() => EntityFieldAnnotation::Version,
+            "version" => {
+                return Err(syn::Error::new_spanned(
+                    &attribute.tokens,
+                    "The `version` annotation doesn't take any argument",
+                ))
+            }
             _ => {
                 return Err(syn::Error::new_spanned(
                     ident.clone(),
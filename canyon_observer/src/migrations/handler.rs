@@ -26,6 +26,7 @@ impl Migrations {
     /// Launches the mechanism to parse the Database schema, the Canyon register
     /// and the database table with the memory of Canyon to perform the
     /// migrations over the targeted database
+    #[cfg_attr(feature = "tracing", tracing::instrument(name = "canyon.migrations"))]
     pub async fn migrate() {
         for datasource in DATASOURCES.iter() {
             if datasource
@@ -34,12 +35,24 @@ impl Migrations {
                 .filter(|status| !status.eq(&MigrationsStatus::Disabled))
                 .is_none()
             {
+                #[cfg(feature = "tracing")]
+                tracing::info!(
+                    datasource = datasource.name,
+                    "Skipped datasource for being disabled (or not configured)"
+                );
+                #[cfg(not(feature = "tracing"))]
                 println!(
                     "Skipped datasource: {:?} for being disabled (or not configured)",
                     datasource.name
                 );
                 continue;
             }
+            #[cfg(feature = "tracing")]
+            tracing::info!(
+                datasource = datasource.name,
+                "Processing migrations for datasource"
+            );
+            #[cfg(not(feature = "tracing"))]
             println!(
                 "Processing migrations for datasource: {:?}",
                 datasource.name
@@ -1,5 +1,5 @@
-///! File that contains all the datatypes and logic to perform the migrations
-///! over a target database
+//! File that contains all the datatypes and logic to perform the migrations
+//! over a target database
 use async_trait::async_trait;
 use canyon_crud::DatabaseType;
 use regex::Regex;
@@ -23,6 +23,10 @@ pub struct MigrationsProcessor {
     set_primary_key_operations: Vec<Box<dyn DatabaseOperation>>,
     drop_primary_key_operations: Vec<Box<dyn DatabaseOperation>>,
     constraints_operations: Vec<Box<dyn DatabaseOperation>>,
+    // Flags that the primary key of the entity being processed must be (re)created
+    primary_key_changed: bool,
+    // The primary key constraints already scheduled to be dropped
+    dropped_primary_keys: Vec<String>,
 }
 impl Transaction<Self> for MigrationsProcessor {}
 
@@ -40,6 +44,13 @@ impl MigrationsProcessor {
         for canyon_register_entity in canyon_entities {
             // TODO Check if its disabled for the current datasource
             let entity_name = canyon_register_entity.entity_name.to_lowercase();
+            let has_composite_pk = canyon_register_entity.has_composite_primary_key();
+            let primary_key_fields = canyon_register_entity
+                .entity_fields
+                .iter()
+                .filter(|field| field.is_primary_key())
+                .cloned()
+                .collect::<Vec<CanyonRegisterEntityField>>();
 
             // 1st operation ->
             self.create_or_rename_tables(
@@ -87,18 +98,34 @@ impl MigrationsProcessor {
                     && !canyon_register_field.annotations.is_empty())
                     || (current_table_metadata.is_some() && current_column_metadata.is_none())
                 {
-                    self.add_constraints(entity_name.as_str(), canyon_register_field.clone())
+                    self.add_constraints(
+                        entity_name.as_str(),
+                        canyon_register_field.clone(),
+                        has_composite_pk,
+                    )
                 }
 
                 // Case when we need to compare the entity with the database contain
-                if current_table_metadata.is_some() && current_column_metadata.is_some() {
+                if let (Some(_), Some(column_metadata)) =
+                    (current_table_metadata, current_column_metadata)
+                {
                     self.add_modify_or_remove_constraints(
                         entity_name.as_str(),
                         canyon_register_field,
-                        current_column_metadata.unwrap(),
+                        column_metadata,
+                        has_composite_pk,
                     )
                 }
             }
+
+            // The primary key is set once per entity, with all the fields that compose it
+            if self.primary_key_changed {
+                self.set_primary_key(
+                    entity_name.as_str(),
+                    primary_key_fields,
+                    current_table_metadata,
+                );
+            }
         }
 
         for operation in &self.operations {
@@ -210,15 +237,15 @@ impl MigrationsProcessor {
     ) {
         // If we do not retrieve data for this database column, it does not exist yet
         // and therefore it has to be created
-        if current_column_metadata.is_none() {
-            self.create_column(
-                entity_name.to_string(),
-                canyon_register_entity_field.clone(),
-            )
-        } else if !MigrationsHelper::is_same_datatype(
+        let Some(column_metadata) = current_column_metadata else {
+            self.create_column(entity_name.to_string(), canyon_register_entity_field);
+            return;
+        };
+
+        if !MigrationsHelper::is_same_datatype(
             db_type,
             &canyon_register_entity_field,
-            current_column_metadata.unwrap(),
+            column_metadata,
         ) {
             self.change_column_datatype(
                 entity_name.to_string(),
@@ -226,13 +253,11 @@ impl MigrationsProcessor {
             )
         }
 
-        if let Some(column_metadata) = current_column_metadata {
-            if canyon_register_entity_field.is_nullable() != column_metadata.is_nullable {
-                if column_metadata.is_nullable {
-                    self.set_not_null(entity_name.to_string(), canyon_register_entity_field)
-                } else {
-                    self.drop_not_null(entity_name.to_string(), canyon_register_entity_field)
-                }
+        if canyon_register_entity_field.is_nullable() != column_metadata.is_nullable {
+            if column_metadata.is_nullable {
+                self.set_not_null(entity_name.to_string(), canyon_register_entity_field)
+            } else {
+                self.drop_not_null(entity_name.to_string(), canyon_register_entity_field)
             }
         }
     }
@@ -288,6 +313,7 @@ impl MigrationsProcessor {
         &mut self,
         entity_name: &str,
         canyon_register_entity_field: CanyonRegisterEntityField,
+        has_composite_pk: bool,
     ) {
         for attr in &canyon_register_entity_field.annotations {
             if attr.starts_with("Annotation: ForeignKey") {
@@ -313,13 +339,29 @@ impl MigrationsProcessor {
                 );
             }
             if attr.starts_with("Annotation: PrimaryKey") {
-                Self::add_primary_key(self, entity_name, canyon_register_entity_field.clone());
+                self.primary_key_changed = true;
 
-                if canyon_register_entity_field.is_autoincremental() {
+                // Composite primary keys are never autoincremental
+                if canyon_register_entity_field.is_autoincremental() && !has_composite_pk {
                     Self::add_identity(self, entity_name, canyon_register_entity_field.clone());
                 }
             }
         }
+
+        // The rules of every `#[validate]` annotation of the field make up a single
+        // constraint. It's only created along with the table or the column, since the
+        // `CHECK` constraints already on the database aren't compared with the entity
+        if canyon_register_entity_field.has_validations() {
+            self.constraints_operations
+                .push(Box::new(TableOperation::AddTableCheck(
+                    entity_name.to_string(),
+                    format!(
+                        "{entity_name}_{}_check",
+                        &canyon_register_entity_field.field_name
+                    ),
+                    canyon_register_entity_field,
+                )));
+        }
     }
 
     fn add_foreign_key(
@@ -340,16 +382,36 @@ impl MigrationsProcessor {
             )));
     }
 
-    fn add_primary_key(
+    /// Schedules the creation of the primary key of an entity, made up by all the fields
+    /// annotated with `#[primary_key]`, so composite keys are created as a single constraint.
+    ///
+    /// If the table already has a primary key constraint (p.e. when a new field is added
+    /// to a composite key), it's dropped first
+    fn set_primary_key(
         &mut self,
         entity_name: &str,
-        canyon_register_entity_field: CanyonRegisterEntityField,
+        primary_key_fields: Vec<CanyonRegisterEntityField>,
+        current_table_metadata: Option<&TableMetadata>,
     ) {
-        self.set_primary_key_operations
-            .push(Box::new(TableOperation::AddTablePrimaryKey(
-                entity_name.to_string(),
-                canyon_register_entity_field,
-            )));
+        self.primary_key_changed = false;
+
+        let current_primary_key_name = current_table_metadata.and_then(|table| {
+            table
+                .columns
+                .iter()
+                .find_map(|column| column.primary_key_name.clone())
+        });
+        if let Some(primary_key_name) = current_primary_key_name {
+            self.drop_primary_key(entity_name, primary_key_name);
+        }
+
+        if !primary_key_fields.is_empty() {
+            self.set_primary_key_operations
+                .push(Box::new(TableOperation::AddTablePrimaryKey(
+                    entity_name.to_string(),
+                    primary_key_fields,
+                )));
+        }
     }
 
     fn add_identity(&mut self, entity_name: &str, field: CanyonRegisterEntityField) {
@@ -371,11 +433,9 @@ impl MigrationsProcessor {
         entity_name: &str,
         canyon_register_entity_field: CanyonRegisterEntityField,
         current_column_metadata: &ColumnMetadata,
+        has_composite_pk: bool,
     ) {
-        let field_is_primary_key = canyon_register_entity_field
-            .annotations
-            .iter()
-            .any(|anno| anno.starts_with("Annotation: PrimaryKey"));
+        let field_is_primary_key = canyon_register_entity_field.is_primary_key();
 
         let field_is_foreign_key = canyon_register_entity_field
             .annotations
@@ -385,15 +445,16 @@ impl MigrationsProcessor {
         // ------------ PRIMARY KEY ---------------
         // Case when field contains a primary key annotation, and it's not already on database, add it to constrains_operations
         if field_is_primary_key && current_column_metadata.primary_key_info.is_none() {
-            Self::add_primary_key(self, entity_name, canyon_register_entity_field.clone());
+            self.primary_key_changed = true;
 
-            if canyon_register_entity_field.is_autoincremental() {
+            if canyon_register_entity_field.is_autoincremental() && !has_composite_pk {
                 Self::add_identity(self, entity_name, canyon_register_entity_field.clone());
             }
         }
         // Case when the field contains a primary key annotation, and it's already on the database
         else if field_is_primary_key && current_column_metadata.primary_key_info.is_some() {
-            let is_autoincr_rust = canyon_register_entity_field.is_autoincremental();
+            let is_autoincr_rust =
+                canyon_register_entity_field.is_autoincremental() && !has_composite_pk;
             let is_autoincr_in_db = current_column_metadata.is_identity;
 
             if !is_autoincr_rust && is_autoincr_in_db {
@@ -404,6 +465,9 @@ impl MigrationsProcessor {
         }
         // Case when field doesn't contains a primary key annotation, but there is one in the database column
         else if !field_is_primary_key && current_column_metadata.primary_key_info.is_some() {
+            // The remaining fields of a composite key (if any) must be set again as primary key
+            self.primary_key_changed = true;
+
             Self::drop_primary_key(
                 self,
                 entity_name,
@@ -506,21 +570,23 @@ impl MigrationsProcessor {
                     &canyon_register_entity_field,
                 )
             }
-        } else if !field_is_foreign_key && current_column_metadata.foreign_key_name.is_some() {
+        } else if let (false, Some(foreign_key_name)) = (
+            field_is_foreign_key,
+            &current_column_metadata.foreign_key_name,
+        ) {
             // Case when field don't contains a foreign key annotation, but there is already one in the database column
-            Self::delete_foreign_key(
-                self,
-                entity_name,
-                current_column_metadata
-                    .foreign_key_name
-                    .as_ref()
-                    .expect("ForeignKey constrain name not found")
-                    .to_string(),
-            );
+            Self::delete_foreign_key(self, entity_name, foreign_key_name.to_string());
         }
     }
 
     fn drop_primary_key(&mut self, entity_name: &str, primary_key_name: String) {
+        // Every column of a composite key references the same constraint
+        let constraint = format!("{entity_name}.{primary_key_name}");
+        if self.dropped_primary_keys.contains(&constraint) {
+            return;
+        }
+        self.dropped_primary_keys.push(constraint);
+
         self.drop_primary_key_operations
             .push(Box::new(TableOperation::DeleteTablePrimaryKey(
                 entity_name.to_string(),
@@ -551,11 +617,30 @@ impl MigrationsProcessor {
 
     /// Make the detected migrations for the next Canyon-SQL run
     #[allow(clippy::await_holding_lock)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "canyon.migrations.apply", skip_all)
+    )]
     pub async fn from_query_register(queries_to_execute: &HashMap<&str, Vec<&str>>) {
         for datasource in queries_to_execute.iter() {
             for query_to_execute in datasource.1 {
                 let res = Self::query(query_to_execute, [], datasource.0).await;
 
+                #[cfg(feature = "tracing")]
+                match res {
+                    Ok(_) => tracing::info!(
+                        datasource = datasource.0,
+                        statement = query_to_execute,
+                        "Migration applied"
+                    ),
+                    Err(e) => tracing::error!(
+                        datasource = datasource.0,
+                        statement = query_to_execute,
+                        error = ?e,
+                        "Migration failed"
+                    ),
+                }
+                #[cfg(not(feature = "tracing"))]
                 match res {
                     Ok(_) => println!(
                         "\t[OK] - {:?} - Query: {:?}",
@@ -622,7 +707,7 @@ impl MigrationsHelper {
 
     fn get_datatype_from_column_metadata(current_column_metadata: &ColumnMetadata) -> String {
         // TODO Add all SQL Server text datatypes
-        if vec!["nvarchar", "varchar"]
+        if ["nvarchar", "varchar"]
             .contains(&current_column_metadata.datatype.to_lowercase().as_str())
         {
             let varchar_len = match &current_column_metadata.character_maximum_length {
@@ -676,7 +761,7 @@ impl MigrationsHelper {
                 .collect::<Vec<String>>();
 
             let table_to_reference = annotation_data
-                .get(0)
+                .first()
                 .expect("Error extracting table ref from FK annotation")
                 .to_string();
             let column_to_reference = annotation_data
@@ -738,10 +823,12 @@ enum TableOperation {
     AddTableForeignKey(String, String, String, String, String),
     // table_with_foreign_key, constraint_name
     DeleteTableForeignKey(String, String),
-    // table_name, entity_field, column_name
-    AddTablePrimaryKey(String, CanyonRegisterEntityField),
+    // table_name, entity_fields (the ones that compose the primary key)
+    AddTablePrimaryKey(String, Vec<CanyonRegisterEntityField>),
     // table_name, constraint_name
     DeleteTablePrimaryKey(String, String),
+    // table_name, constraint_name, entity_field (with the `#[validate]` annotations)
+    AddTableCheck(String, String, CanyonRegisterEntityField),
 }
 
 impl<T: Debug> Transaction<T> for TableOperation {}
@@ -843,14 +930,16 @@ impl DatabaseOperation for TableOperation {
                 }
             }
 
-            TableOperation::AddTablePrimaryKey(table_name, entity_field) => {
-                if db_type == DatabaseType::PostgreSql {
+            TableOperation::AddTablePrimaryKey(table_name, entity_fields) => {
+                if db_type == DatabaseType::PostgreSql || db_type == DatabaseType::SqlServer {
                     format!(
-                        "ALTER TABLE {table_name} ADD PRIMARY KEY (\"{}\");",
-                        entity_field.field_name
+                        "ALTER TABLE {table_name} ADD PRIMARY KEY ({});",
+                        entity_fields
+                            .iter()
+                            .map(|entity_field| format!("\"{}\"", entity_field.field_name))
+                            .collect::<Vec<String>>()
+                            .join(", ")
                     )
-                } else if db_type == DatabaseType::SqlServer {
-                    todo!("[MS-SQL -> Operation still won't supported by Canyon for Sql Server]")
                 } else {
                     todo!()
                 }
@@ -863,6 +952,16 @@ impl DatabaseOperation for TableOperation {
                     todo!()
                 }
             }
+
+            TableOperation::AddTableCheck(table_name, check_name, entity_field) => {
+                match entity_field.check_constraint(db_type) {
+                    Some(predicate) => format!(
+                        "ALTER TABLE {table_name} ADD CONSTRAINT {check_name} CHECK ({predicate});"
+                    ),
+                    // None of the rules of the field could be checked by this database
+                    None => return,
+                }
+            }
         };
 
         if QUERIES_TO_EXECUTE
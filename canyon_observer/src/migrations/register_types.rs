@@ -1,10 +1,10 @@
+//! This file contains `Rust` types that represents an entry on the `CanyonRegister`
+//! where `Canyon` tracks the user types that has to manage
+use canyon_crud::{query_elements::dialect::dialect, DatabaseType};
 use regex::Regex;
 
 use crate::constants::{postgresql_type, regex_patterns, rust_type, sqlserver_type};
 
-/// This file contains `Rust` types that represents an entry on the `CanyonRegister`
-/// where `Canyon` tracks the user types that has to manage
-
 /// Gets the necessary identifiers of a CanyonEntity to make it the comparative
 /// against the database schemas
 #[derive(Debug, Clone, Default)]
@@ -15,6 +15,18 @@ pub struct CanyonRegisterEntity<'a> {
     pub entity_fields: Vec<CanyonRegisterEntityField>,
 }
 
+impl<'a> CanyonRegisterEntity<'a> {
+    /// Return if the entity has more than one field annotated as `#[primary_key]`,
+    /// which means that the primary key of the table is a composite one
+    pub fn has_composite_primary_key(&self) -> bool {
+        self.entity_fields
+            .iter()
+            .filter(|field| field.is_primary_key())
+            .count()
+            > 1
+    }
+}
+
 /// Complementary type for a field that represents a struct field that maps
 /// some real database column data
 #[derive(Debug, Clone, Default)]
@@ -36,24 +48,40 @@ impl CanyonRegisterEntityField {
             rust_type::OPT_I8 | rust_type::OPT_U8 => String::from(postgresql_type::INTEGER),
 
             rust_type::I16 | rust_type::U16 => {
-                String::from(&format!("{} NOT NULL", postgresql_type::INTEGER))
+                format!(
+                    "{} NOT NULL{}",
+                    postgresql_type::INTEGER,
+                    self.version_default()
+                )
             }
             rust_type::OPT_I16 | rust_type::OPT_U16 => String::from(postgresql_type::INTEGER),
 
             rust_type::I32 | rust_type::U32 => {
-                String::from(&format!("{} NOT NULL", postgresql_type::INTEGER))
+                format!(
+                    "{} NOT NULL{}",
+                    postgresql_type::INTEGER,
+                    self.version_default()
+                )
             }
             rust_type::OPT_I32 | rust_type::OPT_U32 => String::from(postgresql_type::INTEGER),
 
             rust_type::I64 | rust_type::U64 => {
-                String::from(&format!("{} NOT NULL", postgresql_type::BIGINT))
+                format!(
+                    "{} NOT NULL{}",
+                    postgresql_type::BIGINT,
+                    self.version_default()
+                )
             }
             rust_type::OPT_I64 | rust_type::OPT_U64 => String::from(postgresql_type::BIGINT),
 
             rust_type::STRING => String::from(&format!("{} NOT NULL", postgresql_type::TEXT)),
             rust_type::OPT_STRING => String::from(postgresql_type::TEXT),
 
-            rust_type::BOOL => String::from(&format!("{} NOT NULL", postgresql_type::BOOLEAN)),
+            rust_type::BOOL => format!(
+                "{} NOT NULL{}",
+                postgresql_type::BOOLEAN,
+                self.soft_delete_default(DatabaseType::PostgreSql)
+            ),
             rust_type::OPT_BOOL => String::from(postgresql_type::BOOLEAN),
 
             rust_type::NAIVE_DATE => String::from(&format!("{} NOT NULL", postgresql_type::DATE)),
@@ -62,10 +90,16 @@ impl CanyonRegisterEntityField {
             rust_type::NAIVE_TIME => String::from(&format!("{} NOT NULL", postgresql_type::TIME)),
             rust_type::OPT_NAIVE_TIME => String::from(postgresql_type::TIME),
 
-            rust_type::NAIVE_DATE_TIME => {
-                String::from(&format!("{} NOT NULL", postgresql_type::DATETIME))
-            }
-            rust_type::OPT_NAIVE_DATE_TIME => String::from(postgresql_type::DATETIME),
+            rust_type::NAIVE_DATE_TIME => format!(
+                "{} NOT NULL{}",
+                postgresql_type::DATETIME,
+                self.timestamp_default(DatabaseType::PostgreSql)
+            ),
+            rust_type::OPT_NAIVE_DATE_TIME => format!(
+                "{}{}",
+                postgresql_type::DATETIME,
+                self.timestamp_default(DatabaseType::PostgreSql)
+            ),
             &_ => todo!("Not supported datatype for this migrations version"),
         }
     }
@@ -82,17 +116,21 @@ impl CanyonRegisterEntityField {
             rust_type::OPT_I8 | rust_type::OPT_U8 => String::from(sqlserver_type::INT),
 
             rust_type::I16 | rust_type::U16 => {
-                String::from(&format!("{} NOT NULL", sqlserver_type::INT))
+                format!("{} NOT NULL{}", sqlserver_type::INT, self.version_default())
             }
             rust_type::OPT_I16 | rust_type::OPT_U16 => String::from(sqlserver_type::INT),
 
             rust_type::I32 | rust_type::U32 => {
-                String::from(&format!("{} NOT NULL", sqlserver_type::INT))
+                format!("{} NOT NULL{}", sqlserver_type::INT, self.version_default())
             }
             rust_type::OPT_I32 | rust_type::OPT_U32 => String::from(sqlserver_type::INT),
 
             rust_type::I64 | rust_type::U64 => {
-                String::from(&format!("{} NOT NULL", sqlserver_type::BIGINT))
+                format!(
+                    "{} NOT NULL{}",
+                    sqlserver_type::BIGINT,
+                    self.version_default()
+                )
             }
             rust_type::OPT_I64 | rust_type::OPT_U64 => String::from(sqlserver_type::BIGINT),
 
@@ -101,7 +139,11 @@ impl CanyonRegisterEntityField {
             }
             rust_type::OPT_STRING => String::from(sqlserver_type::NVARCHAR),
 
-            rust_type::BOOL => String::from(&format!("{} NOT NULL", sqlserver_type::BIT)),
+            rust_type::BOOL => format!(
+                "{} NOT NULL{}",
+                sqlserver_type::BIT,
+                self.soft_delete_default(DatabaseType::SqlServer)
+            ),
             rust_type::OPT_BOOL => String::from(sqlserver_type::BIT),
 
             rust_type::NAIVE_DATE => String::from(&format!("{} NOT NULL", sqlserver_type::DATE)),
@@ -110,10 +152,16 @@ impl CanyonRegisterEntityField {
             rust_type::NAIVE_TIME => String::from(&format!("{} NOT NULL", sqlserver_type::TIME)),
             rust_type::OPT_NAIVE_TIME => String::from(sqlserver_type::TIME),
 
-            rust_type::NAIVE_DATE_TIME => {
-                String::from(&format!("{} NOT NULL", sqlserver_type::DATETIME))
-            }
-            rust_type::OPT_NAIVE_DATE_TIME => String::from(sqlserver_type::DATETIME),
+            rust_type::NAIVE_DATE_TIME => format!(
+                "{} NOT NULL{}",
+                sqlserver_type::DATETIME,
+                self.timestamp_default(DatabaseType::SqlServer)
+            ),
+            rust_type::OPT_NAIVE_DATE_TIME => format!(
+                "{}{}",
+                sqlserver_type::DATETIME,
+                self.timestamp_default(DatabaseType::SqlServer)
+            ),
             &_ => todo!("Not supported datatype for this migrations version"),
         }
     }
@@ -211,7 +259,7 @@ impl CanyonRegisterEntityField {
             None => false,
         };
 
-        let numeric = vec!["i16", "i32", "i64"];
+        let numeric = ["i16", "i32", "i64"];
 
         let postgres_datatype_syntax = Self::to_postgres_syntax(self);
 
@@ -235,7 +283,7 @@ impl CanyonRegisterEntityField {
             None => false,
         };
 
-        let numeric = vec!["i16", "i32", "i64"];
+        let numeric = ["i16", "i32", "i64"];
 
         let sqlserver_datatype_syntax = Self::to_sqlserver_syntax(self);
 
@@ -246,6 +294,13 @@ impl CanyonRegisterEntityField {
         }
     }
 
+    /// Return if the field is annotated as (part of) the primary key of the entity
+    pub fn is_primary_key(&self) -> bool {
+        self.annotations
+            .iter()
+            .any(|a| a.starts_with("Annotation: PrimaryKey"))
+    }
+
     /// Return if the field is autoincremental
     pub fn is_autoincremental(&self) -> bool {
         let has_pk_annotation = self
@@ -258,7 +313,7 @@ impl CanyonRegisterEntityField {
             None => false,
         };
 
-        let numeric = vec!["i16", "i32", "i64"];
+        let numeric = ["i16", "i32", "i64"];
 
         numeric.contains(&self.field_type.as_str()) && pk_is_autoincremental
     }
@@ -267,4 +322,208 @@ impl CanyonRegisterEntityField {
     pub fn is_nullable(&self) -> bool {
         self.field_type.to_uppercase().starts_with("OPTION")
     }
+
+    /// Return the `DEFAULT` clause of the columns of the `#[created_at]` and `#[updated_at]`
+    /// fields, with the current time of the database, or an empty string for the other ones
+    fn timestamp_default(&self, db_type: DatabaseType) -> String {
+        if self.annotations.iter().any(|a| {
+            a.starts_with("Annotation: CreatedAt") || a.starts_with("Annotation: UpdatedAt")
+        }) {
+            format!(" DEFAULT {}", dialect(db_type).current_timestamp())
+        } else {
+            String::new()
+        }
+    }
+
+    /// Return the `DEFAULT` clause of the `bool` columns of the `#[soft_delete]` fields,
+    /// that aren't deleted when they're created, or an empty string for the other ones
+    fn soft_delete_default(&self, db_type: DatabaseType) -> String {
+        if self
+            .annotations
+            .iter()
+            .any(|a| a.starts_with("Annotation: SoftDelete"))
+        {
+            format!(" DEFAULT {}", dialect(db_type).boolean(false))
+        } else {
+            String::new()
+        }
+    }
+
+    /// Return the `DEFAULT` clause of the integer columns of the `#[version]` fields,
+    /// that starts at zero, or an empty string for the other ones
+    fn version_default(&self) -> String {
+        if self
+            .annotations
+            .iter()
+            .any(|a| a.starts_with("Annotation: Version"))
+        {
+            String::from(" DEFAULT 0")
+        } else {
+            String::new()
+        }
+    }
+
+    /// Return if the field has any `#[validate]` annotation
+    pub fn has_validations(&self) -> bool {
+        self.annotations
+            .iter()
+            .any(|a| a.starts_with("Annotation: Validate"))
+    }
+
+    /// Return the predicate of the `CHECK` constraint made up by the `length` and `range`
+    /// rules of the `#[validate]` annotations of the field, or `None` if the field has no
+    /// rules that the database could check.
+    ///
+    /// The `regex` rules are left out, since the patterns of the validations are written
+    /// with the syntax of the `regex` crate, that no database understands as is.
+    ///
+    /// On `SqlServer`, the length is measured with `DATALENGTH(column) / 2` instead of
+    /// `LEN`, because `LEN` ignores the trailing spaces. That's the number of UTF-16 code
+    /// units of the `NVARCHAR` value, so every character outside of the basic multilingual
+    /// plane counts twice, while the `length` validation counts it once
+    pub fn check_constraint(&self, db_type: DatabaseType) -> Option<String> {
+        let column = format!("\"{}\"", self.field_name);
+        let length = match db_type {
+            DatabaseType::PostgreSql => format!("char_length({column})"),
+            DatabaseType::SqlServer => format!("DATALENGTH({column}) / 2"),
+        };
+
+        let mut predicates = Vec::new();
+        for annotation in self
+            .annotations
+            .iter()
+            .filter_map(|a| a.strip_prefix("Annotation: Validate"))
+        {
+            let bounds = annotation
+                .split_once(", Regex: ")
+                .map_or(annotation, |(bounds, _)| bounds);
+
+            for bound in bounds.split(", ").filter_map(|b| b.split_once(": ")) {
+                match bound {
+                    ("MinLength", value) => predicates.push(format!("{length} >= {value}")),
+                    ("MaxLength", value) => predicates.push(format!("{length} <= {value}")),
+                    ("Min", value) => predicates.push(format!("{column} >= {value}")),
+                    ("Max", value) => predicates.push(format!("{column} <= {value}")),
+                    _ => {}
+                }
+            }
+        }
+
+        (!predicates.is_empty()).then(|| predicates.join(" AND "))
+    }
+}
+
+#[cfg(test)]
+mod annotations {
+    use super::*;
+    use crate::manager::field_annotation::EntityFieldAnnotation;
+
+    fn field(attributes: &[syn::Attribute]) -> CanyonRegisterEntityField {
+        CanyonRegisterEntityField {
+            field_name: String::from("slug"),
+            field_type: String::from("String"),
+            annotations: attributes
+                .iter()
+                .map(|attr| {
+                    EntityFieldAnnotation::try_from(&attr)
+                        .unwrap()
+                        .get_as_string()
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn creates_the_predicates_of_the_validation_rules() {
+        let slug = field(&[
+            syn::parse_quote!(#[validate(length(min = 1, max = 64), email)]),
+            syn::parse_quote!(#[validate(regex = "^[a-z0-9-]+, 'lec'$")]),
+        ]);
+
+        assert!(slug.has_validations());
+        assert_eq!(
+            slug.check_constraint(DatabaseType::PostgreSql).unwrap(),
+            "char_length(\"slug\") >= 1 AND char_length(\"slug\") <= 64"
+        );
+        assert_eq!(
+            slug.check_constraint(DatabaseType::SqlServer).unwrap(),
+            "DATALENGTH(\"slug\") / 2 >= 1 AND DATALENGTH(\"slug\") / 2 <= 64"
+        );
+
+        let rank = field(&[syn::parse_quote!(#[validate(range(min = -1.5, max = 100))])]);
+        assert_eq!(
+            rank.check_constraint(DatabaseType::SqlServer).unwrap(),
+            "\"slug\" >= -1.5 AND \"slug\" <= 100"
+        );
+
+        let pattern =
+            field(&[syn::parse_quote!(#[validate(regex = "^[a-z]+$", custom = "checks::slug")])]);
+        assert_eq!(pattern.check_constraint(DatabaseType::PostgreSql), None);
+        assert_eq!(pattern.check_constraint(DatabaseType::SqlServer), None);
+    }
+
+    #[test]
+    fn creates_the_timestamps_with_the_current_time_as_default() {
+        let mut updated_at = field(&[syn::parse_quote!(#[updated_at(source = "database")])]);
+        updated_at.field_type = String::from("NaiveDateTime");
+        assert_eq!(
+            updated_at.to_postgres_syntax(),
+            "timestamp without time zone NOT NULL DEFAULT (CURRENT_TIMESTAMP AT TIME ZONE 'UTC')"
+        );
+
+        let mut created_at = field(&[syn::parse_quote!(#[created_at])]);
+        created_at.field_type = String::from("Option<NaiveDateTime>");
+        assert_eq!(
+            created_at.to_sqlserver_syntax(),
+            "DATETIME2 DEFAULT SYSUTCDATETIME()"
+        );
+        assert_eq!(
+            created_at.annotations,
+            ["Annotation: CreatedAt, Source: Client"]
+        );
+    }
+
+    #[test]
+    fn creates_the_soft_delete_flags_as_alive() {
+        let mut deleted = field(&[syn::parse_quote!(#[soft_delete])]);
+        deleted.field_type = String::from("bool");
+        assert_eq!(
+            deleted.to_postgres_syntax(),
+            "boolean NOT NULL DEFAULT FALSE"
+        );
+        assert_eq!(deleted.to_sqlserver_syntax(), "BIT NOT NULL DEFAULT 0");
+
+        deleted.field_type = String::from("Option<NaiveDateTime>");
+        assert_eq!(deleted.to_sqlserver_syntax(), "DATETIME2");
+    }
+
+    #[test]
+    fn creates_the_versions_starting_at_zero() {
+        let mut version = field(&[syn::parse_quote!(#[version])]);
+        version.field_type = String::from("i64");
+        assert_eq!(version.to_postgres_syntax(), "bigint NOT NULL DEFAULT 0");
+        assert_eq!(version.to_sqlserver_syntax(), "BIGINT NOT NULL DEFAULT 0");
+
+        version.annotations.clear();
+        assert_eq!(version.to_sqlserver_syntax(), "BIGINT NOT NULL");
+    }
+
+    #[test]
+    fn rejects_the_malformed_rules() {
+        let malformed: [syn::Attribute; 10] = [
+            syn::parse_quote!(#[validate(length(min = -1))]),
+            syn::parse_quote!(#[validate(regex = "^[a-z")]),
+            syn::parse_quote!(#[validate(email, email)]),
+            syn::parse_quote!(#[validate(size(max = 3))]),
+            syn::parse_quote!(#[created_at(source = "server")]),
+            syn::parse_quote!(#[updated_at(source = database)]),
+            syn::parse_quote!(#[updated_at(source)]),
+            syn::parse_quote!(#[created_at(a::b = "client")]),
+            syn::parse_quote!(#[soft_delete(column = "deleted")]),
+            syn::parse_quote!(#[version(start = 1)]),
+        ];
+        for attr in &malformed {
+            assert!(EntityFieldAnnotation::try_from(&attr).is_err());
+        }
+    }
 }
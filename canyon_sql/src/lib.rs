@@ -1,8 +1,8 @@
-///! The root crate of the `Canyon-SQL` project.
-///
-/// Here it's where all the available functionalities and features
-/// reaches the top most level, grouping them and making them visible
-/// through this crate, building the *public API* of the library
+//! The root crate of the `Canyon-SQL` project.
+//!
+//! Here it's where all the available functionalities and features
+//! reaches the top most level, grouping them and making them visible
+//! through this crate, building the *public API* of the library
 
 /// Reexported elements to the root of the public API
 pub mod migrations {
@@ -19,19 +19,47 @@ pub mod macros {
     pub use canyon_macros::*;
 }
 
+/// Free functions for launch raw SQL statements, mapping the results into any
+/// type deriving `CanyonMapper`
+pub use canyon_crud::raw::{execute, query_as, query_scalar};
+
 /// Crud module serves to reexport the public elements of the `canyon_crud` crate,
 /// exposing them through the public API
 pub mod crud {
     pub use canyon_crud::bounds;
     pub use canyon_crud::crud::*;
+    pub use canyon_crud::hooks::{self, CanyonHooks};
+    pub use canyon_crud::locking::{self, VersionConflict};
     pub use canyon_crud::mapper::*;
+    pub use canyon_crud::relations::*;
     pub use canyon_crud::result::*;
+    pub use canyon_crud::soft_delete::{self, SoftDelete, SoftDeleteScope};
+    pub use canyon_crud::timestamps::{self, TimestampSource};
+    pub use canyon_crud::tracking::{self, changed_columns};
+    pub use canyon_crud::validation::{self, FieldError, Validate, ValidationErrors};
     pub use canyon_crud::DatabaseType;
 }
 
+/// Middlewares that inspects and rewrites the statements before they're
+/// launched, and observes their outcome afterwards
+pub mod interceptor {
+    pub use canyon_crud::interceptor::*;
+}
+
+/// The reports of the statements that exceeds the `slow_query_threshold_ms`
+/// of their datasource
+pub mod slow_query {
+    pub use canyon_crud::slow_query::*;
+}
+
 /// Re-exports the query elements from the `crud`crate
 pub mod query {
+    pub use canyon_crud::explain::{PlanNode, QueryPlan};
+    pub use canyon_crud::query_elements::dialect;
+    pub use canyon_crud::query_elements::expr;
     pub use canyon_crud::query_elements::operators;
+    pub use canyon_crud::query_elements::parameters::{inline_params, NamedStatement};
+    pub use canyon_crud::query_elements::statement;
     pub use canyon_crud::query_elements::{query::*, query_builder::*};
 }
 
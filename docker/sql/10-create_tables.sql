@@ -38,10 +38,89 @@ CREATE TABLE public.team (
 	home_league			INTEGER REFERENCES league(id)
 );
 
+CREATE TABLE public.team_player (
+	team				INTEGER NOT NULL,
+	player				INTEGER NOT NULL,
+	role				TEXT NOT NULL,
+	PRIMARY KEY (team, player)
+);
+
+CREATE TABLE public.team_tournament (
+	team				INTEGER NOT NULL,
+	tournament			INTEGER NOT NULL,
+	PRIMARY KEY (team, tournament)
+);
+
+CREATE TABLE public.stream (
+	id					INTEGER PRIMARY KEY GENERATED ALWAYS AS IDENTITY,
+	channel				TEXT NOT NULL,
+	started_at			TIMESTAMP(0) NOT NULL
+);
+
+CREATE TABLE public.sponsor (
+	id					INTEGER PRIMARY KEY GENERATED ALWAYS AS IDENTITY,
+	slug				TEXT NOT NULL,
+	contract_start		DATE NOT NULL,
+	contract_end		DATE NOT NULL
+);
+
+CREATE TABLE public.caster (
+	id					INTEGER PRIMARY KEY GENERATED ALWAYS AS IDENTITY,
+	ext_id				BIGINT NOT NULL,
+	nickname			TEXT NOT NULL
+);
+
+CREATE TABLE public.article (
+	id					INTEGER PRIMARY KEY GENERATED ALWAYS AS IDENTITY,
+	title				TEXT NOT NULL,
+	created_at			TIMESTAMP NOT NULL DEFAULT (CURRENT_TIMESTAMP AT TIME ZONE 'UTC'),
+	updated_at			TIMESTAMP NOT NULL DEFAULT (CURRENT_TIMESTAMP AT TIME ZONE 'UTC')
+);
+
+CREATE TABLE public.venue (
+	id					INTEGER PRIMARY KEY GENERATED ALWAYS AS IDENTITY,
+	name				TEXT NOT NULL,
+	city				TEXT NOT NULL,
+	deleted_at			TIMESTAMP
+);
+
+CREATE TABLE public.event (
+	id					INTEGER PRIMARY KEY GENERATED ALWAYS AS IDENTITY,
+	name				TEXT NOT NULL,
+	venue				INTEGER REFERENCES venue(id)
+);
+
+CREATE TABLE public.bracket (
+	id					INTEGER PRIMARY KEY GENERATED ALWAYS AS IDENTITY,
+	name				TEXT NOT NULL,
+	rounds				INTEGER NOT NULL,
+	version				INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE TABLE public.standing (
+	id					INTEGER PRIMARY KEY GENERATED ALWAYS AS IDENTITY,
+	team				INTEGER NOT NULL,
+	wins				INTEGER NOT NULL,
+	losses				INTEGER NOT NULL,
+	created_at			TIMESTAMP NOT NULL DEFAULT (CURRENT_TIMESTAMP AT TIME ZONE 'UTC'),
+	updated_at			TIMESTAMP NOT NULL DEFAULT (CURRENT_TIMESTAMP AT TIME ZONE 'UTC'),
+	deleted_at			TIMESTAMP,
+	version				INTEGER NOT NULL DEFAULT 0
+);
+
 -- For now, we use for out CI process the default data for postgres instances
 
 -- ALTER TABLE public.league OWNER TO triforce;
 -- ALTER TABLE public.tournament OWNER TO triforce;
 -- ALTER TABLE public.player OWNER TO triforce;
 -- ALTER TABLE public.team OWNER TO triforce;
--- ALTER TABLE public.team_player OWNER TO triforce;
\ No newline at end of file
+-- ALTER TABLE public.team_player OWNER TO triforce;
+-- ALTER TABLE public.team_tournament OWNER TO triforce;
+-- ALTER TABLE public.stream OWNER TO triforce;
+-- ALTER TABLE public.sponsor OWNER TO triforce;
+-- ALTER TABLE public.caster OWNER TO triforce;
+-- ALTER TABLE public.article OWNER TO triforce;
+-- ALTER TABLE public.venue OWNER TO triforce;
+-- ALTER TABLE public.event OWNER TO triforce;
+-- ALTER TABLE public.bracket OWNER TO triforce;
+-- ALTER TABLE public.standing OWNER TO triforce;
\ No newline at end of file
@@ -85,6 +85,106 @@ BEGIN
         home_league		    INT REFERENCES league(id)
     );
 END;
+
+IF OBJECT_ID(N'[dbo].[team_player]', N'U') IS NULL
+BEGIN
+    CREATE TABLE dbo.team_player (
+        team				INT NOT NULL,
+        player				INT NOT NULL,
+        role				NVARCHAR(250) NOT NULL,
+        PRIMARY KEY (team, player)
+    );
+END;
+
+IF OBJECT_ID(N'[dbo].[team_tournament]', N'U') IS NULL
+BEGIN
+    CREATE TABLE dbo.team_tournament (
+        team				INT NOT NULL,
+        tournament			INT NOT NULL,
+        PRIMARY KEY (team, tournament)
+    );
+END;
+
+IF OBJECT_ID(N'[dbo].[stream]', N'U') IS NULL
+BEGIN
+    CREATE TABLE dbo.stream (
+        id					INT PRIMARY KEY IDENTITY,
+        channel				NVARCHAR(250) NOT NULL,
+        started_at			DATETIME2(0) NOT NULL
+    );
+END;
+
+IF OBJECT_ID(N'[dbo].[sponsor]', N'U') IS NULL
+BEGIN
+    CREATE TABLE dbo.sponsor (
+        id					INT PRIMARY KEY IDENTITY,
+        slug				NVARCHAR(250) NOT NULL,
+        contract_start		DATE NOT NULL,
+        contract_end		DATE NOT NULL
+    );
+END;
+
+IF OBJECT_ID(N'[dbo].[caster]', N'U') IS NULL
+BEGIN
+    CREATE TABLE dbo.caster (
+        id					INT PRIMARY KEY IDENTITY,
+        ext_id				BIGINT NOT NULL,
+        nickname			NVARCHAR(250) NOT NULL
+    );
+END;
+
+IF OBJECT_ID(N'[dbo].[article]', N'U') IS NULL
+BEGIN
+    CREATE TABLE dbo.article (
+        id					INT PRIMARY KEY IDENTITY,
+        title				NVARCHAR(250) NOT NULL,
+        created_at			DATETIME2 NOT NULL DEFAULT SYSUTCDATETIME(),
+        updated_at			DATETIME2 NOT NULL DEFAULT SYSUTCDATETIME()
+    );
+END;
+
+IF OBJECT_ID(N'[dbo].[venue]', N'U') IS NULL
+BEGIN
+    CREATE TABLE dbo.venue (
+        id					INT PRIMARY KEY IDENTITY,
+        name				NVARCHAR(250) NOT NULL,
+        city				NVARCHAR(250) NOT NULL,
+        deleted_at			DATETIME2
+    );
+END;
+
+IF OBJECT_ID(N'[dbo].[event]', N'U') IS NULL
+BEGIN
+    CREATE TABLE dbo.event (
+        id					INT PRIMARY KEY IDENTITY,
+        name				NVARCHAR(250) NOT NULL,
+        venue				INT REFERENCES venue(id)
+    );
+END;
+
+IF OBJECT_ID(N'[dbo].[bracket]', N'U') IS NULL
+BEGIN
+    CREATE TABLE dbo.bracket (
+        id					INT PRIMARY KEY IDENTITY,
+        name				NVARCHAR(250) NOT NULL,
+        rounds				INT NOT NULL,
+        version				INT NOT NULL DEFAULT 0
+    );
+END;
+
+IF OBJECT_ID(N'[dbo].[standing]', N'U') IS NULL
+BEGIN
+    CREATE TABLE dbo.standing (
+        id					INT PRIMARY KEY IDENTITY,
+        team				INT NOT NULL,
+        wins				INT NOT NULL,
+        losses				INT NOT NULL,
+        created_at			DATETIME2 NOT NULL DEFAULT SYSUTCDATETIME(),
+        updated_at			DATETIME2 NOT NULL DEFAULT SYSUTCDATETIME(),
+        deleted_at			DATETIME2,
+        version				INT NOT NULL DEFAULT 0
+    );
+END;
 ";
 
 pub const SQL_SERVER_FILL_TABLE_VALUES: &str = "
@@ -4,6 +4,7 @@ use canyon_sql::crud::CrudOperations;
 
 use crate::constants::{PSQL_DS, SQL_SERVER_DS};
 use crate::tests_models::league::*;
+use crate::tests_models::team_player::*;
 
 /// Deletes a row from the database that is mapped into some instance of a `T` entity.
 ///
@@ -102,3 +103,30 @@ fn test_crud_delete_datasource_method_operation() {
         None
     );
 }
+
+/// Deletes a record of an entity with a composite primary key, which
+/// is targeted by every field annotated with `#[primary_key]`
+#[canyon_sql::macros::canyon_tokio_test]
+fn test_crud_delete_composite_pk_method_operation() {
+    let mut new_team_player: TeamPlayer = TeamPlayer {
+        team: 1,
+        player: 3,
+        role: "mid".to_string(),
+    };
+    new_team_player
+        .insert()
+        .await
+        .expect("Failed insert operation");
+
+    new_team_player
+        .delete()
+        .await
+        .expect("Failed to delete the operation");
+
+    assert_eq!(
+        TeamPlayer::find_by_pk((1, 3))
+            .await
+            .expect("Unwrapping the result, letting the Option<T>"),
+        None
+    );
+}
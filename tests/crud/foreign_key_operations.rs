@@ -8,7 +8,7 @@
 ///! Names of the foreign key methods are autogenerated for the direct and
 ///! reverse side of the implementations.
 ///! For more info: TODO -> Link to the docs of the foreign key chapter
-use canyon_sql::crud::CrudOperations;
+use canyon_sql::crud::{CrudOperations, EagerLoading};
 
 use crate::constants::SQL_SERVER_DS;
 use crate::tests_models::league::*;
@@ -105,3 +105,87 @@ fn test_crud_search_reverse_side_foreign_key_datasource() {
         .iter()
         .for_each(|t| assert_eq!(t.league, some_league.id));
 }
+
+/// Loads the parent of every entity of a collection with a single query,
+/// instead of launching one `search_league()` per tournament
+#[canyon_sql::macros::canyon_tokio_test]
+fn test_crud_eager_loading_of_the_parents() {
+    let tournaments: Vec<(Tournament, Option<League>)> = Tournament::find_all()
+        .await
+        .expect("Result variant of the query is err")
+        .with::<League>()
+        .await
+        .expect("Result variant of the eager loading is err");
+
+    assert!(!tournaments.is_empty());
+    tournaments.iter().for_each(|(tournament, league)| {
+        let league = league
+            .as_ref()
+            .expect("Every tournament belongs to a league");
+        assert_eq!(tournament.league, league.id)
+    });
+}
+
+/// Same as the eager loading of the parents, but with the specified datasource
+#[canyon_sql::macros::canyon_tokio_test]
+fn test_crud_eager_loading_of_the_parents_datasource() {
+    let tournaments: Vec<(Tournament, Option<League>)> =
+        Tournament::find_all_datasource(SQL_SERVER_DS)
+            .await
+            .expect("Result variant of the query is err")
+            .with_datasource::<League>(SQL_SERVER_DS)
+            .await
+            .expect("Result variant of the eager loading is err");
+
+    assert!(!tournaments.is_empty());
+    tournaments.iter().for_each(|(tournament, league)| {
+        let league = league
+            .as_ref()
+            .expect("Every tournament belongs to a league");
+        assert_eq!(tournament.league, league.id)
+    });
+}
+
+/// Loads the childrens of every entity of a collection with a single query,
+/// being the eager version of the reverse side search of a foreign key
+#[canyon_sql::macros::canyon_tokio_test]
+fn test_crud_eager_loading_of_the_childrens() {
+    let leagues: Vec<(League, Vec<Tournament>)> = League::find_all()
+        .await
+        .expect("Result variant of the query is err")
+        .with_children::<Tournament>()
+        .await
+        .expect("Result variant of the eager loading is err");
+
+    let tournaments = Tournament::find_all_unchecked().await;
+    assert_eq!(
+        leagues
+            .iter()
+            .map(|(_, childs)| childs.len())
+            .sum::<usize>(),
+        tournaments.len()
+    );
+    leagues.iter().for_each(|(league, childs)| {
+        childs
+            .iter()
+            .for_each(|tournament| assert_eq!(tournament.league, league.id))
+    });
+}
+
+/// Same as the eager loading of the childrens, but with the specified datasource
+#[canyon_sql::macros::canyon_tokio_test]
+fn test_crud_eager_loading_of_the_childrens_datasource() {
+    let leagues: Vec<(League, Vec<Tournament>)> = League::find_all_datasource(SQL_SERVER_DS)
+        .await
+        .expect("Result variant of the query is err")
+        .with_children_datasource::<Tournament>(SQL_SERVER_DS)
+        .await
+        .expect("Result variant of the eager loading is err");
+
+    assert!(leagues.iter().any(|(_, childs)| !childs.is_empty()));
+    leagues.iter().for_each(|(league, childs)| {
+        childs
+            .iter()
+            .for_each(|tournament| assert_eq!(tournament.league, league.id))
+    });
+}
@@ -0,0 +1,57 @@
+//! Integration tests for the `CanyonHooks` invoked by the CRUD operations
+use canyon_sql::{crud::CrudOperations, date_time::NaiveDate};
+
+use crate::tests_models::sponsor::*;
+
+/// The `Sponsor` entity implements the `CanyonHooks`, normalizing its slug
+/// before the insert, and rejecting the updates that leaves its contract ending
+/// before it starts, that must not reach the database
+#[canyon_sql::macros::canyon_tokio_test]
+fn test_crud_hooks_operations() {
+    let mut sponsor = Sponsor {
+        id: Default::default(),
+        slug: "  Red Bull ".to_string(),
+        contract_start: NaiveDate::from_ymd_opt(2023, 1, 1).unwrap(),
+        contract_end: NaiveDate::from_ymd_opt(2023, 12, 31).unwrap(),
+    };
+
+    sponsor.insert().await.expect("Failed insert operation");
+    assert_eq!(sponsor.slug, "red-bull");
+    assert_eq!(
+        Sponsor::find_by_pk(&sponsor.id)
+            .await
+            .expect("Request error")
+            .expect("None value")
+            .slug,
+        "red-bull"
+    );
+
+    let mut rejected = sponsor.clone();
+    rejected.contract_end = NaiveDate::from_ymd_opt(2022, 12, 31).unwrap();
+    assert!(rejected.update().await.is_err());
+    assert_eq!(
+        Sponsor::find_by_pk(&sponsor.id)
+            .await
+            .expect("Request error")
+            .expect("None value"),
+        sponsor
+    );
+
+    // Every instance of a multi insert runs its own hooks
+    let mut first = sponsor.clone();
+    first.slug = " Secret Lab".to_string();
+    let mut second = sponsor.clone();
+    second.slug = "Logitech G ".to_string();
+    Sponsor::multi_insert(&mut [&mut first, &mut second])
+        .await
+        .expect("Failed multi insert operation");
+    assert_eq!(first.slug, "secret-lab");
+    assert_eq!(second.slug, "logitech-g");
+
+    for sponsor in [sponsor, first, second] {
+        sponsor
+            .delete()
+            .await
+            .expect("Failed to delete the operation");
+    }
+}
@@ -1,9 +1,12 @@
 ///! Integration tests for the CRUD operations available in `Canyon` that
 ///! generates and executes *INSERT* statements
 use canyon_sql::crud::CrudOperations;
+use canyon_sql::date_time::NaiveDate;
 
 use crate::constants::SQL_SERVER_DS;
 use crate::tests_models::league::*;
+use crate::tests_models::stream::*;
+use crate::tests_models::team_player::*;
 
 /// Inserts a new record on the database, given an entity that is
 /// annotated with `#[canyon_entity]` macro over a *T* type.
@@ -83,6 +86,65 @@ fn test_crud_insert_datasource_operation() {
     assert_eq!(new_league.id, inserted_league.id);
 }
 
+/// Inserts a new record on the database reading back *every* column of the new row,
+/// so the `started_at` of the `Stream`, rounded to whole seconds by the database, is
+/// reassigned to the instance along with its primary key
+#[canyon_sql::macros::canyon_tokio_test]
+fn test_crud_insert_returning_all_operation() {
+    let mut new_stream: Stream = Stream {
+        id: Default::default(),
+        channel: "lolesports".to_string(),
+        started_at: NaiveDate::from_ymd_opt(2023, 5, 14)
+            .unwrap()
+            .and_hms_milli_opt(18, 30, 0, 700)
+            .unwrap(),
+    };
+    let sent_started_at = new_stream.started_at;
+
+    new_stream
+        .insert_returning_all()
+        .await
+        .expect("Failed insert returning all operation");
+    assert_ne!(new_stream.started_at, sent_started_at);
+
+    let inserted_stream = Stream::find_by_pk(&new_stream.id)
+        .await
+        .expect("Failed the query to the database")
+        .expect("No entity found for the primary key value passed in");
+
+    assert_eq!(new_stream.started_at, inserted_stream.started_at);
+    assert_eq!(new_stream, inserted_stream);
+}
+
+/// Same as the insert returning all operation above, but targeting the database defined in
+/// the specified datasource
+#[canyon_sql::macros::canyon_tokio_test]
+fn test_crud_insert_returning_all_datasource_operation() {
+    let mut new_stream: Stream = Stream {
+        id: Default::default(),
+        channel: "lolesports".to_string(),
+        started_at: NaiveDate::from_ymd_opt(2023, 5, 14)
+            .unwrap()
+            .and_hms_milli_opt(18, 30, 0, 700)
+            .unwrap(),
+    };
+    let sent_started_at = new_stream.started_at;
+
+    new_stream
+        .insert_returning_all_datasource(SQL_SERVER_DS)
+        .await
+        .expect("Failed insert returning all datasource operation");
+    assert_ne!(new_stream.started_at, sent_started_at);
+
+    let inserted_stream = Stream::find_by_pk_datasource(&new_stream.id, SQL_SERVER_DS)
+        .await
+        .expect("Failed the query to the database")
+        .expect("No entity found for the primary key value passed in");
+
+    assert_eq!(new_stream.started_at, inserted_stream.started_at);
+    assert_eq!(new_stream, inserted_stream);
+}
+
 /// The multi insert operation is a shorthand for insert multiple instances of *T*
 /// in the database at once.
 ///
@@ -213,3 +275,27 @@ fn test_crud_multi_insert_datasource_operation() {
     assert_eq!(new_league_mi_2.id, inserted_league_2.id);
     assert_eq!(new_league_mi_3.id, inserted_league_3.id);
 }
+
+/// Inserts a new record on an entity with a composite primary key. The values
+/// of every `#[primary_key]` field are inserted, because they are never generated
+/// by the database, and they can be used as a tuple to find the record again
+#[canyon_sql::macros::canyon_tokio_test]
+fn test_crud_insert_composite_pk_operation() {
+    let mut new_team_player: TeamPlayer = TeamPlayer {
+        team: 1,
+        player: 1,
+        role: "top".to_string(),
+    };
+
+    new_team_player
+        .insert()
+        .await
+        .expect("Failed insert operation");
+
+    let inserted_team_player = TeamPlayer::find_by_pk(new_team_player.primary_key())
+        .await
+        .expect("Failed the query to the database")
+        .expect("No entity found for the primary key values passed in");
+
+    assert_eq!(new_team_player, inserted_team_player);
+}
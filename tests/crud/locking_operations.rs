@@ -0,0 +1,88 @@
+//! Integration tests for the optimistic locking of the `#[version]` annotation
+use canyon_sql::crud::{CrudOperations, DatabaseType, VersionConflict};
+
+use crate::tests_models::bracket::*;
+
+/// The writes of the `Bracket` only matches the row with the same version,
+/// and the updates moves it forward by one
+#[canyon_sql::macros::canyon_tokio_test]
+fn test_crud_locking_sql_inspection() {
+    let bracket = Bracket {
+        id: 1,
+        name: "Swiss stage".to_string(),
+        rounds: 5,
+        version: 3,
+    };
+
+    let (sql, params) = bracket
+        .update_sql(DatabaseType::PostgreSql)
+        .expect("Bracket has a primary key");
+    assert_eq!(
+        sql,
+        "UPDATE bracket SET \"name\" = $2, \"rounds\" = $3, \"version\" = \"version\" + 1 \
+        WHERE \"id\" = $1 AND \"version\" = $4"
+    );
+    assert_eq!(params.len(), 4);
+
+    let (sql, params) = bracket
+        .delete_sql(DatabaseType::PostgreSql)
+        .expect("Bracket has a primary key");
+    assert_eq!(
+        sql,
+        "DELETE FROM bracket WHERE \"id\" = $1 AND \"version\" = $2"
+    );
+    assert_eq!(params.len(), 2);
+}
+
+/// Two copies of the same bracket are modified concurrently, so only the
+/// first write succeeds, and the second one fails with a `VersionConflict`
+/// instead of overwriting it
+#[canyon_sql::macros::canyon_tokio_test]
+fn test_crud_locking_operations() {
+    let mut bracket = Bracket {
+        id: Default::default(),
+        name: "Swiss stage".to_string(),
+        rounds: 5,
+        version: 0,
+    };
+    bracket.insert().await.expect("Failed insert operation");
+
+    let mut first = Bracket::find_by_pk(&bracket.id)
+        .await
+        .expect("Request error")
+        .expect("None value");
+    let mut second = first.clone();
+
+    first.rounds = 6;
+    first.update().await.expect("Failed update operation");
+    assert_eq!(first.version, 1);
+
+    second.rounds = 4;
+    let error = second.update().await.unwrap_err();
+    let conflict = error
+        .downcast_ref::<VersionConflict>()
+        .expect("Expected a version conflict");
+    assert_eq!(conflict.version, 0);
+    assert!(second
+        .delete()
+        .await
+        .unwrap_err()
+        .downcast_ref::<VersionConflict>()
+        .is_some());
+
+    // The version of the first copy follows the row, so it can keep writing it
+    first.rounds = 7;
+    first.update().await.expect("Failed update operation");
+    assert_eq!(first.version, 2);
+
+    let current = Bracket::find_by_pk(&bracket.id)
+        .await
+        .expect("Request error")
+        .expect("None value");
+    assert_eq!(current, first);
+
+    current
+        .delete()
+        .await
+        .expect("Failed to delete the bracket");
+}
@@ -1,9 +1,17 @@
 pub mod delete_operations;
 pub mod foreign_key_operations;
+pub mod hooks_operations;
 pub mod insert_operations;
+pub mod locking_operations;
 pub mod querybuilder_operations;
+pub mod raw_operations;
+pub mod relation_operations;
 pub mod select_operations;
+pub mod soft_delete_operations;
+pub mod timestamps_operations;
+pub mod tracking_operations;
 pub mod update_operations;
+pub mod validation_operations;
 
 use crate::constants::SQL_SERVER_CREATE_TABLES;
 use crate::constants::SQL_SERVER_DS;
@@ -5,8 +5,13 @@
 ///! with the SQL filters
 ///
 use canyon_sql::{
-    crud::CrudOperations,
-    query::{operators::Comp, ops::QueryBuilder},
+    crud::{CrudOperations, DatabaseType},
+    macros::CanyonMapper,
+    query::{
+        expr::{not, Expr},
+        operators::{Aggregate, Comp, Condition, Nulls, Order},
+        ops::QueryBuilder,
+    },
 };
 
 use crate::constants::SQL_SERVER_DS;
@@ -32,7 +37,7 @@ fn test_generated_sql_by_the_select_querybuilder() {
     // generated SQL by the SelectQueryBuilder<T> is the spected
     assert_eq!(
         select_with_joins.read_sql(),
-        "SELECT * FROM league INNER JOIN tournament ON league.id = tournament.league_id LEFT JOIN team ON tournament.id = player.tournament_id WHERE id > $1 AND name = $2  AND name IN ($2, $3) "
+        "SELECT * FROM league INNER JOIN tournament ON league.id = tournament.league_id LEFT JOIN team ON tournament.id = player.tournament_id WHERE id > $1 AND name = $2 AND name IN ($3, $4)"
     )
 }
 
@@ -169,6 +174,21 @@ fn test_crud_delete_with_querybuilder_datasource() {
         .is_empty());
 }
 
+/// The builders of an undeclared datasource are built without panicking,
+/// and their queries fails when they're launched
+#[canyon_sql::macros::canyon_tokio_test]
+fn test_querybuilder_for_an_unknown_datasource() {
+    let mut players = Player::select_query_datasource("not_declared");
+    players.r#where(PlayerFieldValue::id(&1), Comp::Eq);
+
+    assert_eq!(players.read_sql(), "SELECT * FROM player WHERE id = $1");
+    assert!(Player::select_query_datasource("not_declared")
+        .r#where(PlayerFieldValue::id(&1), Comp::Eq)
+        .query()
+        .await
+        .is_err());
+}
+
 /// Tests for the generated SQL query after use the
 /// WHERE clause
 #[canyon_sql::macros::canyon_tokio_test]
@@ -203,7 +223,7 @@ fn test_and_clause_with_in_constraint() {
 
     assert_eq!(
         l.read_sql().trim(),
-        "SELECT * FROM league WHERE name = $1 AND id IN ($1, $2, $3)"
+        "SELECT * FROM league WHERE name = $1 AND id IN ($2, $3, $4)"
     )
 }
 
@@ -231,7 +251,7 @@ fn test_or_clause_with_in_constraint() {
 
     assert_eq!(
         l.read_sql(),
-        "SELECT * FROM league WHERE name = $1 OR id IN ($1, $2, $3) "
+        "SELECT * FROM league WHERE name = $1 OR id IN ($2, $3, $4)"
     )
 }
 
@@ -241,10 +261,676 @@ fn test_or_clause_with_in_constraint() {
 fn test_order_by_clause() {
     let mut l = League::select_query();
     l.r#where(LeagueFieldValue::name(&"LEC"), Comp::Eq)
-        .order_by(LeagueField::id, false);
+        .order_by(LeagueField::id, Order::Asc);
 
     assert_eq!(
         l.read_sql(),
         "SELECT * FROM league WHERE name = $1 ORDER BY id"
     )
 }
+
+/// Successive calls to `order_by` accumulates the keys on the same clause
+#[canyon_sql::macros::canyon_tokio_test]
+fn test_order_by_multiple_columns() {
+    let mut l = League::select_query();
+    l.r#where(LeagueFieldValue::region(&"EUROPE"), Comp::Eq)
+        .order_by(LeagueField::name, Order::Asc)
+        .order_by(LeagueField::id, Order::Desc);
+
+    assert_eq!(
+        l.read_sql(),
+        "SELECT * FROM league WHERE region = $1 ORDER BY name, id DESC"
+    )
+}
+
+/// The keys of `order_by` are accumulated on the same clause even when
+/// other clauses are chained between the calls
+#[canyon_sql::macros::canyon_tokio_test]
+fn test_order_by_interleaved_with_filters() {
+    let mut l = League::select_query();
+    l.order_by(LeagueField::name, Order::Asc)
+        .r#where(LeagueFieldValue::region(&"EUROPE"), Comp::Eq)
+        .order_by(LeagueField::id, Order::Desc);
+
+    assert_eq!(
+        l.read_sql(),
+        "SELECT * FROM league WHERE region = $1 ORDER BY name, id DESC"
+    )
+}
+
+/// The position of the `NULL` values is native on `PostgreSQL`, and emulated
+/// with a `CASE` on `SqlServer`
+#[canyon_sql::macros::canyon_tokio_test]
+fn test_order_by_nulls_by_database() {
+    let mut psql = Player::select_query();
+    psql.order_by_nulls(PlayerField::image_url, Order::Desc, Nulls::Last)
+        .order_by(PlayerField::id, Order::Asc);
+    assert_eq!(
+        psql.read_sql(),
+        "SELECT * FROM Player ORDER BY image_url DESC NULLS LAST, id"
+    );
+
+    let mut mssql = Player::select_query_datasource(SQL_SERVER_DS);
+    mssql
+        .order_by_nulls(PlayerField::image_url, Order::Asc, Nulls::First)
+        .order_by(PlayerField::id, Order::Asc);
+    assert_eq!(
+        mssql.read_sql(),
+        "SELECT * FROM Player ORDER BY CASE WHEN image_url IS NULL THEN 0 ELSE 1 END, image_url, id"
+    );
+}
+
+/// Orders the groups of the query by an aggregate function,
+/// and the rows by an SQL expression
+#[canyon_sql::macros::canyon_tokio_test]
+fn test_order_by_expressions_and_aggregates() {
+    let mut grouped = League::select_query();
+    grouped
+        .aggregate(Aggregate::Count, LeagueField::id, "leagues")
+        .group_by(&[LeagueField::region])
+        .order_by_aggregate(Aggregate::Count, LeagueField::id, Order::Desc)
+        .order_by(LeagueField::region, Order::Asc);
+    assert_eq!(
+        grouped.read_sql(),
+        "SELECT CAST(COUNT(id) AS BIGINT) AS leagues, region FROM league GROUP BY region \
+        ORDER BY CAST(COUNT(id) AS BIGINT) DESC, region"
+    );
+
+    let mut l = League::select_query();
+    l.order_by_expr("LOWER(name)", Order::Asc);
+    assert_eq!(l.read_sql(), "SELECT * FROM league ORDER BY LOWER(name)");
+}
+
+/// Sorts the leagues of a region by a multi key `ORDER BY` against the database
+#[canyon_sql::macros::canyon_tokio_test]
+fn test_order_by_multiple_columns_query() {
+    let mut l = League::select_query();
+    l.r#where(LeagueFieldValue::region(&"EUROPE"), Comp::Eq)
+        .order_by(LeagueField::name, Order::Desc)
+        .order_by(LeagueField::id, Order::Asc);
+    let leagues = l.query().await.expect("Failed to query with multiple keys");
+
+    assert!(!leagues.is_empty());
+    assert!(leagues.windows(2).all(|w| w[0].name >= w[1].name));
+}
+
+/// Tests for the generated SQL query after use the
+/// aggregate functions with the GROUP BY and HAVING clauses
+#[canyon_sql::macros::canyon_tokio_test]
+fn test_group_by_and_having_clauses() {
+    let mut l = League::select_query();
+    l.r#where(LeagueFieldValue::id(&50), Comp::LtEq)
+        .group_by(&[LeagueField::region])
+        .aggregate(Aggregate::Count, LeagueField::id, "leagues")
+        .having(Aggregate::Count, LeagueField::id, Comp::Gt, &1);
+
+    assert_eq!(
+        l.read_sql(),
+        "SELECT region, CAST(COUNT(id) AS BIGINT) AS leagues FROM league WHERE id <= $1 GROUP BY region HAVING CAST(COUNT(id) AS BIGINT) > $2"
+    )
+}
+
+/// Retrieves the results of aggregate functions over the rows
+/// filtered by the querybuilder as single values
+#[canyon_sql::macros::canyon_tokio_test]
+fn test_crud_aggregates_with_querybuilder() {
+    let leagues: Vec<League> = League::select_query()
+        .r#where(LeagueFieldValue::id(&50), Comp::LtEq)
+        .query()
+        .await
+        .expect("Failed to retrieve database League entries with the querybuilder");
+
+    let count = League::select_query()
+        .r#where(LeagueFieldValue::id(&50), Comp::LtEq)
+        .count(LeagueField::id)
+        .await
+        .expect("Failed the count aggregate");
+    assert_eq!(count, leagues.len() as i64);
+
+    let sum: Option<i64> = League::select_query()
+        .r#where(LeagueFieldValue::id(&50), Comp::LtEq)
+        .sum(LeagueField::id)
+        .await
+        .expect("Failed the sum aggregate");
+    assert_eq!(sum, Some(leagues.iter().map(|l| l.id as i64).sum()));
+
+    let max: Option<i32> = League::select_query()
+        .r#where(LeagueFieldValue::id(&50), Comp::LtEq)
+        .max(LeagueField::id)
+        .await
+        .expect("Failed the max aggregate");
+    assert_eq!(max, leagues.iter().map(|l| l.id).max());
+
+    // There's no values over an empty set of rows
+    let avg = League::select_query()
+        .r#where(LeagueFieldValue::id(&-1), Comp::Eq)
+        .avg(LeagueField::id)
+        .await
+        .expect("Failed the avg aggregate");
+    assert_eq!(avg, None);
+}
+
+/// The result of grouping the leagues by its region
+#[derive(Debug, CanyonMapper)]
+pub struct LeaguesByRegion {
+    region: String,
+    leagues: i64,
+}
+
+/// Maps the rows of a query with aggregate functions and
+/// a GROUP BY clause into a user defined type
+#[canyon_sql::macros::canyon_tokio_test]
+fn test_crud_group_by_with_querybuilder_datasource() {
+    let leagues: Vec<League> = League::find_all_datasource(SQL_SERVER_DS)
+        .await
+        .expect("Failed to retrieve database League entries");
+
+    let leagues_by_region: Vec<LeaguesByRegion> = League::select_query_datasource(SQL_SERVER_DS)
+        .group_by(&[LeagueField::region])
+        .aggregate(Aggregate::Count, LeagueField::id, "leagues")
+        .query_as::<LeaguesByRegion>()
+        .await
+        .expect("Failed to group the League entries by region");
+
+    assert!(!leagues_by_region.is_empty());
+    leagues_by_region.iter().for_each(|group| {
+        assert_eq!(
+            group.leagues,
+            leagues.iter().filter(|l| l.region == group.region).count() as i64
+        )
+    });
+}
+
+/// Tests for the generated SQL query after select only
+/// some columns of the table
+#[canyon_sql::macros::canyon_tokio_test]
+fn test_columns_projection() {
+    let mut l = League::select_query();
+    l.columns(&[LeagueField::id, LeagueField::name])
+        .column_as("UPPER(region)", "region")
+        .r#where(LeagueFieldValue::id(&10), Comp::LtEq);
+
+    assert_eq!(
+        l.read_sql(),
+        "SELECT id, name, UPPER(region) AS region FROM league WHERE id <= $1"
+    )
+}
+
+/// A type that only holds some of the columns of the `League` entity
+#[derive(Debug, CanyonMapper)]
+pub struct LeagueSummary {
+    id: i32,
+    name: String,
+}
+
+/// Maps the selected columns into a type that it's not an entity
+#[canyon_sql::macros::canyon_tokio_test]
+fn test_crud_columns_projection_with_querybuilder() {
+    let summaries: Vec<LeagueSummary> = League::select_query()
+        .columns(&[LeagueField::id, LeagueField::name])
+        .r#where(LeagueFieldValue::id(&1), Comp::Eq)
+        .query_as::<LeagueSummary>()
+        .await
+        .expect("Failed to retrieve the projection of the League entries");
+
+    let league = League::find_by_pk(&1)
+        .await
+        .expect("Failed the query to the database")
+        .expect("No entity found for the primary key value passed in");
+
+    assert_eq!(summaries.len(), 1);
+    assert_eq!(summaries[0].id, league.id);
+    assert_eq!(summaries[0].name, league.name);
+}
+
+/// Same as the above, but with the specified datasource
+#[canyon_sql::macros::canyon_tokio_test]
+fn test_crud_columns_projection_with_querybuilder_datasource() {
+    let summaries: Vec<LeagueSummary> = League::select_query_datasource(SQL_SERVER_DS)
+        .columns(&[LeagueField::id, LeagueField::name])
+        .r#where(LeagueFieldValue::id(&1), Comp::Eq)
+        .query_as::<LeagueSummary>()
+        .await
+        .expect("Failed to retrieve the projection of the League entries");
+
+    let league = League::find_by_pk_datasource(&1, SQL_SERVER_DS)
+        .await
+        .expect("Failed the query to the database")
+        .expect("No entity found for the primary key value passed in");
+
+    assert_eq!(summaries.len(), 1);
+    assert_eq!(summaries[0].id, league.id);
+    assert_eq!(summaries[0].name, league.name);
+}
+
+/// Builds a SELECT with the pattern matching operators, the conditions
+/// that doesn't take a single value, and a `NOT IN` constraint
+#[canyon_sql::macros::canyon_tokio_test]
+fn test_richer_operators_on_the_select_querybuilder() {
+    let mut l = Player::select_query();
+    l.r#where(PlayerFieldValue::first_name(&"Ar%"), Comp::Like)
+        .and(PlayerFieldValue::last_name(&"%x"), Comp::NotLike)
+        .and_condition(PlayerField::image_url, Condition::IsNull)
+        .or_condition(PlayerField::id, Condition::Between(&1, &10))
+        .and_not_values_in(PlayerField::role, &["top", "mid"]);
+
+    assert_eq!(
+        l.read_sql(),
+        "SELECT * FROM Player WHERE first_name LIKE $1 AND last_name NOT LIKE $2 \
+        AND image_url IS NULL OR id BETWEEN $3 AND $4 AND role NOT IN ($5, $6)"
+    )
+}
+
+/// The case insensitive `ILIKE` is emulated on `SqlServer`
+#[canyon_sql::macros::canyon_tokio_test]
+fn test_ilike_operator_by_database() {
+    let mut psql = League::select_query();
+    psql.r#where(LeagueFieldValue::name(&"%korea%"), Comp::ILike);
+    assert_eq!(psql.read_sql(), "SELECT * FROM league WHERE name ILIKE $1");
+
+    let mut mssql = League::select_query_datasource(SQL_SERVER_DS);
+    mssql.r#where(LeagueFieldValue::name(&"%korea%"), Comp::ILike);
+    assert_eq!(
+        mssql.read_sql(),
+        "SELECT * FROM league WHERE LOWER(name) LIKE LOWER(@P1)"
+    );
+}
+
+/// The conditions are also available for the UPDATE and DELETE statements
+#[canyon_sql::macros::canyon_tokio_test]
+fn test_conditions_on_the_update_and_delete_querybuilders() {
+    let mut update = Player::update_query();
+    update
+        .set(&[(PlayerField::role, "sup")])
+        .where_condition(PlayerField::image_url, Condition::IsNotNull)
+        .or_not_values_in(PlayerField::id, &[1, 2]);
+    assert_eq!(
+        update.read_sql(),
+        "UPDATE Player SET role = $1 WHERE image_url IS NOT NULL OR id NOT IN ($2, $3)"
+    );
+
+    let mut delete = Player::delete_query();
+    delete.where_condition(PlayerField::id, Condition::Between(&100, &200));
+    assert_eq!(
+        delete.read_sql(),
+        "DELETE FROM Player WHERE id BETWEEN $1 AND $2"
+    );
+}
+
+/// The statements are rendered with the syntax of the database of the datasource,
+/// numbering the placeholders by the position of their values
+#[canyon_sql::macros::canyon_tokio_test]
+fn test_generated_sql_for_the_sqlserver_datasource() {
+    let mut select = League::select_query_datasource(SQL_SERVER_DS);
+    select
+        .where_condition(LeagueField::id, Condition::Between(&1, &10))
+        .or_values_in(LeagueField::region, &["EU", "KR"])
+        .push_sql(" AND ext_id > $5");
+    assert_eq!(
+        select.read_sql(),
+        "SELECT * FROM league WHERE id BETWEEN @P1 AND @P2 OR region IN (@P3, @P4) AND ext_id > @P5"
+    );
+
+    let mut update = Player::update_query_datasource(SQL_SERVER_DS);
+    update
+        .r#where(PlayerFieldValue::id(&1), Comp::Eq)
+        .set(&[(PlayerField::role, "sup")]);
+    assert_eq!(
+        update.read_sql(),
+        "UPDATE Player SET role = @P2 WHERE id = @P1"
+    );
+}
+
+/// The SQL of the builders can be inspected for any database without executing
+/// them, and also written with the values of the parameters inlined
+#[canyon_sql::macros::canyon_tokio_test]
+fn test_inspect_the_sql_of_the_querybuilders() {
+    let mut select = League::select_query();
+    select
+        .r#where(LeagueFieldValue::name(&"O'Neill"), Comp::Eq)
+        .and(LeagueFieldValue::ext_id(&10), Comp::Gt);
+    let (sql, params) = select.to_sql(DatabaseType::SqlServer);
+    assert_eq!(
+        sql,
+        "SELECT * FROM league WHERE name = @P1 AND ext_id > @P2"
+    );
+    assert_eq!(params.len(), 2);
+    assert_eq!(
+        select.debug_sql(),
+        "SELECT * FROM league WHERE name = 'O''Neill' AND ext_id > 10"
+    );
+
+    let mut update = Player::update_query_datasource(SQL_SERVER_DS);
+    update
+        .r#where(PlayerFieldValue::id(&1), Comp::Eq)
+        .set(&[(PlayerField::role, "sup")]);
+    let (sql, _) = update.to_sql(DatabaseType::PostgreSql);
+    assert_eq!(sql, "UPDATE Player SET role = $2 WHERE id = $1");
+    assert_eq!(
+        update.debug_sql(),
+        "UPDATE Player SET role = 'sup' WHERE id = 1"
+    );
+
+    let mut delete = Player::delete_query();
+    delete.where_condition(PlayerField::id, Condition::Between(&1, &5));
+    let (sql, params) = delete.to_sql(DatabaseType::PostgreSql);
+    assert_eq!(sql, "DELETE FROM Player WHERE id BETWEEN $1 AND $2");
+    assert_eq!(params.len(), 2);
+    assert_eq!(
+        delete.debug_sql(),
+        "DELETE FROM Player WHERE id BETWEEN 1 AND 5"
+    );
+}
+
+/// Retrieves the plan of a query on both databases, before and after executing it
+#[canyon_sql::macros::canyon_tokio_test]
+fn test_explain_the_select_querybuilder() {
+    for (datasource, database_type) in [
+        ("", DatabaseType::PostgreSql),
+        (SQL_SERVER_DS, DatabaseType::SqlServer),
+    ] {
+        let mut select = League::select_query_datasource(datasource);
+        select.r#where(LeagueFieldValue::id(&3), Comp::Gt);
+
+        let plan = select.explain().await.expect("Failed to explain the query");
+        assert_eq!(plan.database_type, database_type);
+        assert!(!plan.root.node_type.is_empty());
+        assert!(plan.root.actual_rows.is_none());
+
+        let analyzed = select
+            .explain_analyze()
+            .await
+            .expect("Failed to explain and analyze the query");
+        assert!(analyzed.root.actual_rows.is_some());
+        assert!(!analyzed.raw.is_empty());
+    }
+}
+
+/// Filters with the new operators against both databases
+#[canyon_sql::macros::canyon_tokio_test]
+fn test_crud_find_with_richer_operators() {
+    for datasource in ["", SQL_SERVER_DS] {
+        let leagues = League::select_query_datasource(datasource)
+            .where_condition(LeagueField::id, Condition::Between(&1, &10))
+            .and(LeagueFieldValue::slug(&"%"), Comp::Like)
+            .and_not_values_in(LeagueField::id, &[1, 2])
+            .query()
+            .await
+            .expect("Failed to query with the richer operators");
+
+        assert!(leagues.len() <= 8);
+        assert!(leagues.iter().all(|l| l.id > 2 && l.id <= 10));
+    }
+}
+
+/// Builds a WHERE clause from a predicate expression, with nested groups
+/// that must be rendered between parentheses
+#[canyon_sql::macros::canyon_tokio_test]
+fn test_grouped_predicates_with_expr() {
+    let mut l = League::select_query();
+    l.where_expr(Expr::and([
+        Expr::compare(LeagueFieldValue::region(&"KOREA"), Comp::Eq),
+        Expr::or([
+            Expr::compare(LeagueFieldValue::id(&1), Comp::Lt),
+            Expr::values_in(LeagueField::id, &[7, 10]),
+        ]),
+        not(Expr::condition(
+            LeagueField::slug,
+            Condition::Between(&"a", &"m"),
+        )),
+    ]))
+    .or_expr(Expr::and([
+        Expr::compare(LeagueFieldValue::name(&"LCK"), Comp::Eq),
+        Expr::compare(LeagueFieldValue::ext_id(&0), Comp::Gt),
+    ]));
+
+    assert_eq!(
+        l.read_sql(),
+        "SELECT * FROM league WHERE (region = $1 AND (id < $2 OR id IN ($3, $4)) \
+        AND NOT (slug BETWEEN $5 AND $6)) OR (name = $7 AND ext_id > $8)"
+    )
+}
+
+/// A compound expression on the WHERE clause keeps its parentheses when
+/// more filters are chained after it
+#[canyon_sql::macros::canyon_tokio_test]
+fn test_compound_where_expr_followed_by_other_filters() {
+    let mut l = League::select_query();
+    l.where_expr(Expr::or([
+        Expr::compare(LeagueFieldValue::region(&"KOREA"), Comp::Eq),
+        Expr::compare(LeagueFieldValue::region(&"EU"), Comp::Eq),
+    ]))
+    .and(LeagueFieldValue::id(&10), Comp::Gt);
+
+    assert_eq!(
+        l.read_sql(),
+        "SELECT * FROM league WHERE (region = $1 OR region = $2) AND id > $3"
+    )
+}
+
+/// The IN filters without values doesn't match any row, and the
+/// NOT IN ones matches all of them
+#[canyon_sql::macros::canyon_tokio_test]
+fn test_in_filters_without_values() {
+    let ids: &[i32] = &[];
+
+    let mut l = League::select_query();
+    l.r#where(LeagueFieldValue::region(&"EU"), Comp::Eq)
+        .and_values_in(LeagueField::id, ids)
+        .or_not_values_in(LeagueField::id, ids);
+
+    assert_eq!(
+        l.read_sql(),
+        "SELECT * FROM league WHERE region = $1 AND 1 = 0 OR 1 = 1"
+    );
+
+    let mut l = League::select_query();
+    l.where_expr(Expr::values_in(LeagueField::id, ids));
+
+    assert_eq!(l.read_sql(), "SELECT * FROM league WHERE 1 = 0")
+}
+
+/// The empty groups of a predicate expression are skipped, so the
+/// expression can be built from optional filters
+#[canyon_sql::macros::canyon_tokio_test]
+fn test_predicates_with_optional_filters() {
+    let name: Option<&str> = None;
+    let ids: Option<&[i32]> = Some(&[1, 2]);
+
+    let filters = [
+        name.map(|_| Expr::compare(LeagueFieldValue::name(&"LEC"), Comp::Eq)),
+        ids.map(|ids| Expr::not_values_in(LeagueField::id, ids)),
+    ];
+
+    let mut l = League::select_query();
+    l.r#where(LeagueFieldValue::region(&"EU"), Comp::Eq)
+        .and_expr(Expr::and(filters.into_iter().flatten()))
+        .and_expr(Expr::or([]));
+
+    assert_eq!(
+        l.read_sql(),
+        "SELECT * FROM league WHERE region = $1 AND id NOT IN ($2, $3)"
+    )
+}
+
+/// Filters with a predicate expression against both databases
+#[canyon_sql::macros::canyon_tokio_test]
+fn test_crud_find_with_grouped_predicates() {
+    for datasource in ["", SQL_SERVER_DS] {
+        let leagues = League::select_query_datasource(datasource)
+            .where_expr(Expr::or([
+                Expr::compare(LeagueFieldValue::id(&1), Comp::Eq),
+                Expr::and([
+                    Expr::compare(LeagueFieldValue::id(&5), Comp::GtEq),
+                    not(Expr::compare(LeagueFieldValue::id(&6), Comp::Gt)),
+                ]),
+            ]))
+            .query()
+            .await
+            .expect("Failed to query with a predicate expression");
+
+        let mut ids = leagues.iter().map(|l| l.id).collect::<Vec<i32>>();
+        ids.sort();
+        assert_eq!(ids, [1, 5, 6]);
+    }
+}
+
+/// Embeds the query of another entity into the filters, renumbering
+/// its parameters after the ones of the outer query
+#[canyon_sql::macros::canyon_tokio_test]
+fn test_in_subquery_and_exists_clauses() {
+    let mut correlated = Tournament::select_query();
+    correlated
+        .r#where(TournamentFieldValue::ext_id(&10), Comp::Gt)
+        .push_sql(" AND Tournament.league = league.id");
+
+    let mut l = League::select_query();
+    l.r#where(LeagueFieldValue::region(&"KOREA"), Comp::Eq)
+        .and_expr(Expr::in_subquery(
+            LeagueField::id,
+            Tournament::select_query()
+                .columns(&[TournamentField::league])
+                .r#where(TournamentFieldValue::slug(&"lck"), Comp::Like),
+        ))
+        .or_expr(not(Expr::exists(&correlated)));
+
+    assert_eq!(
+        l.read_sql(),
+        "SELECT * FROM league WHERE region = $1 \
+        AND id IN (SELECT league FROM Tournament WHERE slug LIKE $2) \
+        OR NOT (EXISTS (SELECT * FROM Tournament WHERE ext_id > $3 AND Tournament.league = league.id))"
+    );
+
+    let mut p = Tournament::select_query();
+    p.where_in_subquery(
+        TournamentField::league,
+        League::select_query()
+            .columns(&[LeagueField::id])
+            .r#where(LeagueFieldValue::region(&"EU"), Comp::Eq),
+    );
+    assert_eq!(
+        p.read_sql(),
+        "SELECT * FROM Tournament WHERE league IN (SELECT id FROM league WHERE region = $1)"
+    );
+}
+
+/// Filters the leagues with a subquery over the tournaments against both databases
+#[canyon_sql::macros::canyon_tokio_test]
+fn test_crud_find_with_subqueries() {
+    for datasource in ["", SQL_SERVER_DS] {
+        let tournaments = Tournament::find_all_datasource(datasource)
+            .await
+            .expect("Failed to retrieve the tournaments");
+        let mut league_ids = tournaments.iter().map(|t| t.league).collect::<Vec<i32>>();
+        league_ids.sort();
+        league_ids.dedup();
+
+        let mut leagues_with_tournaments = League::select_query_datasource(datasource)
+            .where_in_subquery(
+                LeagueField::id,
+                Tournament::select_query().columns(&[TournamentField::league]),
+            )
+            .query()
+            .await
+            .expect("Failed to query with a subquery")
+            .iter()
+            .map(|l| l.id)
+            .collect::<Vec<i32>>();
+        leagues_with_tournaments.sort();
+        assert_eq!(leagues_with_tournaments, league_ids);
+
+        let mut correlated = Tournament::select_query();
+        correlated.push_sql(" WHERE Tournament.league = league.id");
+        let leagues_exists = League::select_query_datasource(datasource)
+            .where_exists(&correlated)
+            .query()
+            .await
+            .expect("Failed to query with an EXISTS subquery");
+        assert_eq!(leagues_exists.len(), league_ids.len());
+    }
+}
+
+/// Builds a typed join through the `#[foreign_key]` of the entities,
+/// aliasing the columns of both tables
+#[canyon_sql::macros::canyon_tokio_test]
+fn test_generated_sql_by_the_typed_joins() {
+    let mut inner = Tournament::select_query()
+        .inner_join_entity::<League>()
+        .expect("The tournaments are related with their leagues");
+    inner
+        .r#where(LeagueFieldValue::region(&"KOREA"), Comp::Eq)
+        .and(TournamentFieldValue::ext_id(&10), Comp::Gt)
+        .order_by(TournamentField::id, Order::Desc);
+
+    assert_eq!(
+        inner.read_sql(),
+        "SELECT Tournament.id AS t0_id, Tournament.ext_id AS t0_ext_id, Tournament.slug AS t0_slug, \
+        Tournament.start_date AS t0_start_date, Tournament.end_date AS t0_end_date, \
+        Tournament.league AS t0_league, league.id AS t1_id, league.ext_id AS t1_ext_id, \
+        league.slug AS t1_slug, league.name AS t1_name, league.region AS t1_region, \
+        league.image_url AS t1_image_url, \
+        CASE WHEN league.id IS NULL THEN 0 ELSE 1 END AS canyon_joined \
+        FROM Tournament INNER JOIN league ON Tournament.league = league.id \
+        WHERE league.region = $1 AND Tournament.ext_id > $2 ORDER BY Tournament.id DESC"
+    );
+
+    // The relation is also found from the referenced side
+    let left = League::select_query()
+        .left_join_entity::<Tournament>()
+        .expect("The leagues are related with their tournaments");
+    assert!(left.read_sql().ends_with(
+        "CASE WHEN Tournament.league IS NULL THEN 0 ELSE 1 END AS canyon_joined \
+        FROM league LEFT JOIN Tournament ON Tournament.league = league.id"
+    ));
+
+    // The entities without a foreign key between them can't be joined
+    assert!(Player::select_query()
+        .inner_join_entity::<League>()
+        .is_err());
+
+    // The filters and orders of the query would be lost by the join
+    let mut filtered = Tournament::select_query();
+    filtered.r#where(TournamentFieldValue::ext_id(&10), Comp::Gt);
+    assert!(filtered.inner_join_entity::<League>().is_err());
+    let mut ordered = League::select_query();
+    ordered.order_by(LeagueField::id, Order::Asc);
+    assert!(ordered.left_join_entity::<Tournament>().is_err());
+}
+
+/// Retrieves the tournaments with their leagues, mapped into tuples
+#[canyon_sql::macros::canyon_tokio_test]
+fn test_crud_inner_join_with_querybuilder() {
+    for datasource in ["", SQL_SERVER_DS] {
+        let joined: Vec<(Tournament, League)> = Tournament::select_query_datasource(datasource)
+            .inner_join_entity::<League>()
+            .expect("The tournaments are related with their leagues")
+            .query()
+            .await
+            .expect("Failed to query the tournaments joined with their leagues");
+
+        assert!(!joined.is_empty());
+        assert!(joined.iter().all(|(t, l)| t.league == l.id));
+    }
+}
+
+/// Retrieves every league with its tournaments, that may not exist
+#[canyon_sql::macros::canyon_tokio_test]
+fn test_crud_left_join_with_querybuilder() {
+    for datasource in ["", SQL_SERVER_DS] {
+        let joined: Vec<(League, Option<Tournament>)> = League::select_query_datasource(datasource)
+            .left_join_entity::<Tournament>()
+            .expect("The leagues are related with their tournaments")
+            .query()
+            .await
+            .expect("Failed to query the leagues joined with their tournaments");
+
+        let leagues = League::find_all_datasource(datasource)
+            .await
+            .expect("Failed to retrieve the leagues");
+
+        assert!(joined.len() >= leagues.len());
+        assert!(joined
+            .iter()
+            .all(|(l, t)| t.as_ref().is_none_or(|t| t.league == l.id)));
+    }
+}
@@ -0,0 +1,127 @@
+//! Integration tests for the free functions that launches raw SQL statements,
+//! mapping the results into types that aren't entities
+
+use crate::constants::{PSQL_DS, SQL_SERVER_DS};
+use canyon_sql::crud::{CrudOperations, Transaction};
+use canyon_sql::macros::CanyonMapper;
+
+use crate::tests_models::league::*;
+
+/// A report that only holds some of the columns of the `league` table
+#[derive(Debug, CanyonMapper)]
+pub struct LeagueReport {
+    id: i32,
+    region: String,
+}
+
+/// Maps the results of a raw query into a type that only derives `CanyonMapper`
+#[canyon_sql::macros::canyon_tokio_test]
+fn test_raw_query_as() {
+    let reports: Vec<LeagueReport> = canyon_sql::query_as::<LeagueReport>(
+        PSQL_DS,
+        "SELECT id, region FROM league WHERE id = $1",
+        [&1 as _],
+    )
+    .await
+    .expect("Failed to launch the raw query");
+
+    let league = League::find_by_pk(&1)
+        .await
+        .expect("Failed the query to the database")
+        .expect("No entity found for the primary key value passed in");
+
+    assert_eq!(reports.len(), 1);
+    assert_eq!(reports[0].id, league.id);
+    assert_eq!(reports[0].region, league.region);
+}
+
+/// Same as the above, but against the `SqlServer` datasource
+#[canyon_sql::macros::canyon_tokio_test]
+fn test_raw_query_as_datasource() {
+    let reports: Vec<LeagueReport> = canyon_sql::query_as::<LeagueReport>(
+        SQL_SERVER_DS,
+        "SELECT id, region FROM league WHERE id = $1",
+        [&1 as _],
+    )
+    .await
+    .expect("Failed to launch the raw query");
+
+    assert_eq!(reports.len(), 1);
+    assert_eq!(reports[0].id, 1);
+}
+
+/// Retrieves a single value with a raw query
+#[canyon_sql::macros::canyon_tokio_test]
+fn test_raw_query_scalar() {
+    let count =
+        canyon_sql::query_scalar::<i64>(PSQL_DS, "SELECT CAST(COUNT(*) AS BIGINT) FROM league", [])
+            .await
+            .expect("Failed to launch the raw query");
+
+    assert_eq!(count, Some(League::find_all().await.unwrap().len() as i64));
+}
+
+/// Same as the above, but against the `SqlServer` datasource
+#[canyon_sql::macros::canyon_tokio_test]
+fn test_raw_query_scalar_datasource() {
+    let count = canyon_sql::query_scalar::<i64>(
+        SQL_SERVER_DS,
+        "SELECT CAST(COUNT(*) AS BIGINT) FROM league",
+        [],
+    )
+    .await
+    .expect("Failed to launch the raw query");
+
+    assert_eq!(
+        count,
+        Some(
+            League::find_all_datasource(SQL_SERVER_DS)
+                .await
+                .unwrap()
+                .len() as i64
+        )
+    );
+}
+
+/// Executes a statement that doesn't retrieve rows, checking the affected ones
+#[canyon_sql::macros::canyon_tokio_test]
+fn test_raw_execute() {
+    let affected = canyon_sql::execute(
+        PSQL_DS,
+        "UPDATE league SET slug = slug WHERE id = $1",
+        [&1 as _],
+    )
+    .await
+    .expect("Failed to execute the raw statement");
+
+    assert_eq!(affected, 1);
+}
+
+/// Same as the above, but against the `SqlServer` datasource
+#[canyon_sql::macros::canyon_tokio_test]
+fn test_raw_execute_datasource() {
+    let affected = canyon_sql::execute(
+        SQL_SERVER_DS,
+        "UPDATE league SET slug = slug WHERE id = $1",
+        [&1 as _],
+    )
+    .await
+    .expect("Failed to execute the raw statement");
+
+    assert_eq!(affected, 1);
+}
+
+/// Launches a raw statement written with named parameters, against both databases
+#[canyon_sql::macros::canyon_tokio_test]
+fn test_raw_query_named_parameters() {
+    let stmt = "SELECT * FROM league WHERE id = :league_id OR (id = @league_id AND slug <> ':no')";
+
+    for datasource in [PSQL_DS, SQL_SERVER_DS] {
+        let leagues = League::query_named(stmt, &[("league_id", &1)], datasource)
+            .await
+            .expect("Failed to launch the statement with named parameters")
+            .get_entities::<League>();
+
+        assert_eq!(leagues.len(), 1);
+    }
+}
@@ -0,0 +1,122 @@
+//! Integration tests for the relation fields of the entities, the ones
+//! annotated with `#[has_many(Entity)]` and `#[many_to_many(Entity, through = "...")]`.
+//!
+//! They aren't columns of the table, so they're empty when the entity is retrieved
+//! from the database, until they're filled with the autogenerated `load_*` methods
+use canyon_sql::crud::CrudOperations;
+
+use crate::constants::SQL_SERVER_DS;
+use crate::tests_models::team::*;
+use crate::tests_models::team_player::*;
+use crate::tests_models::tournament::*;
+
+/// The relation fields are not mapped against any column, so they
+/// don't take part on the queries generated for the entity
+#[canyon_sql::macros::canyon_tokio_test]
+fn test_crud_relation_fields_are_not_columns() {
+    let team: Team = Team::find_by_pk(&1)
+        .await
+        .expect("Result variant of the query is err")
+        .expect("No result found for the given parameter");
+
+    assert_eq!(team.id, 1);
+    assert!(team.roster.is_empty());
+    assert!(team.tournaments.is_empty());
+}
+
+/// Loads into a `#[has_many]` field the entities that points to the parent
+/// through their `#[foreign_key]`
+#[canyon_sql::macros::canyon_tokio_test]
+fn test_crud_load_has_many_relation() {
+    let mut team: Team = Team::find_by_pk(&2)
+        .await
+        .expect("Result variant of the query is err")
+        .expect("No result found for the given parameter");
+
+    let mut team_player = TeamPlayer {
+        team: team.id,
+        player: 3,
+        role: "mid".to_string(),
+    };
+    team_player.insert().await.expect("Failed insert operation");
+
+    team.load_roster()
+        .await
+        .expect("Failed to load the has many relation");
+
+    assert!(team.roster.contains(&team_player));
+    team.roster
+        .iter()
+        .for_each(|tp| assert_eq!(tp.team, team.id));
+
+    team_player.delete().await.expect("Failed delete operation");
+}
+
+/// Adds and removes associations in the join table of a `#[many_to_many]` field,
+/// and loads the related entities through it
+#[canyon_sql::macros::canyon_tokio_test]
+fn test_crud_many_to_many_relation() {
+    let mut team: Team = Team::find_by_pk(&3)
+        .await
+        .expect("Result variant of the query is err")
+        .expect("No result found for the given parameter");
+    let first: Tournament = Tournament::find_by_pk(&1)
+        .await
+        .expect("Result variant of the query is err")
+        .expect("No result found for the given parameter");
+    let second: Tournament = Tournament::find_by_pk(&2)
+        .await
+        .expect("Result variant of the query is err")
+        .expect("No result found for the given parameter");
+
+    team.add_tournaments(&[&first, &second])
+        .await
+        .expect("Failed to add the associations");
+    team.load_tournaments()
+        .await
+        .expect("Failed to load the many to many relation");
+    assert_eq!(team.tournaments.len(), 2);
+    assert!(team.tournaments.contains(&first));
+    assert!(team.tournaments.contains(&second));
+
+    team.remove_tournaments(&[&first])
+        .await
+        .expect("Failed to remove the associations");
+    team.load_tournaments()
+        .await
+        .expect("Failed to load the many to many relation");
+    assert_eq!(team.tournaments, vec![second.clone()]);
+
+    team.remove_tournaments(&[&second])
+        .await
+        .expect("Failed to remove the associations");
+}
+
+/// Same as the many to many relation test, but with the specified datasource
+#[canyon_sql::macros::canyon_tokio_test]
+fn test_crud_many_to_many_relation_datasource() {
+    let mut team: Team = Team::find_by_pk_datasource(&3, SQL_SERVER_DS)
+        .await
+        .expect("Result variant of the query is err")
+        .expect("No result found for the given parameter");
+    let tournament: Tournament = Tournament::find_by_pk_datasource(&1, SQL_SERVER_DS)
+        .await
+        .expect("Result variant of the query is err")
+        .expect("No result found for the given parameter");
+
+    team.add_tournaments_datasource(&[&tournament], SQL_SERVER_DS)
+        .await
+        .expect("Failed to add the associations");
+    team.load_tournaments_datasource(SQL_SERVER_DS)
+        .await
+        .expect("Failed to load the many to many relation");
+    assert_eq!(team.tournaments, vec![tournament.clone()]);
+
+    team.remove_tournaments_datasource(&[&tournament], SQL_SERVER_DS)
+        .await
+        .expect("Failed to remove the associations");
+    team.load_tournaments_datasource(SQL_SERVER_DS)
+        .await
+        .expect("Failed to load the many to many relation");
+    assert!(team.tournaments.is_empty());
+}
@@ -0,0 +1,164 @@
+//! Integration tests for the entities with a `#[soft_delete]` field
+use canyon_sql::{
+    crud::{CrudOperations, DatabaseType, EagerLoading},
+    query::{operators::Comp, ops::QueryBuilder},
+};
+
+use crate::tests_models::event::*;
+use crate::tests_models::venue::*;
+
+/// The `Venue` marks its rows as deleted with the `deleted_at` column,
+/// that leaves them out of the reads unless they're explicitly requested
+#[canyon_sql::macros::canyon_tokio_test]
+fn test_crud_soft_delete_sql_inspection() {
+    let (sql, _) = Venue::find_all_sql(DatabaseType::PostgreSql);
+    assert_eq!(sql, "SELECT * FROM venue WHERE \"deleted_at\" IS NULL");
+
+    let (sql, _) = Venue::count_sql(DatabaseType::SqlServer);
+    assert_eq!(
+        sql,
+        "SELECT COUNT(*) FROM venue WHERE \"deleted_at\" IS NULL"
+    );
+
+    let (sql, _) =
+        Venue::find_by_pk_sql(&1, DatabaseType::PostgreSql).expect("Venue has a primary key");
+    assert_eq!(
+        sql,
+        "SELECT * FROM venue WHERE \"id\" = $1 AND \"deleted_at\" IS NULL"
+    );
+
+    let venue = Venue {
+        id: 1,
+        name: "Gocheok Sky Dome".to_string(),
+        city: "Seoul".to_string(),
+        deleted_at: None,
+    };
+    let (sql, params) = venue
+        .delete_sql(DatabaseType::SqlServer)
+        .expect("Venue has a primary key");
+    assert_eq!(
+        sql,
+        "UPDATE venue SET \"deleted_at\" = SYSUTCDATETIME() \
+        WHERE \"id\" = @P1 AND \"deleted_at\" IS NULL"
+    );
+    assert_eq!(params.len(), 1);
+
+    let mut select = Venue::select_query();
+    select.r#where(VenueFieldValue::city(&"Seoul"), Comp::Eq);
+    let (sql, _) = select.to_sql(DatabaseType::PostgreSql);
+    assert_eq!(
+        sql,
+        "SELECT * FROM venue WHERE city = $1 AND deleted_at IS NULL"
+    );
+
+    select.only_deleted();
+    let (sql, _) = select.to_sql(DatabaseType::PostgreSql);
+    assert_eq!(
+        sql,
+        "SELECT * FROM venue WHERE city = $1 AND deleted_at IS NOT NULL"
+    );
+
+    select.with_deleted();
+    let (sql, _) = select.to_sql(DatabaseType::PostgreSql);
+    assert_eq!(sql, "SELECT * FROM venue WHERE city = $1");
+
+    // The deleted venues are left out of both sides of the joins
+    let join = Event::select_query()
+        .left_join_entity::<Venue>()
+        .expect("The events are related with their venues");
+    assert!(join.read_sql().ends_with(
+        "FROM event LEFT JOIN venue ON event.venue = venue.id AND venue.deleted_at IS NULL"
+    ));
+    let join = Venue::select_query()
+        .inner_join_entity::<Event>()
+        .expect("The venues are related with their events");
+    assert!(join.read_sql().ends_with(
+        "FROM venue INNER JOIN event ON event.venue = venue.id WHERE venue.deleted_at IS NULL"
+    ));
+
+    // The scope of the deleted rows can't be carried into a join
+    let mut scoped = Venue::select_query();
+    scoped.with_deleted();
+    assert!(scoped.inner_join_entity::<Event>().is_err());
+}
+
+/// The deleted venues are only found when they're asked for, until
+/// they're restored or removed for good, neither as the venue of an event
+#[canyon_sql::macros::canyon_tokio_test]
+fn test_crud_soft_delete_operations() {
+    let mut venue = Venue {
+        id: Default::default(),
+        name: "Gocheok Sky Dome".to_string(),
+        city: "Seoul".to_string(),
+        deleted_at: None,
+    };
+    venue.insert().await.expect("Failed insert operation");
+    let count = Venue::count().await.expect("Failed to count");
+    let mut event = Event {
+        id: Default::default(),
+        name: "Worlds 2023 final".to_string(),
+        venue: venue.id,
+    };
+    event.insert().await.expect("Failed insert operation");
+
+    venue
+        .delete()
+        .await
+        .expect("Failed to soft delete the venue");
+    assert_eq!(
+        Venue::find_by_pk(&venue.id).await.expect("Request error"),
+        None
+    );
+    assert_eq!(Venue::count().await.expect("Failed to count"), count - 1);
+    let events = vec![event.clone()]
+        .with::<Venue>()
+        .await
+        .expect("Failed to load the venues of the events");
+    assert_eq!(events[0].1, None);
+    let joined: Vec<(Event, Option<Venue>)> = Event::select_query()
+        .left_join_entity::<Venue>()
+        .expect("The events are related with their venues")
+        .query()
+        .await
+        .expect("Failed to query the events joined with their venues");
+    assert!(joined
+        .iter()
+        .any(|(joined, venue)| *joined == event && venue.is_none()));
+
+    let deleted = Venue::select_query()
+        .only_deleted()
+        .r#where(VenueFieldValue::id(&venue.id), Comp::Eq)
+        .query()
+        .await
+        .expect("Failed to retrieve the deleted venues");
+    assert_eq!(deleted.len(), 1);
+    assert!(deleted[0].deleted_at.is_some());
+
+    deleted[0]
+        .restore()
+        .await
+        .expect("Failed to restore the venue");
+    let restored = Venue::find_by_pk(&venue.id)
+        .await
+        .expect("Request error")
+        .expect("None value");
+    assert_eq!(restored.deleted_at, None);
+    let events = vec![event.clone()]
+        .with::<Venue>()
+        .await
+        .expect("Failed to load the venues of the events");
+    assert_eq!(events[0].1.as_ref(), Some(&restored));
+
+    event.delete().await.expect("Failed to delete the event");
+    restored
+        .force_delete()
+        .await
+        .expect("Failed to delete the venue");
+    assert!(Venue::select_query()
+        .with_deleted()
+        .r#where(VenueFieldValue::id(&venue.id), Comp::Eq)
+        .query()
+        .await
+        .expect("Failed to retrieve the venues")
+        .is_empty());
+}
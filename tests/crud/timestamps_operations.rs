@@ -0,0 +1,86 @@
+//! Integration tests for the `#[created_at]` and `#[updated_at]` annotations
+use canyon_sql::{
+    crud::{CrudOperations, DatabaseType},
+    query::ops::QueryBuilder,
+};
+
+use crate::tests_models::article::*;
+
+/// The `created_at` of the `Article` is generated by the database, so it's left
+/// out of the inserts and never updated, while its `updated_at` is bound to the
+/// current time of the client by every update
+#[canyon_sql::macros::canyon_tokio_test]
+fn test_crud_timestamps_sql_inspection() {
+    let article = Article {
+        id: 1,
+        title: "Worlds 2023 recap".to_string(),
+        created_at: Default::default(),
+        updated_at: Default::default(),
+    };
+
+    let (sql, params) = article.insert_sql(DatabaseType::PostgreSql);
+    assert_eq!(
+        sql,
+        "INSERT INTO article (\"title\", \"updated_at\") VALUES ($1, $2) RETURNING id"
+    );
+    assert_eq!(params.len(), 2);
+
+    let (sql, params) = article
+        .update_sql(DatabaseType::PostgreSql)
+        .expect("Article has a primary key");
+    assert_eq!(
+        sql,
+        "UPDATE article SET \"title\" = $2, \"updated_at\" = $3 WHERE \"id\" = $1"
+    );
+    assert_eq!(params.len(), 3);
+
+    let mut update = Article::update_query();
+    update.set(&[(ArticleField::title, "Worlds 2024 recap")]);
+    let (sql, params) = update.to_sql(DatabaseType::SqlServer);
+    assert_eq!(sql, "UPDATE article SET title = @P1, updated_at = @P2");
+    assert_eq!(params.len(), 2);
+
+    let mut update = Article::update_query();
+    update.set(&[
+        (ArticleField::title, "Worlds 2024 recap"),
+        (ArticleField::updated_at, "2023-11-19 00:00:00"),
+    ]);
+    let (sql, _) = update.to_sql(DatabaseType::PostgreSql);
+    assert_eq!(sql, "UPDATE article SET title = $1, updated_at = $2");
+}
+
+/// The inserts assigns both timestamps of the `Article`, and the updates
+/// moves forward only its `updated_at`
+#[canyon_sql::macros::canyon_tokio_test]
+fn test_crud_timestamps_operations() {
+    let mut article = Article {
+        id: Default::default(),
+        title: "Worlds 2023 recap".to_string(),
+        created_at: Default::default(),
+        updated_at: Default::default(),
+    };
+
+    article
+        .insert_returning_all()
+        .await
+        .expect("Failed insert operation");
+    assert_ne!(article.created_at, Default::default());
+    assert_ne!(article.updated_at, Default::default());
+
+    let inserted_at = article.updated_at;
+    article.title = "Worlds 2023 final recap".to_string();
+    article.update().await.expect("Failed update operation");
+    assert!(article.updated_at >= inserted_at);
+
+    // The entity holds the time written by the update
+    let updated = Article::find_by_pk(&article.id)
+        .await
+        .expect("Request error")
+        .expect("None value");
+    assert_eq!(updated, article);
+
+    updated
+        .delete()
+        .await
+        .expect("Failed to delete the operation");
+}
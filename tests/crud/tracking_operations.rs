@@ -0,0 +1,134 @@
+//! Integration tests for the partial updates of the changed columns of the entities
+use canyon_sql::{
+    crud::{changed_columns, CrudOperations, DatabaseType},
+    date_time::NaiveDate,
+    query::ops::QueryBuilder,
+};
+
+use crate::tests_models::standing::*;
+
+/// The `diff` of two `Standing` only assigns their changed columns, along with
+/// the `#[updated_at]` and the `#[version]` ones, and leaves out the timestamps
+/// and the soft delete columns
+#[canyon_sql::macros::canyon_tokio_test]
+fn test_crud_tracking_sql_inspection() {
+    let old = Standing {
+        id: 1,
+        team: 1,
+        wins: 3,
+        losses: 1,
+        created_at: Default::default(),
+        updated_at: Default::default(),
+        deleted_at: None,
+        version: 2,
+    };
+    let mut new = old.clone();
+    assert!(changed_columns(&old, &new).is_empty());
+    let (sql, params) = Standing::diff(&old, &new)
+        .expect("Standing has a primary key")
+        .to_sql(DatabaseType::PostgreSql);
+    assert_eq!(sql, "UPDATE standing");
+    assert!(params.is_empty());
+
+    let now = NaiveDate::from_ymd_opt(2024, 11, 2)
+        .unwrap()
+        .and_hms_opt(0, 0, 0)
+        .unwrap();
+    new.created_at = now;
+    new.updated_at = now;
+    new.deleted_at = Some(now);
+    assert!(changed_columns(&old, &new).is_empty());
+
+    new.wins = 4;
+    new.losses = 2;
+    assert_eq!(changed_columns(&old, &new), ["wins", "losses"]);
+
+    let (sql, params) = Standing::diff(&old, &new)
+        .expect("Standing has a primary key")
+        .to_sql(DatabaseType::SqlServer);
+    assert_eq!(
+        sql,
+        "UPDATE standing SET wins = @P1, losses = @P2, updated_at = @P3, \
+        version = version + 1 WHERE id = @P4 AND version = @P5"
+    );
+    assert_eq!(params.len(), 5);
+
+    let mut update = Standing::update_query();
+    update.set(&[(StandingField::wins, 4)]);
+    assert!(update.changes(&old, &new).is_err());
+
+    // Neither the `set` after the `changes` nor an update without any
+    // assignment are launched, but both fails instead of doing nothing
+    let mut update = Standing::diff(&old, &new).expect("Standing has a primary key");
+    update.set(&[(StandingField::wins, 5)]);
+    assert!(update.execute().await.is_err());
+    assert!(Standing::update_query().execute().await.is_err());
+
+    // While the changes of an unchanged entity are skipped
+    let unchanged = Standing::diff(&old, &old).expect("Standing has a primary key");
+    assert_eq!(unchanged.execute().await.expect("Nothing to launch"), 0);
+}
+
+/// Only the changed columns of the `Standing` are written, and nothing is
+/// launched when there's no changes
+#[canyon_sql::macros::canyon_tokio_test]
+fn test_crud_tracking_operations() {
+    let mut standing = Standing {
+        id: Default::default(),
+        team: 1,
+        wins: 0,
+        losses: 0,
+        created_at: Default::default(),
+        updated_at: Default::default(),
+        deleted_at: None,
+        version: 0,
+    };
+    standing.insert().await.expect("Failed insert operation");
+
+    let snapshot = Standing::find_by_pk(&standing.id)
+        .await
+        .expect("Request error")
+        .expect("None value");
+    let mut modified = snapshot.clone();
+    modified.wins = 1;
+    modified
+        .update_changes(&snapshot)
+        .await
+        .expect("Failed update operation");
+    assert_eq!(modified.version, 1);
+
+    let mut current = Standing::find_by_pk(&standing.id)
+        .await
+        .expect("Request error")
+        .expect("None value");
+    assert_eq!(current.wins, 1);
+    assert_eq!(current.version, 1);
+
+    let unchanged = current.clone();
+    current
+        .update_changes(&unchanged)
+        .await
+        .expect("Failed update operation");
+    assert_eq!(current, unchanged);
+
+    let mut extended = current.clone();
+    extended.losses = 1;
+    let updated = Standing::diff(&current, &extended)
+        .expect("Standing has a primary key")
+        .execute()
+        .await
+        .expect("Failed update operation");
+    assert_eq!(updated, 1);
+
+    let current = Standing::find_by_pk(&standing.id)
+        .await
+        .expect("Request error")
+        .expect("None value");
+    assert_eq!(current.losses, extended.losses);
+    assert_eq!(current.version, 2);
+
+    current
+        .force_delete()
+        .await
+        .expect("Failed to delete the standing");
+}
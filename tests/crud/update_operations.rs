@@ -1,9 +1,10 @@
 ///! Integration tests for the CRUD operations available in `Canyon` that
 ///! generates and executes *UPDATE* statements
-use canyon_sql::crud::CrudOperations;
+use canyon_sql::crud::{CrudOperations, DatabaseType};
 
 use crate::constants::SQL_SERVER_DS;
 use crate::tests_models::league::*;
+use crate::tests_models::team_player::*;
 
 /// Update operation is a *CRUD* method defined for some entity `T`, that works by appliying
 /// some change to a Rust's entity instance, and persisting them into the database.
@@ -93,3 +94,86 @@ fn test_crud_update_datasource_method_operation() {
         .await
         .expect("Failed to restablish the initial value update operation");
 }
+
+/// Updates a record of an entity with a composite primary key, which
+/// is targeted by every field annotated with `#[primary_key]`
+#[canyon_sql::macros::canyon_tokio_test]
+fn test_crud_update_composite_pk_method_operation() {
+    let mut new_team_player: TeamPlayer = TeamPlayer {
+        team: 1,
+        player: 2,
+        role: "jungle".to_string(),
+    };
+    new_team_player
+        .insert()
+        .await
+        .expect("Failed insert operation");
+
+    new_team_player.role = "support".to_string();
+    new_team_player
+        .update()
+        .await
+        .expect("Failed the update operation");
+
+    let updt_entity: TeamPlayer = TeamPlayer::find_by_pk((&1, &2))
+        .await
+        .expect("Failed the query to the database")
+        .expect("No entity found for the primary key values passed in");
+
+    assert_eq!(updt_entity.role, "support");
+}
+
+/// The statements of the CRUD operations can be inspected for any database
+/// without executing them
+#[canyon_sql::macros::canyon_tokio_test]
+fn test_crud_operations_sql_inspection() {
+    let league = League {
+        id: 3,
+        ext_id: 7892635306594_i64,
+        slug: "some-league".to_string(),
+        name: "Some League".to_string(),
+        region: "EU".to_string(),
+        image_url: "https://someleague.io".to_string(),
+    };
+
+    let (sql, params) = league.insert_sql(DatabaseType::SqlServer);
+    assert_eq!(
+        sql,
+        "INSERT INTO league (\"ext_id\", \"slug\", \"name\", \"region\", \"image_url\") OUTPUT inserted.id VALUES (@P1, @P2, @P3, @P4, @P5)"
+    );
+    assert_eq!(params.len(), 5);
+
+    let (sql, params) = league
+        .update_sql(DatabaseType::PostgreSql)
+        .expect("League has a primary key");
+    assert_eq!(
+        sql,
+        "UPDATE league SET \"ext_id\" = $2, \"slug\" = $3, \"name\" = $4, \"region\" = $5, \"image_url\" = $6 WHERE \"id\" = $1"
+    );
+    assert_eq!(params.len(), 6);
+
+    let (sql, _) = league
+        .delete_sql(DatabaseType::PostgreSql)
+        .expect("League has a primary key");
+    assert_eq!(sql, "DELETE FROM league WHERE \"id\" = $1");
+
+    let (sql, params) =
+        League::find_by_pk_sql(&3, DatabaseType::SqlServer).expect("League has a primary key");
+    assert_eq!(sql, "SELECT * FROM league WHERE \"id\" = @P1");
+    assert_eq!(params.values().len(), 1);
+
+    assert_eq!(
+        League::count_sql(DatabaseType::PostgreSql).0,
+        "SELECT COUNT(*) FROM league"
+    );
+
+    // The composite primary keys takes the values of all their fields
+    let (sql, params) = TeamPlayer::find_by_pk_sql((1, 7), DatabaseType::PostgreSql)
+        .expect("TeamPlayer has a composite primary key");
+    assert_eq!(
+        sql,
+        "SELECT * FROM team_player WHERE \"team\" = $1 AND \"player\" = $2"
+    );
+    assert_eq!(params.values().len(), 2);
+    assert!(TeamPlayer::find_by_pk_sql(&1, DatabaseType::PostgreSql).is_err());
+}
@@ -0,0 +1,37 @@
+//! Integration tests for the `#[validate]` annotations checked by the CRUD operations
+use canyon_sql::crud::{CrudOperations, Validate, ValidationErrors};
+
+use crate::tests_models::caster::*;
+
+/// The `Caster` entity validates the `ext_id` and the `nickname` fields, so the
+/// invalid entities are rejected with all the broken rules before reaching the database
+#[canyon_sql::macros::canyon_tokio_test]
+fn test_crud_validation_operations() {
+    let mut caster = Caster {
+        id: Default::default(),
+        ext_id: -1,
+        nickname: "Caster.Jatt".repeat(7),
+    };
+
+    let errors = caster.validate().unwrap_err();
+    assert_eq!(errors.field("ext_id").len(), 1);
+    assert_eq!(
+        errors
+            .field("nickname")
+            .iter()
+            .map(|error| error.rule)
+            .collect::<Vec<_>>(),
+        ["length", "regex"]
+    );
+
+    let error = caster.insert().await.unwrap_err();
+    let errors = error
+        .downcast_ref::<ValidationErrors>()
+        .expect("Expected the broken rules of the entity");
+    assert_eq!(errors.errors.len(), 3);
+    assert!(caster.update().await.is_err());
+
+    caster.ext_id = 7892635306594_i64;
+    caster.nickname = String::from("jatt");
+    assert!(caster.validate().is_ok());
+}
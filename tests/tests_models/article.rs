@@ -0,0 +1,17 @@
+use canyon_sql::{date_time::NaiveDateTime, macros::*};
+
+#[derive(Debug, Clone, Fields, CanyonCrud, CanyonMapper, Eq, PartialEq)]
+#[canyon_entity(table_name = "article")]
+/// Data model that represents a database entity for the news Articles.
+///
+/// Its `created_at` is generated by the database, and its `updated_at`
+/// by the client
+pub struct Article {
+    #[primary_key]
+    id: i32,
+    title: String,
+    #[created_at(source = "database")]
+    created_at: NaiveDateTime,
+    #[updated_at]
+    updated_at: NaiveDateTime,
+}
@@ -0,0 +1,15 @@
+use canyon_sql::macros::*;
+
+#[derive(Debug, Clone, Fields, CanyonCrud, CanyonMapper, Eq, PartialEq)]
+#[canyon_entity(table_name = "bracket")]
+/// Data model that represents a database entity for the Brackets of the tournaments.
+///
+/// Its writes are guarded by the optimistic locking of its `version`
+pub struct Bracket {
+    #[primary_key]
+    id: i32,
+    name: String,
+    rounds: i32,
+    #[version]
+    version: i32,
+}
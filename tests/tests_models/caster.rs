@@ -0,0 +1,15 @@
+use canyon_sql::macros::*;
+
+#[derive(Debug, Clone, Fields, CanyonCrud, CanyonMapper, Eq, PartialEq)]
+#[canyon_entity(table_name = "caster")]
+/// Data model that represents a database entity for the Casters of the matches.
+///
+/// Its fields declares `#[validate]` rules, checked before writing it
+pub struct Caster {
+    #[primary_key]
+    id: i32,
+    #[validate(range(min = 0))]
+    ext_id: i64,
+    #[validate(length(min = 1, max = 64), regex = "^[a-z0-9_-]+$")]
+    nickname: String,
+}
@@ -0,0 +1,14 @@
+use crate::tests_models::venue::Venue;
+use canyon_sql::macros::*;
+
+#[derive(Debug, Clone, Fields, CanyonCrud, CanyonMapper, Eq, PartialEq)]
+#[canyon_entity(table_name = "event")]
+/// Data model that represents a database entity for the Events held on a venue,
+/// whose rows can be soft deleted
+pub struct Event {
+    #[primary_key]
+    id: i32,
+    name: String,
+    #[foreign_key(table = "venue", column = "id")]
+    venue: i32,
+}
@@ -1,3 +1,13 @@
+pub mod article;
+pub mod bracket;
+pub mod caster;
+pub mod event;
 pub mod league;
 pub mod player;
+pub mod sponsor;
+pub mod standing;
+pub mod stream;
+pub mod team;
+pub mod team_player;
 pub mod tournament;
+pub mod venue;
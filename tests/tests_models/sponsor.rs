@@ -0,0 +1,36 @@
+use canyon_sql::{crud::CanyonHooks, date_time::NaiveDate, macros::*};
+
+#[derive(Debug, Clone, Fields, CanyonCrud, CanyonMapper, Eq, PartialEq)]
+#[canyon_entity(table_name = "sponsor")]
+/// Data model that represents a database entity for the Sponsors of the teams.
+///
+/// It implements the `CanyonHooks`, to test the ones invoked by the CRUD operations
+pub struct Sponsor {
+    #[primary_key]
+    id: i32,
+    slug: String,
+    contract_start: NaiveDate,
+    contract_end: NaiveDate,
+}
+
+/// Normalizes the slugs of the new sponsors, and rejects the updates
+/// that leaves a contract ending before it starts
+impl CanyonHooks for Sponsor {
+    fn before_insert(&mut self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.slug = self.slug.trim().to_lowercase().replace(' ', "-");
+        Ok(())
+    }
+
+    fn before_update(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        if self.contract_end < self.contract_start {
+            Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "A contract can't end before it starts",
+            )
+            .into_inner()
+            .unwrap())
+        } else {
+            Ok(())
+        }
+    }
+}
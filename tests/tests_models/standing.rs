@@ -0,0 +1,23 @@
+use canyon_sql::{date_time::NaiveDateTime, macros::*};
+
+#[derive(Debug, Clone, Fields, CanyonCrud, CanyonMapper, Eq, PartialEq)]
+#[canyon_entity(table_name = "standing")]
+/// Data model that represents a database entity for the Standings of the teams.
+///
+/// It's updated column by column, so it declares the timestamps, the soft delete
+/// and the version columns, that aren't changed by the users
+pub struct Standing {
+    #[primary_key]
+    id: i32,
+    team: i32,
+    wins: i32,
+    losses: i32,
+    #[created_at]
+    created_at: NaiveDateTime,
+    #[updated_at]
+    updated_at: NaiveDateTime,
+    #[soft_delete]
+    deleted_at: Option<NaiveDateTime>,
+    #[version]
+    version: i32,
+}
@@ -0,0 +1,14 @@
+use canyon_sql::{date_time::NaiveDateTime, macros::*};
+
+#[derive(Debug, Clone, Fields, CanyonCrud, CanyonMapper, Eq, PartialEq)]
+#[canyon_entity(table_name = "stream")]
+/// Data model that represents a database entity for the Streams of the matches.
+///
+/// Its `started_at` column only stores whole seconds, so the database rounds the
+/// value sent on the insert, and the stored one is only known after reading back the row
+pub struct Stream {
+    #[primary_key]
+    id: i32,
+    channel: String,
+    started_at: NaiveDateTime,
+}
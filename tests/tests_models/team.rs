@@ -0,0 +1,26 @@
+use crate::tests_models::team_player::TeamPlayer;
+use crate::tests_models::tournament::Tournament;
+use canyon_sql::macros::*;
+
+#[derive(Debug, Clone, Fields, CanyonCrud, CanyonMapper, Eq, PartialEq)]
+#[canyon_entity(table_name = "team")]
+/// Data model that represents a database entity for Teams.
+///
+/// Besides its columns, it holds the relation fields, that are not columns of the
+/// table but the related entities, loaded with the autogenerated `load_*` methods
+pub struct Team {
+    #[primary_key]
+    id: i32,
+    ext_id: i64,
+    slug: String,
+    name: String,
+    code: String,
+    image_url: String,
+    alt_image_url: Option<String>,
+    bg_image_url: Option<String>,
+    home_league: Option<i32>,
+    #[has_many(TeamPlayer)]
+    roster: Vec<TeamPlayer>,
+    #[many_to_many(Tournament, through = "team_tournament")]
+    tournaments: Vec<Tournament>,
+}
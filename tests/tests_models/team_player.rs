@@ -0,0 +1,13 @@
+use crate::tests_models::team::Team;
+use canyon_sql::macros::*;
+
+#[derive(Debug, Clone, Fields, CanyonCrud, CanyonMapper, Eq, PartialEq)]
+#[canyon_entity(table_name = "team_player")]
+pub struct TeamPlayer {
+    #[primary_key(autoincremental = false)]
+    #[foreign_key(table = "team", column = "id")]
+    team: i32,
+    #[primary_key(autoincremental = false)]
+    player: i32,
+    role: String,
+}
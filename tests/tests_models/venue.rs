@@ -0,0 +1,15 @@
+use canyon_sql::{date_time::NaiveDateTime, macros::*};
+
+#[derive(Debug, Clone, Fields, CanyonCrud, CanyonMapper, ForeignKeyable, Eq, PartialEq)]
+#[canyon_entity(table_name = "venue")]
+/// Data model that represents a database entity for the Venues of the events.
+///
+/// Its rows are marked as deleted with the `deleted_at` column
+pub struct Venue {
+    #[primary_key]
+    id: i32,
+    name: String,
+    city: String,
+    #[soft_delete]
+    deleted_at: Option<NaiveDateTime>,
+}